@@ -1,4 +1,5 @@
 {
+  config,
   pkgs,
   inputs,
   settings,
@@ -6,24 +7,71 @@
   ...
 }: let
   oomkillerPkg = pkgs.callPackage inputs.oomkiller-src {};
+  metricsDir = "/var/lib/oomkiller/metrics";
+
+  # Per-host overrides go in `settings.oomkiller`; anything unset keeps the daemon default.
+  oomkillerConfig =
+    lib.recursiveUpdate {
+      threshold_percent = 90.0;
+      # Checks every 0.1s near the threshold, at most 5s apart when idle.
+      poll.max_secs = 5.0;
+      target = "user";
+      dry_run = false;
+      trigger = "either";
+      # Hosts run zram swap; count it (compression-weighted) as headroom.
+      memory.accounting = "effective";
+      # Picked up by node_exporter's textfile collector (below).
+      metrics.textfile = "${metricsDir}/oomkiller.prom";
+      # The service has no XDG_STATE_HOME; keep the history in its StateDirectory.
+      kill_loop.history = "/var/lib/oomkiller/kill-history.json";
+      freeze.state = "/var/lib/oomkiller/frozen.json";
+      # Cancel a running `nix build` / `nixos-rebuild` before closing an app; the
+      # builders themselves run as nixbld users, outside target = "user".
+      nix.enabled = true;
+      snapshot = {
+        enabled = true;
+        dir = "/var/lib/oomkiller/snapshots";
+      };
+      # In the unit's RuntimeDirectory, so it exists before the user logs in.
+      control.socket = "/run/oomkiller/control.sock";
+    }
+    (settings.oomkiller or {});
+
+  configFile = (pkgs.formats.toml {}).generate "oomkiller.toml" oomkillerConfig;
 in {
   environment = {
     systemPackages = with pkgs; [
       oomkillerPkg
     ];
+    # Lets `oomkiller ctl` find the daemon without reading its config.
+    sessionVariables.OOMKILLER_SOCKET = oomkillerConfig.control.socket;
   };
 
+  services.prometheus.exporters.node.extraFlags =
+    lib.mkIf config.services.prometheus.exporters.node.enable
+    ["--collector.textfile.directory=${metricsDir}"];
+
   systemd = {
     services = {
       oomkiller = {
         description = "OOM Killer Daemon - Monitors system memory and kills highest memory-consuming process when threshold exceeded";
         wantedBy = ["multi-user.target"];
         after = ["basic.target"];
+        reloadTriggers = [configFile];
 
         serviceConfig = {
-          Type = "simple";
+          # READY=1 after the first memory read; WATCHDOG=1 after every check
+          # (the poll interval is capped at half of WatchdogSec), so a hung loop is restarted.
+          Type = "notify";
+          WatchdogSec = "30s";
           User = "${settings.username}";
-          ExecStart = "${oomkillerPkg}/bin/oomkiller";
+          ExecStart = "${oomkillerPkg}/bin/oomkiller --config ${configFile}";
+          ExecReload = "${pkgs.coreutils}/bin/kill -HUP $MAINPID";
+          StateDirectory = ["oomkiller" "oomkiller/metrics"];
+          RuntimeDirectory = "oomkiller";
+          # Set before dropping to User=, so the daemon gets them without capabilities.
+          OOMScoreAdjust = -900;
+          LimitMEMLOCK = "infinity";
           Restart = "always";
           RestartSec = "5s";
         };
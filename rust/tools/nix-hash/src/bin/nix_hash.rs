@@ -5,7 +5,9 @@ use std::process::ExitCode;
 
 use clap::Parser;
 
-use nix_hash::{CRATE_VERSION, Encoding, HashAlgo, HashError, run_convert, run_hash_paths};
+use nix_hash::{
+    CRATE_VERSION, Encoding, HashAlgo, HashError, HashOptions, run_convert, run_hash_paths_multi,
+};
 
 #[derive(Debug, Parser)]
 #[command(
@@ -39,10 +41,19 @@ struct Cli {
     #[arg(long)]
     truncate: bool,
 
-    /// Hash algorithm: blake3, md5, sha1, sha256, sha512 (default: md5)
+    /// Show progress on stderr while hashing large --flat inputs (only when stderr is a TTY)
+    #[arg(long)]
+    progress: bool,
+
+    /// Hash algorithm: blake3, md5, sha1, sha256, sha512 (default: md5); a
+    /// comma-separated list hashes each path once per algorithm in one pass
     #[arg(long = "type", value_name = "hashAlgo")]
     hash_type: Option<String>,
 
+    /// Print one JSON object per path with a field per algorithm (not with --to-*)
+    #[arg(long)]
+    json: bool,
+
     /// Convert the base-32 hash representation to hexadecimal
     #[arg(long = "to-base16")]
     to_base16: bool,
@@ -125,15 +136,27 @@ fn main() -> ExitCode {
 }
 
 fn run(cli: Cli) -> Result<Vec<String>, HashError> {
-    let type_hint = match cli.hash_type.as_deref() {
-        None => None,
-        Some(s) => Some(HashAlgo::parse(s).map_err(HashError::msg)?),
+    let algos = match cli.hash_type.as_deref() {
+        None => Vec::new(),
+        Some(s) => HashAlgo::parse_list(s).map_err(HashError::msg)?,
     };
 
     if let Some(to) = convert_target(&cli)? {
+        if cli.json {
+            return Err(HashError::msg("--json cannot be combined with --to-*"));
+        }
         if cli.args.is_empty() {
             return Err(HashError::msg("no hashes specified"));
         }
+        let type_hint = match algos.as_slice() {
+            [] => None,
+            [algo] => Some(*algo),
+            _ => {
+                return Err(HashError::msg(
+                    "--type takes a single hash algorithm when converting",
+                ));
+            }
+        };
         return run_convert(&cli.args, type_hint, to);
     }
 
@@ -142,8 +165,18 @@ fn run(cli: Cli) -> Result<Vec<String>, HashError> {
     }
 
     // Path hashing: stock defaults to md5 when --type is omitted.
-    let algo = type_hint.unwrap_or(HashAlgo::Md5);
-    let encoding = encoding_from_flags(&cli)?;
+    let algos = if algos.is_empty() {
+        vec![HashAlgo::Md5]
+    } else {
+        algos
+    };
+    let opts = HashOptions {
+        flat: cli.flat,
+        truncate: cli.truncate,
+        encoding: encoding_from_flags(&cli)?,
+        progress: cli.progress,
+        json: cli.json,
+    };
     let paths: Vec<PathBuf> = cli.args.iter().map(PathBuf::from).collect();
-    run_hash_paths(&paths, algo, cli.flat, cli.truncate, encoding)
+    run_hash_paths_multi(&paths, &algos, opts)
 }
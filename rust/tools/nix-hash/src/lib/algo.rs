@@ -27,6 +27,20 @@ impl HashAlgo {
         }
     }
 
+    /// Parse `--type`, which may name several algorithms separated by commas
+    /// (`sha256,blake3`). Order is preserved; repeats are rejected.
+    pub fn parse_list(s: &str) -> Result<Vec<Self>, String> {
+        let mut algos = Vec::new();
+        for part in s.split(',') {
+            let algo = Self::parse(part.trim())?;
+            if algos.contains(&algo) {
+                return Err(format!("hash algorithm '{}' given twice", algo.as_str()));
+            }
+            algos.push(algo);
+        }
+        Ok(algos)
+    }
+
     pub fn as_str(self) -> &'static str {
         match self {
             Self::Md5 => "md5",
@@ -55,6 +69,51 @@ impl HashAlgo {
             Self::Blake3 => blake3::hash(data).as_bytes().to_vec(),
         }
     }
+
+    /// Incremental hasher for inputs that are fed in chunks.
+    pub fn hasher(self) -> Hasher {
+        match self {
+            Self::Md5 => Hasher::Md5(Md5::new()),
+            Self::Sha1 => Hasher::Sha1(Sha1::new()),
+            Self::Sha256 => Hasher::Sha256(Sha256::new()),
+            Self::Sha512 => Hasher::Sha512(Sha512::new()),
+            Self::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+}
+
+/// Streaming counterpart of [`HashAlgo::digest`].
+#[derive(Clone)]
+pub enum Hasher {
+    Md5(Md5),
+    Sha1(Sha1),
+    Sha256(Sha256),
+    Sha512(Sha512),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Md5(h) => h.update(data),
+            Self::Sha1(h) => h.update(data),
+            Self::Sha256(h) => h.update(data),
+            Self::Sha512(h) => h.update(data),
+            Self::Blake3(h) => {
+                h.update(data);
+            }
+        }
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        match self {
+            Self::Md5(h) => h.finalize().to_vec(),
+            Self::Sha1(h) => h.finalize().to_vec(),
+            Self::Sha256(h) => h.finalize().to_vec(),
+            Self::Sha512(h) => h.finalize().to_vec(),
+            Self::Blake3(h) => h.finalize().as_bytes().to_vec(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -76,4 +135,36 @@ mod tests {
             "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
         );
     }
+
+    #[test]
+    fn parse_list_keeps_order() {
+        assert_eq!(
+            HashAlgo::parse_list("sha256, blake3,sha512").unwrap(),
+            vec![HashAlgo::Sha256, HashAlgo::Blake3, HashAlgo::Sha512]
+        );
+        assert_eq!(HashAlgo::parse_list("md5").unwrap(), vec![HashAlgo::Md5]);
+    }
+
+    #[test]
+    fn parse_list_rejects_repeats_and_blanks() {
+        assert!(HashAlgo::parse_list("sha256,sha256").is_err());
+        assert!(HashAlgo::parse_list("sha256,").is_err());
+    }
+
+    #[test]
+    fn chunked_hasher_matches_one_shot_digest() {
+        for algo in [
+            HashAlgo::Md5,
+            HashAlgo::Sha1,
+            HashAlgo::Sha256,
+            HashAlgo::Sha512,
+            HashAlgo::Blake3,
+        ] {
+            let mut h = algo.hasher();
+            h.update(b"hel");
+            h.update(b"");
+            h.update(b"lo");
+            assert_eq!(h.finalize(), algo.digest(b"hello"), "{}", algo.as_str());
+        }
+    }
 }
@@ -1,21 +1,109 @@
 //! Path hashing: flat file bytes or recursive NAR.
 
-use std::fs;
+//! Flat inputs are streamed through the hasher in fixed-size chunks, so
+//! hashing a multi-GB image costs one read buffer rather than the whole file.
+//! Several algorithms can be computed from a single read of the input.
+
+use std::fs::File;
+use std::io::{self, Read};
 use std::path::Path;
 
 use nixfetch::nar_bytes;
 
 use crate::algo::HashAlgo;
 use crate::error::HashError;
+use crate::progress::Progress;
+
+/// Read buffer size for flat hashing.
+const CHUNK: usize = 1024 * 1024;
 
 pub fn hash_path(path: &Path, algo: HashAlgo, flat: bool) -> Result<Vec<u8>, HashError> {
-    let bytes = if flat {
-        fs::read(path).map_err(|e| HashError::Io {
-            path: path.display().to_string(),
-            message: e.to_string(),
-        })?
+    let mut digests = hash_path_multi(path, &[algo], flat, false)?;
+    Ok(digests.remove(0))
+}
+
+/// Hash `path` once per algorithm in `algos` while reading (or NAR-serialising)
+/// it only once, optionally drawing a stderr progress line for large flat
+/// inputs. Digests come back in the order of `algos`.
+pub fn hash_path_multi(
+    path: &Path,
+    algos: &[HashAlgo],
+    flat: bool,
+    progress: bool,
+) -> Result<Vec<Vec<u8>>, HashError> {
+    if flat {
+        return hash_file(path, algos, progress);
+    }
+    let bytes = nar_bytes(path).map_err(|e| HashError::Nar(e.to_string()))?;
+    Ok(algos.iter().map(|a| a.digest(&bytes)).collect())
+}
+
+fn hash_file(path: &Path, algos: &[HashAlgo], progress: bool) -> Result<Vec<Vec<u8>>, HashError> {
+    let io_err = |e: io::Error| HashError::Io {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    };
+    let mut file = File::open(path).map_err(io_err)?;
+    let mut bar = if progress {
+        let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Progress::for_stderr(path.display().to_string(), total)
     } else {
-        nar_bytes(path).map_err(|e| HashError::Nar(e.to_string()))?
+        None
     };
-    Ok(algo.digest(&bytes))
+    hash_reader(&mut file, algos, bar.as_mut()).map_err(io_err)
+}
+
+/// Hash everything `reader` yields with each of `algos`, reporting each chunk
+/// to `progress`.
+pub fn hash_reader(
+    reader: &mut impl Read,
+    algos: &[HashAlgo],
+    mut progress: Option<&mut Progress>,
+) -> io::Result<Vec<Vec<u8>>> {
+    let mut hashers: Vec<_> = algos.iter().map(|a| a.hasher()).collect();
+    let mut buf = vec![0u8; CHUNK];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        for h in &mut hashers {
+            h.update(&buf[..n]);
+        }
+        if let Some(p) = progress.as_deref_mut() {
+            p.advance(n as u64);
+        }
+    }
+    if let Some(p) = progress {
+        p.finish();
+    }
+    Ok(hashers.into_iter().map(|h| h.finalize()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_reader_spans_multiple_chunks() {
+        let data: Vec<u8> = (0..(CHUNK * 2 + 17)).map(|i| i as u8).collect();
+        let got = hash_reader(&mut data.as_slice(), &[HashAlgo::Sha256], None).unwrap();
+        assert_eq!(got, vec![HashAlgo::Sha256.digest(&data)]);
+    }
+
+    #[test]
+    fn hash_reader_fans_out_to_every_algo() {
+        let algos = [HashAlgo::Sha256, HashAlgo::Blake3, HashAlgo::Md5];
+        let got = hash_reader(&mut &b"hello"[..], &algos, None).unwrap();
+        let want: Vec<_> = algos.iter().map(|a| a.digest(b"hello")).collect();
+        assert_eq!(got, want);
+    }
+
+    #[test]
+    fn flat_missing_file_reports_path() {
+        let err = hash_path(Path::new("/nonexistent/nix-hash-f"), HashAlgo::Md5, true).unwrap_err();
+        assert!(matches!(err, HashError::Io { ref path, .. } if path == "/nonexistent/nix-hash-f"));
+    }
 }
@@ -7,6 +7,7 @@ pub mod convert;
 pub mod encode;
 pub mod error;
 pub mod hash_path;
+pub mod progress;
 pub mod run;
 
 #[cfg(test)]
@@ -17,7 +18,7 @@ mod tools_compat;
 pub use algo::HashAlgo;
 pub use encode::Encoding;
 pub use error::HashError;
-pub use run::{run_convert, run_hash_paths};
+pub use run::{HashOptions, run_convert, run_hash_paths, run_hash_paths_multi};
 
 /// Crate identity for smoke tests and `--version` plumbing.
 pub const CRATE_NAME: &str = env!("CARGO_PKG_NAME");
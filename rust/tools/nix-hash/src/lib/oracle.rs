@@ -132,3 +132,24 @@ fn embedded_flat_sha256_hello() {
         "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
     );
 }
+
+#[test]
+fn embedded_multi_algo_matches_separate_runs() {
+    use crate::{HashOptions, run_hash_paths_multi};
+    let path = temp_hello();
+    let algos = [HashAlgo::Sha256, HashAlgo::Sha512, HashAlgo::Blake3];
+    let opts = HashOptions {
+        encoding: Encoding::Base32,
+        ..HashOptions::default()
+    };
+    let got = run_hash_paths_multi(&[path.as_path()], &algos, opts).unwrap();
+    let want: Vec<String> = algos
+        .iter()
+        .map(|&a| {
+            run_hash_paths(&[path.as_path()], a, false, false, Encoding::Base32)
+                .unwrap()
+                .remove(0)
+        })
+        .collect();
+    assert_eq!(got, want);
+}
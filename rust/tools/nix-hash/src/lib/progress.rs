@@ -0,0 +1,143 @@
+//! Optional stderr progress line for long flat hashes.
+
+//! Drawn only when stderr is a terminal, so piped / captured runs stay
+//! byte-identical to stock `nix-hash` on both streams.
+
+use std::io::{IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+/// Inputs smaller than this finish too quickly for a progress line to help.
+pub const PROGRESS_MIN_BYTES: u64 = 64 * 1024 * 1024;
+
+const REDRAW_EVERY: Duration = Duration::from_millis(200);
+
+pub struct Progress {
+    label: String,
+    total: u64,
+    done: u64,
+    started: Instant,
+    last_draw: Option<Instant>,
+}
+
+impl Progress {
+    /// Progress for a `total`-byte input, or `None` when stderr is not a TTY
+    /// or the input is below [`PROGRESS_MIN_BYTES`].
+    pub fn for_stderr(label: impl Into<String>, total: u64) -> Option<Self> {
+        if total < PROGRESS_MIN_BYTES || !std::io::stderr().is_terminal() {
+            return None;
+        }
+        Some(Self {
+            label: label.into(),
+            total,
+            done: 0,
+            started: Instant::now(),
+            last_draw: None,
+        })
+    }
+
+    pub fn advance(&mut self, bytes: u64) {
+        self.done = self.done.saturating_add(bytes);
+        let now = Instant::now();
+        if self
+            .last_draw
+            .is_some_and(|t| now.duration_since(t) < REDRAW_EVERY)
+        {
+            return;
+        }
+        self.last_draw = Some(now);
+        let line = render_line(
+            &self.label,
+            self.done,
+            self.total,
+            now.duration_since(self.started),
+        );
+        let mut err = std::io::stderr().lock();
+        let _ = write!(err, "\r\x1b[2K{line}");
+        let _ = err.flush();
+    }
+
+    /// Erase the progress line so the terminal is left as stock would leave it.
+    pub fn finish(&mut self) {
+        if self.last_draw.is_some() {
+            let mut err = std::io::stderr().lock();
+            let _ = write!(err, "\r\x1b[2K");
+            let _ = err.flush();
+        }
+    }
+}
+
+/// `label: done / total (pct%) rate/s ETA m:ss`
+pub fn render_line(label: &str, done: u64, total: u64, elapsed: Duration) -> String {
+    let pct = (done.min(total) * 100).checked_div(total).unwrap_or(100);
+    let secs = elapsed.as_secs_f64();
+    let rate = if secs > 0.0 { done as f64 / secs } else { 0.0 };
+    let eta = if rate > 0.0 {
+        let left = total.saturating_sub(done) as f64 / rate;
+        format_eta(left.round() as u64)
+    } else {
+        "--:--".to_string()
+    };
+    format!(
+        "{label}: {} / {} ({pct}%) {}/s ETA {eta}",
+        format_bytes(done),
+        format_bytes(total),
+        format_bytes(rate as u64),
+    )
+}
+
+fn format_eta(secs: u64) -> String {
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m}:{s:02}")
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const KIB: f64 = 1024.0;
+    const MIB: f64 = KIB * 1024.0;
+    const GIB: f64 = MIB * 1024.0;
+    let b = bytes as f64;
+    if b >= GIB {
+        format!("{:.2} GiB", b / GIB)
+    } else if b >= MIB {
+        format!("{:.1} MiB", b / MIB)
+    } else if b >= KIB {
+        format!("{:.1} KiB", b / KIB)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_inputs_get_no_progress() {
+        assert!(Progress::for_stderr("f", PROGRESS_MIN_BYTES - 1).is_none());
+    }
+
+    #[test]
+    fn render_line_shows_rate_and_eta() {
+        let gib = 1024 * 1024 * 1024;
+        let line = render_line("disk.img", gib, 4 * gib, Duration::from_secs(2));
+        assert_eq!(
+            line,
+            "disk.img: 1.00 GiB / 4.00 GiB (25%) 512.0 MiB/s ETA 0:06"
+        );
+    }
+
+    #[test]
+    fn render_line_before_any_time_elapsed() {
+        let line = render_line("x", 0, 100, Duration::ZERO);
+        assert_eq!(line, "x: 0 B / 100 B (0%) 0 B/s ETA --:--");
+    }
+
+    #[test]
+    fn eta_rolls_over_to_hours() {
+        assert_eq!(format_eta(59), "0:59");
+        assert_eq!(format_eta(3 * 3600 + 62), "3:01:02");
+    }
+}
@@ -6,7 +6,19 @@ use crate::algo::HashAlgo;
 use crate::convert::convert_hash;
 use crate::encode::{Encoding, format_digest, maybe_truncate};
 use crate::error::HashError;
-use crate::hash_path::hash_path;
+use crate::hash_path::hash_path_multi;
+
+/// Output and input knobs for path hashing beyond the algorithm list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HashOptions {
+    pub flat: bool,
+    pub truncate: bool,
+    pub encoding: Encoding,
+    /// Draw a stderr progress line for large flat inputs.
+    pub progress: bool,
+    /// One JSON object per path instead of one line per digest.
+    pub json: bool,
+}
 
 pub fn run_hash_paths(
     paths: &[impl AsRef<Path>],
@@ -15,15 +27,69 @@ pub fn run_hash_paths(
     truncate: bool,
     encoding: Encoding,
 ) -> Result<Vec<String>, HashError> {
-    let mut lines = Vec::with_capacity(paths.len());
+    let opts = HashOptions {
+        flat,
+        truncate,
+        encoding,
+        ..HashOptions::default()
+    };
+    run_hash_paths_multi(paths, &[algo], opts)
+}
+
+/// Hash every path with each of `algos` in a single pass over the input.
+///
+/// Plain output is one line per path per algorithm, in `algos` order, so a
+/// single algorithm prints exactly what stock `nix-hash` prints. With
+/// `opts.json` each path becomes `{"path":…,"<algo>":…}` on one line.
+pub fn run_hash_paths_multi(
+    paths: &[impl AsRef<Path>],
+    algos: &[HashAlgo],
+    opts: HashOptions,
+) -> Result<Vec<String>, HashError> {
+    let mut lines = Vec::with_capacity(paths.len() * algos.len());
     for p in paths {
-        let dig = hash_path(p.as_ref(), algo, flat)?;
-        let dig = maybe_truncate(&dig, truncate);
-        lines.push(format_digest(algo, &dig, encoding));
+        let p = p.as_ref();
+        let digests = hash_path_multi(p, algos, opts.flat, opts.progress)?;
+        let encoded = algos.iter().zip(&digests).map(|(&algo, dig)| {
+            let dig = maybe_truncate(dig, opts.truncate);
+            (algo, format_digest(algo, &dig, opts.encoding))
+        });
+        if opts.json {
+            lines.push(json_line(&p.display().to_string(), encoded));
+        } else {
+            lines.extend(encoded.map(|(_, s)| s));
+        }
     }
     Ok(lines)
 }
 
+fn json_line(path: &str, digests: impl Iterator<Item = (HashAlgo, String)>) -> String {
+    let mut out = format!("{{\"path\":{}", json_string(path));
+    for (algo, s) in digests {
+        out.push_str(&format!(",\"{}\":{}", algo.as_str(), json_string(&s)));
+    }
+    out.push('}');
+    out
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => out.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
 pub fn run_convert(
     hashes: &[String],
     type_hint: Option<HashAlgo>,
@@ -35,3 +101,26 @@ pub fn run_convert(
     }
     Ok(lines)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_string_escapes_quotes_and_controls() {
+        assert_eq!(json_string("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+    }
+
+    #[test]
+    fn json_line_lists_algos_in_order() {
+        let line = json_line(
+            "/tmp/x",
+            [
+                (HashAlgo::Sha256, "aa".to_string()),
+                (HashAlgo::Blake3, "bb".to_string()),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(line, r#"{"path":"/tmp/x","sha256":"aa","blake3":"bb"}"#);
+    }
+}
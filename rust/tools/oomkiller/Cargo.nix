@@ -1,2172 +0,0 @@
-# This file was @generated by crate2nix 0.14.1 with the command:
-#   "generate"
-# See https://github.com/kolloch/crate2nix for more info.
-{
-  nixpkgs ? <nixpkgs>,
-  pkgs ? import nixpkgs {config = {};},
-  lib ? pkgs.lib,
-  stdenv ? pkgs.stdenv,
-  buildRustCrateForPkgs ? pkgs: pkgs.buildRustCrate,
-  # This is used as the `crateOverrides` argument for `buildRustCrate`.
-  defaultCrateOverrides ? pkgs.defaultCrateOverrides,
-  # The features to enable for the root_crate or the workspace_members.
-  rootFeatures ? ["default"],
-  # If true, throw errors instead of issueing deprecation warnings.
-  strictDeprecation ? false,
-  # Elements to add to the `-C target-feature=` argument passed to `rustc`
-  # (separated by `,`, prefixed with `+`).
-  # Used for conditional compilation based on CPU feature detection.
-  targetFeatures ? [],
-  # Whether to perform release builds: longer compile times, faster binaries.
-  release ? true,
-  # Additional crate2nix configuration if it exists.
-  crateConfig ?
-    if builtins.pathExists ./crate-config.nix
-    then pkgs.callPackage ./crate-config.nix {}
-    else {},
-}: rec {
-  #
-  # "public" attributes that we attempt to keep stable with new versions of crate2nix.
-  #
-
-  rootCrate = rec {
-    packageId = "oomkiller";
-
-    # Use this attribute to refer to the derivation building your root crate package.
-    # You can override the features with rootCrate.build.override { features = [ "default" "feature1" ... ]; }.
-    build = internal.buildRustCrateWithFeatures {
-      inherit packageId;
-    };
-
-    # Debug support which might change between releases.
-    # File a bug if you depend on any for non-debug work!
-    debug = internal.debugCrate {inherit packageId;};
-  };
-  # Refer your crate build derivation by name here.
-  # You can override the features with
-  # workspaceMembers."${crateName}".build.override { features = [ "default" "feature1" ... ]; }.
-  workspaceMembers = {
-    "oomkiller" = rec {
-      packageId = "oomkiller";
-      build = internal.buildRustCrateWithFeatures {
-        packageId = "oomkiller";
-      };
-
-      # Debug support which might change between releases.
-      # File a bug if you depend on any for non-debug work!
-      debug = internal.debugCrate {inherit packageId;};
-    };
-  };
-
-  # A derivation that joins the outputs of all workspace members together.
-  allWorkspaceMembers = pkgs.symlinkJoin {
-    name = "all-workspace-members";
-    paths = let
-      members = builtins.attrValues workspaceMembers;
-    in
-      builtins.map (m: m.build) members;
-  };
-
-  #
-  # "internal" ("private") attributes that may change in every new version of crate2nix.
-  #
-
-  internal = rec {
-    # Build and dependency information for crates.
-    # Many of the fields are passed one-to-one to buildRustCrate.
-    #
-    # Noteworthy:
-    # * `dependencies`/`buildDependencies`: similar to the corresponding fields for buildRustCrate.
-    #   but with additional information which is used during dependency/feature resolution.
-    # * `resolvedDependencies`: the selected default features reported by cargo - only included for debugging.
-    # * `devDependencies` as of now not used by `buildRustCrate` but used to
-    #   inject test dependencies into the build
-
-    crates = {
-      "core-foundation-sys" = rec {
-        crateName = "core-foundation-sys";
-        version = "0.8.7";
-        edition = "2018";
-        sha256 = "12w8j73lazxmr1z0h98hf3z623kl8ms7g07jch7n4p8f9nwlhdkp";
-        libName = "core_foundation_sys";
-        authors = [
-          "The Servo Project Developers"
-        ];
-        features = {
-          "default" = ["link"];
-        };
-        resolvedDefaultFeatures = ["default" "link"];
-      };
-      "crossbeam-deque" = rec {
-        crateName = "crossbeam-deque";
-        version = "0.8.6";
-        edition = "2021";
-        sha256 = "0l9f1saqp1gn5qy0rxvkmz4m6n7fc0b3dbm6q1r5pmgpnyvi3lcx";
-        libName = "crossbeam_deque";
-        dependencies = [
-          {
-            name = "crossbeam-epoch";
-            packageId = "crossbeam-epoch";
-            usesDefaultFeatures = false;
-          }
-          {
-            name = "crossbeam-utils";
-            packageId = "crossbeam-utils";
-            usesDefaultFeatures = false;
-          }
-        ];
-        features = {
-          "default" = ["std"];
-          "std" = ["crossbeam-epoch/std" "crossbeam-utils/std"];
-        };
-        resolvedDefaultFeatures = ["default" "std"];
-      };
-      "crossbeam-epoch" = rec {
-        crateName = "crossbeam-epoch";
-        version = "0.9.18";
-        edition = "2021";
-        sha256 = "03j2np8llwf376m3fxqx859mgp9f83hj1w34153c7a9c7i5ar0jv";
-        libName = "crossbeam_epoch";
-        dependencies = [
-          {
-            name = "crossbeam-utils";
-            packageId = "crossbeam-utils";
-            usesDefaultFeatures = false;
-          }
-        ];
-        features = {
-          "default" = ["std"];
-          "loom" = ["loom-crate" "crossbeam-utils/loom"];
-          "loom-crate" = ["dep:loom-crate"];
-          "nightly" = ["crossbeam-utils/nightly"];
-          "std" = ["alloc" "crossbeam-utils/std"];
-        };
-        resolvedDefaultFeatures = ["alloc" "std"];
-      };
-      "crossbeam-utils" = rec {
-        crateName = "crossbeam-utils";
-        version = "0.8.21";
-        edition = "2021";
-        sha256 = "0a3aa2bmc8q35fb67432w16wvi54sfmb69rk9h5bhd18vw0c99fh";
-        libName = "crossbeam_utils";
-        features = {
-          "default" = ["std"];
-          "loom" = ["dep:loom"];
-        };
-        resolvedDefaultFeatures = ["default" "std"];
-      };
-      "either" = rec {
-        crateName = "either";
-        version = "1.15.0";
-        edition = "2021";
-        sha256 = "069p1fknsmzn9llaizh77kip0pqmcwpdsykv2x30xpjyija5gis8";
-        authors = [
-          "bluss"
-        ];
-        features = {
-          "default" = ["std"];
-          "serde" = ["dep:serde"];
-          "use_std" = ["std"];
-        };
-      };
-      "libc" = rec {
-        crateName = "libc";
-        version = "0.2.180";
-        edition = "2021";
-        sha256 = "1z2n7hl10fnk1xnv19ahhqxwnb4qi9aclnl6gigim2aaahw5mhxw";
-        authors = [
-          "The Rust Project Developers"
-        ];
-        features = {
-          "default" = ["std"];
-          "rustc-dep-of-std" = ["align" "rustc-std-workspace-core"];
-          "rustc-std-workspace-core" = ["dep:rustc-std-workspace-core"];
-          "use_std" = ["std"];
-        };
-        resolvedDefaultFeatures = ["default" "std"];
-      };
-      "memchr" = rec {
-        crateName = "memchr";
-        version = "2.7.6";
-        edition = "2021";
-        sha256 = "0wy29kf6pb4fbhfksjbs05jy2f32r2f3r1ga6qkmpz31k79h0azm";
-        authors = [
-          "Andrew Gallant <jamslam@gmail.com>"
-          "bluss"
-        ];
-        features = {
-          "core" = ["dep:core"];
-          "default" = ["std"];
-          "logging" = ["dep:log"];
-          "rustc-dep-of-std" = ["core"];
-          "std" = ["alloc"];
-          "use_std" = ["std"];
-        };
-        resolvedDefaultFeatures = ["alloc" "default" "std"];
-      };
-      "ntapi" = rec {
-        crateName = "ntapi";
-        version = "0.4.2";
-        edition = "2018";
-        sha256 = "10ghcc1kmj5ygy4ls81as6s5akd1wflwcc0b1k3nf8ql46g223y7";
-        authors = [
-          "MSxDOS <melcodos@gmail.com>"
-        ];
-        dependencies = [
-          {
-            name = "winapi";
-            packageId = "winapi";
-            features = ["cfg" "evntrace" "in6addr" "inaddr" "minwinbase" "ntsecapi" "windef" "winioctl"];
-          }
-        ];
-        features = {
-          "default" = ["user"];
-          "impl-default" = ["winapi/impl-default"];
-        };
-        resolvedDefaultFeatures = ["default" "user"];
-      };
-      "oomkiller" = rec {
-        crateName = "oomkiller";
-        version = "0.1.0";
-        edition = "2024";
-        crateBin = [
-          {
-            name = "oomkiller";
-            path = "src/main.rs";
-            requiredFeatures = [];
-          }
-        ];
-        src = lib.cleanSourceWith {
-          filter = sourceFilter;
-          src = ./.;
-        };
-        libPath = "src/lib/mod.rs";
-        authors = [
-          "Industrial <tom.wieland@gmail.com>"
-        ];
-        dependencies = [
-          {
-            name = "sysinfo";
-            packageId = "sysinfo";
-          }
-        ];
-      };
-      "proc-macro2" = rec {
-        crateName = "proc-macro2";
-        version = "1.0.106";
-        edition = "2021";
-        sha256 = "0d09nczyaj67x4ihqr5p7gxbkz38gxhk4asc0k8q23g9n85hzl4g";
-        libName = "proc_macro2";
-        authors = [
-          "David Tolnay <dtolnay@gmail.com>"
-          "Alex Crichton <alex@alexcrichton.com>"
-        ];
-        dependencies = [
-          {
-            name = "unicode-ident";
-            packageId = "unicode-ident";
-          }
-        ];
-        features = {
-          "default" = ["proc-macro"];
-        };
-        resolvedDefaultFeatures = ["default" "proc-macro"];
-      };
-      "quote" = rec {
-        crateName = "quote";
-        version = "1.0.44";
-        edition = "2021";
-        sha256 = "1r7c7hxl66vz3q9qizgjhy77pdrrypqgk4ghc7260xvvfb7ypci1";
-        authors = [
-          "David Tolnay <dtolnay@gmail.com>"
-        ];
-        dependencies = [
-          {
-            name = "proc-macro2";
-            packageId = "proc-macro2";
-            usesDefaultFeatures = false;
-          }
-        ];
-        features = {
-          "default" = ["proc-macro"];
-          "proc-macro" = ["proc-macro2/proc-macro"];
-        };
-        resolvedDefaultFeatures = ["default" "proc-macro"];
-      };
-      "rayon" = rec {
-        crateName = "rayon";
-        version = "1.11.0";
-        edition = "2021";
-        sha256 = "13x5fxb7rn4j2yw0cr26n7782jkc7rjzmdkg42qxk3xz0p8033rn";
-        dependencies = [
-          {
-            name = "either";
-            packageId = "either";
-            usesDefaultFeatures = false;
-          }
-          {
-            name = "rayon-core";
-            packageId = "rayon-core";
-          }
-        ];
-        features = {
-          "web_spin_lock" = ["dep:wasm_sync" "rayon-core/web_spin_lock"];
-        };
-      };
-      "rayon-core" = rec {
-        crateName = "rayon-core";
-        version = "1.13.0";
-        edition = "2021";
-        links = "rayon-core";
-        sha256 = "14dbr0sq83a6lf1rfjq5xdpk5r6zgzvmzs5j6110vlv2007qpq92";
-        libName = "rayon_core";
-        dependencies = [
-          {
-            name = "crossbeam-deque";
-            packageId = "crossbeam-deque";
-          }
-          {
-            name = "crossbeam-utils";
-            packageId = "crossbeam-utils";
-          }
-        ];
-        features = {
-          "web_spin_lock" = ["dep:wasm_sync"];
-        };
-      };
-      "syn" = rec {
-        crateName = "syn";
-        version = "2.0.114";
-        edition = "2021";
-        sha256 = "0akw62dizhyrkf3ym1jsys0gy1nphzgv0y8qkgpi6c1s4vghglfl";
-        authors = [
-          "David Tolnay <dtolnay@gmail.com>"
-        ];
-        dependencies = [
-          {
-            name = "proc-macro2";
-            packageId = "proc-macro2";
-            usesDefaultFeatures = false;
-          }
-          {
-            name = "quote";
-            packageId = "quote";
-            optional = true;
-            usesDefaultFeatures = false;
-          }
-          {
-            name = "unicode-ident";
-            packageId = "unicode-ident";
-          }
-        ];
-        features = {
-          "default" = ["derive" "parsing" "printing" "clone-impls" "proc-macro"];
-          "printing" = ["dep:quote"];
-          "proc-macro" = ["proc-macro2/proc-macro" "quote?/proc-macro"];
-          "test" = ["syn-test-suite/all-features"];
-        };
-        resolvedDefaultFeatures = ["clone-impls" "derive" "full" "parsing" "printing" "proc-macro"];
-      };
-      "sysinfo" = rec {
-        crateName = "sysinfo";
-        version = "0.31.4";
-        edition = "2021";
-        sha256 = "1gm1d4pxwnx4gmh6kwawchv8v8djb7y0a3qvbsq09cwrhx7vwp9m";
-        authors = [
-          "Guillaume Gomez <guillaume1.gomez@gmail.com>"
-        ];
-        dependencies = [
-          {
-            name = "core-foundation-sys";
-            packageId = "core-foundation-sys";
-            target = {
-              target,
-              features,
-            }: (("macos" == target."os" or null) || ("ios" == target."os" or null));
-          }
-          {
-            name = "libc";
-            packageId = "libc";
-            target = {
-              target,
-              features,
-            }: (!(("unknown" == target."os" or null) || ("wasm32" == target."arch" or null)));
-          }
-          {
-            name = "memchr";
-            packageId = "memchr";
-            optional = true;
-          }
-          {
-            name = "ntapi";
-            packageId = "ntapi";
-            optional = true;
-            target = {
-              target,
-              features,
-            }: (target."windows" or false);
-          }
-          {
-            name = "rayon";
-            packageId = "rayon";
-            optional = true;
-          }
-          {
-            name = "windows";
-            packageId = "windows";
-            optional = true;
-            target = {
-              target,
-              features,
-            }: (target."windows" or false);
-          }
-        ];
-        features = {
-          "apple-app-store" = ["apple-sandbox"];
-          "c-interface" = ["default"];
-          "component" = ["windows/Win32_Foundation" "windows/Win32_Security" "windows/Win32_System_Com" "windows/Win32_System_Rpc" "windows/Win32_System_Variant" "windows/Win32_System_Wmi"];
-          "debug" = ["libc/extra_traits"];
-          "default" = ["component" "disk" "network" "system" "user" "multithread"];
-          "disk" = ["windows/Win32_Foundation" "windows/Win32_Storage_FileSystem" "windows/Win32_Security" "windows/Win32_System_IO" "windows/Win32_System_Ioctl" "windows/Win32_System_WindowsProgramming"];
-          "multithread" = ["dep:rayon"];
-          "network" = ["windows/Win32_Foundation" "windows/Win32_NetworkManagement_IpHelper" "windows/Win32_NetworkManagement_Ndis" "windows/Win32_Networking_WinSock"];
-          "serde" = ["dep:serde"];
-          "system" = ["windows/Win32_Foundation" "windows/Wdk_System_SystemInformation" "windows/Wdk_System_SystemServices" "windows/Wdk_System_Threading" "windows/Win32_Security_Authorization" "windows/Win32_System_Diagnostics_Debug" "windows/Win32_System_Kernel" "windows/Win32_System_Memory" "windows/Win32_System_Performance" "windows/Win32_System_Power" "windows/Win32_System_ProcessStatus" "windows/Win32_System_Registry" "windows/Win32_System_RemoteDesktop" "windows/Win32_System_SystemInformation" "windows/Win32_System_SystemServices" "windows/Win32_System_Threading" "windows/Win32_UI_Shell" "dep:ntapi" "dep:memchr"];
-          "user" = ["windows/Win32_Foundation" "windows/Win32_NetworkManagement_NetManagement" "windows/Win32_Security" "windows/Win32_Security_Authentication_Identity" "windows/Win32_Security_Authorization"];
-          "windows" = ["dep:windows"];
-        };
-        resolvedDefaultFeatures = ["component" "default" "disk" "multithread" "network" "system" "user" "windows"];
-      };
-      "unicode-ident" = rec {
-        crateName = "unicode-ident";
-        version = "1.0.22";
-        edition = "2018";
-        sha256 = "1x8xrz17vqi6qmkkcqr8cyf0an76ig7390j9cnqnk47zyv2gf4lk";
-        libName = "unicode_ident";
-        authors = [
-          "David Tolnay <dtolnay@gmail.com>"
-        ];
-      };
-      "winapi" = rec {
-        crateName = "winapi";
-        version = "0.3.9";
-        edition = "2015";
-        sha256 = "06gl025x418lchw1wxj64ycr7gha83m44cjr5sarhynd9xkrm0sw";
-        authors = [
-          "Peter Atashian <retep998@gmail.com>"
-        ];
-        dependencies = [
-          {
-            name = "winapi-i686-pc-windows-gnu";
-            packageId = "winapi-i686-pc-windows-gnu";
-            target = {
-              target,
-              features,
-            }: (stdenv.hostPlatform.rust.rustcTarget == "i686-pc-windows-gnu");
-          }
-          {
-            name = "winapi-x86_64-pc-windows-gnu";
-            packageId = "winapi-x86_64-pc-windows-gnu";
-            target = {
-              target,
-              features,
-            }: (stdenv.hostPlatform.rust.rustcTarget == "x86_64-pc-windows-gnu");
-          }
-        ];
-        features = {
-          "debug" = ["impl-debug"];
-        };
-        resolvedDefaultFeatures = ["cfg" "evntrace" "in6addr" "inaddr" "minwinbase" "ntsecapi" "windef" "winioctl"];
-      };
-      "winapi-i686-pc-windows-gnu" = rec {
-        crateName = "winapi-i686-pc-windows-gnu";
-        version = "0.4.0";
-        edition = "2015";
-        sha256 = "1dmpa6mvcvzz16zg6d5vrfy4bxgg541wxrcip7cnshi06v38ffxc";
-        libName = "winapi_i686_pc_windows_gnu";
-        authors = [
-          "Peter Atashian <retep998@gmail.com>"
-        ];
-      };
-      "winapi-x86_64-pc-windows-gnu" = rec {
-        crateName = "winapi-x86_64-pc-windows-gnu";
-        version = "0.4.0";
-        edition = "2015";
-        sha256 = "0gqq64czqb64kskjryj8isp62m2sgvx25yyj3kpc2myh85w24bki";
-        libName = "winapi_x86_64_pc_windows_gnu";
-        authors = [
-          "Peter Atashian <retep998@gmail.com>"
-        ];
-      };
-      "windows" = rec {
-        crateName = "windows";
-        version = "0.57.0";
-        edition = "2021";
-        sha256 = "0hqid10bqvxa3pbpgvrh2cilf950lxsd9zqfv3rldc73v2s2qd0j";
-        authors = [
-          "Microsoft"
-        ];
-        dependencies = [
-          {
-            name = "windows-core";
-            packageId = "windows-core";
-          }
-          {
-            name = "windows-targets";
-            packageId = "windows-targets";
-          }
-        ];
-        features = {
-          "AI" = ["Foundation"];
-          "AI_MachineLearning" = ["AI"];
-          "ApplicationModel" = ["Foundation"];
-          "ApplicationModel_Activation" = ["ApplicationModel"];
-          "ApplicationModel_AppExtensions" = ["ApplicationModel"];
-          "ApplicationModel_AppService" = ["ApplicationModel"];
-          "ApplicationModel_Appointments" = ["ApplicationModel"];
-          "ApplicationModel_Appointments_AppointmentsProvider" = ["ApplicationModel_Appointments"];
-          "ApplicationModel_Appointments_DataProvider" = ["ApplicationModel_Appointments"];
-          "ApplicationModel_Background" = ["ApplicationModel"];
-          "ApplicationModel_Calls" = ["ApplicationModel"];
-          "ApplicationModel_Calls_Background" = ["ApplicationModel_Calls"];
-          "ApplicationModel_Calls_Provider" = ["ApplicationModel_Calls"];
-          "ApplicationModel_Chat" = ["ApplicationModel"];
-          "ApplicationModel_CommunicationBlocking" = ["ApplicationModel"];
-          "ApplicationModel_Contacts" = ["ApplicationModel"];
-          "ApplicationModel_Contacts_DataProvider" = ["ApplicationModel_Contacts"];
-          "ApplicationModel_Contacts_Provider" = ["ApplicationModel_Contacts"];
-          "ApplicationModel_ConversationalAgent" = ["ApplicationModel"];
-          "ApplicationModel_Core" = ["ApplicationModel"];
-          "ApplicationModel_DataTransfer" = ["ApplicationModel"];
-          "ApplicationModel_DataTransfer_DragDrop" = ["ApplicationModel_DataTransfer"];
-          "ApplicationModel_DataTransfer_DragDrop_Core" = ["ApplicationModel_DataTransfer_DragDrop"];
-          "ApplicationModel_DataTransfer_ShareTarget" = ["ApplicationModel_DataTransfer"];
-          "ApplicationModel_Email" = ["ApplicationModel"];
-          "ApplicationModel_Email_DataProvider" = ["ApplicationModel_Email"];
-          "ApplicationModel_ExtendedExecution" = ["ApplicationModel"];
-          "ApplicationModel_ExtendedExecution_Foreground" = ["ApplicationModel_ExtendedExecution"];
-          "ApplicationModel_Holographic" = ["ApplicationModel"];
-          "ApplicationModel_LockScreen" = ["ApplicationModel"];
-          "ApplicationModel_Payments" = ["ApplicationModel"];
-          "ApplicationModel_Payments_Provider" = ["ApplicationModel_Payments"];
-          "ApplicationModel_Preview" = ["ApplicationModel"];
-          "ApplicationModel_Preview_Holographic" = ["ApplicationModel_Preview"];
-          "ApplicationModel_Preview_InkWorkspace" = ["ApplicationModel_Preview"];
-          "ApplicationModel_Preview_Notes" = ["ApplicationModel_Preview"];
-          "ApplicationModel_Resources" = ["ApplicationModel"];
-          "ApplicationModel_Resources_Core" = ["ApplicationModel_Resources"];
-          "ApplicationModel_Resources_Management" = ["ApplicationModel_Resources"];
-          "ApplicationModel_Search" = ["ApplicationModel"];
-          "ApplicationModel_Search_Core" = ["ApplicationModel_Search"];
-          "ApplicationModel_UserActivities" = ["ApplicationModel"];
-          "ApplicationModel_UserActivities_Core" = ["ApplicationModel_UserActivities"];
-          "ApplicationModel_UserDataAccounts" = ["ApplicationModel"];
-          "ApplicationModel_UserDataAccounts_Provider" = ["ApplicationModel_UserDataAccounts"];
-          "ApplicationModel_UserDataAccounts_SystemAccess" = ["ApplicationModel_UserDataAccounts"];
-          "ApplicationModel_UserDataTasks" = ["ApplicationModel"];
-          "ApplicationModel_UserDataTasks_DataProvider" = ["ApplicationModel_UserDataTasks"];
-          "ApplicationModel_VoiceCommands" = ["ApplicationModel"];
-          "ApplicationModel_Wallet" = ["ApplicationModel"];
-          "ApplicationModel_Wallet_System" = ["ApplicationModel_Wallet"];
-          "Data" = ["Foundation"];
-          "Data_Html" = ["Data"];
-          "Data_Json" = ["Data"];
-          "Data_Pdf" = ["Data"];
-          "Data_Text" = ["Data"];
-          "Data_Xml" = ["Data"];
-          "Data_Xml_Dom" = ["Data_Xml"];
-          "Data_Xml_Xsl" = ["Data_Xml"];
-          "Devices" = ["Foundation"];
-          "Devices_Adc" = ["Devices"];
-          "Devices_Adc_Provider" = ["Devices_Adc"];
-          "Devices_Background" = ["Devices"];
-          "Devices_Bluetooth" = ["Devices"];
-          "Devices_Bluetooth_Advertisement" = ["Devices_Bluetooth"];
-          "Devices_Bluetooth_Background" = ["Devices_Bluetooth"];
-          "Devices_Bluetooth_GenericAttributeProfile" = ["Devices_Bluetooth"];
-          "Devices_Bluetooth_Rfcomm" = ["Devices_Bluetooth"];
-          "Devices_Custom" = ["Devices"];
-          "Devices_Display" = ["Devices"];
-          "Devices_Display_Core" = ["Devices_Display"];
-          "Devices_Enumeration" = ["Devices"];
-          "Devices_Enumeration_Pnp" = ["Devices_Enumeration"];
-          "Devices_Geolocation" = ["Devices"];
-          "Devices_Geolocation_Geofencing" = ["Devices_Geolocation"];
-          "Devices_Geolocation_Provider" = ["Devices_Geolocation"];
-          "Devices_Gpio" = ["Devices"];
-          "Devices_Gpio_Provider" = ["Devices_Gpio"];
-          "Devices_Haptics" = ["Devices"];
-          "Devices_HumanInterfaceDevice" = ["Devices"];
-          "Devices_I2c" = ["Devices"];
-          "Devices_I2c_Provider" = ["Devices_I2c"];
-          "Devices_Input" = ["Devices"];
-          "Devices_Input_Preview" = ["Devices_Input"];
-          "Devices_Lights" = ["Devices"];
-          "Devices_Lights_Effects" = ["Devices_Lights"];
-          "Devices_Midi" = ["Devices"];
-          "Devices_PointOfService" = ["Devices"];
-          "Devices_PointOfService_Provider" = ["Devices_PointOfService"];
-          "Devices_Portable" = ["Devices"];
-          "Devices_Power" = ["Devices"];
-          "Devices_Printers" = ["Devices"];
-          "Devices_Printers_Extensions" = ["Devices_Printers"];
-          "Devices_Pwm" = ["Devices"];
-          "Devices_Pwm_Provider" = ["Devices_Pwm"];
-          "Devices_Radios" = ["Devices"];
-          "Devices_Scanners" = ["Devices"];
-          "Devices_Sensors" = ["Devices"];
-          "Devices_Sensors_Custom" = ["Devices_Sensors"];
-          "Devices_SerialCommunication" = ["Devices"];
-          "Devices_SmartCards" = ["Devices"];
-          "Devices_Sms" = ["Devices"];
-          "Devices_Spi" = ["Devices"];
-          "Devices_Spi_Provider" = ["Devices_Spi"];
-          "Devices_Usb" = ["Devices"];
-          "Devices_WiFi" = ["Devices"];
-          "Devices_WiFiDirect" = ["Devices"];
-          "Devices_WiFiDirect_Services" = ["Devices_WiFiDirect"];
-          "Embedded" = ["Foundation"];
-          "Embedded_DeviceLockdown" = ["Embedded"];
-          "Foundation_Collections" = ["Foundation"];
-          "Foundation_Diagnostics" = ["Foundation"];
-          "Foundation_Metadata" = ["Foundation"];
-          "Foundation_Numerics" = ["Foundation"];
-          "Gaming" = ["Foundation"];
-          "Gaming_Input" = ["Gaming"];
-          "Gaming_Input_Custom" = ["Gaming_Input"];
-          "Gaming_Input_ForceFeedback" = ["Gaming_Input"];
-          "Gaming_Input_Preview" = ["Gaming_Input"];
-          "Gaming_Preview" = ["Gaming"];
-          "Gaming_Preview_GamesEnumeration" = ["Gaming_Preview"];
-          "Gaming_UI" = ["Gaming"];
-          "Gaming_XboxLive" = ["Gaming"];
-          "Gaming_XboxLive_Storage" = ["Gaming_XboxLive"];
-          "Globalization" = ["Foundation"];
-          "Globalization_Collation" = ["Globalization"];
-          "Globalization_DateTimeFormatting" = ["Globalization"];
-          "Globalization_Fonts" = ["Globalization"];
-          "Globalization_NumberFormatting" = ["Globalization"];
-          "Globalization_PhoneNumberFormatting" = ["Globalization"];
-          "Graphics" = ["Foundation"];
-          "Graphics_Capture" = ["Graphics"];
-          "Graphics_DirectX" = ["Graphics"];
-          "Graphics_DirectX_Direct3D11" = ["Graphics_DirectX"];
-          "Graphics_Display" = ["Graphics"];
-          "Graphics_Display_Core" = ["Graphics_Display"];
-          "Graphics_Effects" = ["Graphics"];
-          "Graphics_Holographic" = ["Graphics"];
-          "Graphics_Imaging" = ["Graphics"];
-          "Graphics_Printing" = ["Graphics"];
-          "Graphics_Printing3D" = ["Graphics"];
-          "Graphics_Printing_OptionDetails" = ["Graphics_Printing"];
-          "Graphics_Printing_PrintSupport" = ["Graphics_Printing"];
-          "Graphics_Printing_PrintTicket" = ["Graphics_Printing"];
-          "Graphics_Printing_Workflow" = ["Graphics_Printing"];
-          "Management" = ["Foundation"];
-          "Management_Core" = ["Management"];
-          "Management_Deployment" = ["Management"];
-          "Management_Deployment_Preview" = ["Management_Deployment"];
-          "Management_Policies" = ["Management"];
-          "Management_Update" = ["Management"];
-          "Management_Workplace" = ["Management"];
-          "Media" = ["Foundation"];
-          "Media_AppBroadcasting" = ["Media"];
-          "Media_AppRecording" = ["Media"];
-          "Media_Audio" = ["Media"];
-          "Media_Capture" = ["Media"];
-          "Media_Capture_Core" = ["Media_Capture"];
-          "Media_Capture_Frames" = ["Media_Capture"];
-          "Media_Casting" = ["Media"];
-          "Media_ClosedCaptioning" = ["Media"];
-          "Media_ContentRestrictions" = ["Media"];
-          "Media_Control" = ["Media"];
-          "Media_Core" = ["Media"];
-          "Media_Core_Preview" = ["Media_Core"];
-          "Media_Devices" = ["Media"];
-          "Media_Devices_Core" = ["Media_Devices"];
-          "Media_DialProtocol" = ["Media"];
-          "Media_Editing" = ["Media"];
-          "Media_Effects" = ["Media"];
-          "Media_FaceAnalysis" = ["Media"];
-          "Media_Import" = ["Media"];
-          "Media_MediaProperties" = ["Media"];
-          "Media_Miracast" = ["Media"];
-          "Media_Ocr" = ["Media"];
-          "Media_PlayTo" = ["Media"];
-          "Media_Playback" = ["Media"];
-          "Media_Playlists" = ["Media"];
-          "Media_Protection" = ["Media"];
-          "Media_Protection_PlayReady" = ["Media_Protection"];
-          "Media_Render" = ["Media"];
-          "Media_SpeechRecognition" = ["Media"];
-          "Media_SpeechSynthesis" = ["Media"];
-          "Media_Streaming" = ["Media"];
-          "Media_Streaming_Adaptive" = ["Media_Streaming"];
-          "Media_Transcoding" = ["Media"];
-          "Networking" = ["Foundation"];
-          "Networking_BackgroundTransfer" = ["Networking"];
-          "Networking_Connectivity" = ["Networking"];
-          "Networking_NetworkOperators" = ["Networking"];
-          "Networking_Proximity" = ["Networking"];
-          "Networking_PushNotifications" = ["Networking"];
-          "Networking_ServiceDiscovery" = ["Networking"];
-          "Networking_ServiceDiscovery_Dnssd" = ["Networking_ServiceDiscovery"];
-          "Networking_Sockets" = ["Networking"];
-          "Networking_Vpn" = ["Networking"];
-          "Networking_XboxLive" = ["Networking"];
-          "Perception" = ["Foundation"];
-          "Perception_Automation" = ["Perception"];
-          "Perception_Automation_Core" = ["Perception_Automation"];
-          "Perception_People" = ["Perception"];
-          "Perception_Spatial" = ["Perception"];
-          "Perception_Spatial_Preview" = ["Perception_Spatial"];
-          "Perception_Spatial_Surfaces" = ["Perception_Spatial"];
-          "Phone" = ["Foundation"];
-          "Phone_ApplicationModel" = ["Phone"];
-          "Phone_Devices" = ["Phone"];
-          "Phone_Devices_Notification" = ["Phone_Devices"];
-          "Phone_Devices_Power" = ["Phone_Devices"];
-          "Phone_Management" = ["Phone"];
-          "Phone_Management_Deployment" = ["Phone_Management"];
-          "Phone_Media" = ["Phone"];
-          "Phone_Media_Devices" = ["Phone_Media"];
-          "Phone_Notification" = ["Phone"];
-          "Phone_Notification_Management" = ["Phone_Notification"];
-          "Phone_PersonalInformation" = ["Phone"];
-          "Phone_PersonalInformation_Provisioning" = ["Phone_PersonalInformation"];
-          "Phone_Speech" = ["Phone"];
-          "Phone_Speech_Recognition" = ["Phone_Speech"];
-          "Phone_StartScreen" = ["Phone"];
-          "Phone_System" = ["Phone"];
-          "Phone_System_Power" = ["Phone_System"];
-          "Phone_System_Profile" = ["Phone_System"];
-          "Phone_System_UserProfile" = ["Phone_System"];
-          "Phone_System_UserProfile_GameServices" = ["Phone_System_UserProfile"];
-          "Phone_System_UserProfile_GameServices_Core" = ["Phone_System_UserProfile_GameServices"];
-          "Phone_UI" = ["Phone"];
-          "Phone_UI_Input" = ["Phone_UI"];
-          "Security" = ["Foundation"];
-          "Security_Authentication" = ["Security"];
-          "Security_Authentication_Identity" = ["Security_Authentication"];
-          "Security_Authentication_Identity_Core" = ["Security_Authentication_Identity"];
-          "Security_Authentication_OnlineId" = ["Security_Authentication"];
-          "Security_Authentication_Web" = ["Security_Authentication"];
-          "Security_Authentication_Web_Core" = ["Security_Authentication_Web"];
-          "Security_Authentication_Web_Provider" = ["Security_Authentication_Web"];
-          "Security_Authorization" = ["Security"];
-          "Security_Authorization_AppCapabilityAccess" = ["Security_Authorization"];
-          "Security_Credentials" = ["Security"];
-          "Security_Credentials_UI" = ["Security_Credentials"];
-          "Security_Cryptography" = ["Security"];
-          "Security_Cryptography_Certificates" = ["Security_Cryptography"];
-          "Security_Cryptography_Core" = ["Security_Cryptography"];
-          "Security_Cryptography_DataProtection" = ["Security_Cryptography"];
-          "Security_DataProtection" = ["Security"];
-          "Security_EnterpriseData" = ["Security"];
-          "Security_ExchangeActiveSyncProvisioning" = ["Security"];
-          "Security_Isolation" = ["Security"];
-          "Services" = ["Foundation"];
-          "Services_Maps" = ["Services"];
-          "Services_Maps_Guidance" = ["Services_Maps"];
-          "Services_Maps_LocalSearch" = ["Services_Maps"];
-          "Services_Maps_OfflineMaps" = ["Services_Maps"];
-          "Services_Store" = ["Services"];
-          "Services_TargetedContent" = ["Services"];
-          "Storage" = ["Foundation"];
-          "Storage_AccessCache" = ["Storage"];
-          "Storage_BulkAccess" = ["Storage"];
-          "Storage_Compression" = ["Storage"];
-          "Storage_FileProperties" = ["Storage"];
-          "Storage_Pickers" = ["Storage"];
-          "Storage_Pickers_Provider" = ["Storage_Pickers"];
-          "Storage_Provider" = ["Storage"];
-          "Storage_Search" = ["Storage"];
-          "Storage_Streams" = ["Storage"];
-          "System" = ["Foundation"];
-          "System_Diagnostics" = ["System"];
-          "System_Diagnostics_DevicePortal" = ["System_Diagnostics"];
-          "System_Diagnostics_Telemetry" = ["System_Diagnostics"];
-          "System_Diagnostics_TraceReporting" = ["System_Diagnostics"];
-          "System_Display" = ["System"];
-          "System_Implementation" = ["System"];
-          "System_Implementation_FileExplorer" = ["System_Implementation"];
-          "System_Inventory" = ["System"];
-          "System_Power" = ["System"];
-          "System_Profile" = ["System"];
-          "System_Profile_SystemManufacturers" = ["System_Profile"];
-          "System_RemoteDesktop" = ["System"];
-          "System_RemoteDesktop_Input" = ["System_RemoteDesktop"];
-          "System_RemoteDesktop_Provider" = ["System_RemoteDesktop"];
-          "System_RemoteSystems" = ["System"];
-          "System_Threading" = ["System"];
-          "System_Threading_Core" = ["System_Threading"];
-          "System_Update" = ["System"];
-          "System_UserProfile" = ["System"];
-          "UI" = ["Foundation"];
-          "UI_Accessibility" = ["UI"];
-          "UI_ApplicationSettings" = ["UI"];
-          "UI_Composition" = ["UI"];
-          "UI_Composition_Core" = ["UI_Composition"];
-          "UI_Composition_Desktop" = ["UI_Composition"];
-          "UI_Composition_Diagnostics" = ["UI_Composition"];
-          "UI_Composition_Effects" = ["UI_Composition"];
-          "UI_Composition_Interactions" = ["UI_Composition"];
-          "UI_Composition_Scenes" = ["UI_Composition"];
-          "UI_Core" = ["UI"];
-          "UI_Core_AnimationMetrics" = ["UI_Core"];
-          "UI_Core_Preview" = ["UI_Core"];
-          "UI_Input" = ["UI"];
-          "UI_Input_Core" = ["UI_Input"];
-          "UI_Input_Inking" = ["UI_Input"];
-          "UI_Input_Inking_Analysis" = ["UI_Input_Inking"];
-          "UI_Input_Inking_Core" = ["UI_Input_Inking"];
-          "UI_Input_Inking_Preview" = ["UI_Input_Inking"];
-          "UI_Input_Preview" = ["UI_Input"];
-          "UI_Input_Preview_Injection" = ["UI_Input_Preview"];
-          "UI_Input_Spatial" = ["UI_Input"];
-          "UI_Notifications" = ["UI"];
-          "UI_Notifications_Management" = ["UI_Notifications"];
-          "UI_Notifications_Preview" = ["UI_Notifications"];
-          "UI_Popups" = ["UI"];
-          "UI_Shell" = ["UI"];
-          "UI_StartScreen" = ["UI"];
-          "UI_Text" = ["UI"];
-          "UI_Text_Core" = ["UI_Text"];
-          "UI_UIAutomation" = ["UI"];
-          "UI_UIAutomation_Core" = ["UI_UIAutomation"];
-          "UI_ViewManagement" = ["UI"];
-          "UI_ViewManagement_Core" = ["UI_ViewManagement"];
-          "UI_WebUI" = ["UI"];
-          "UI_WebUI_Core" = ["UI_WebUI"];
-          "UI_WindowManagement" = ["UI"];
-          "UI_WindowManagement_Preview" = ["UI_WindowManagement"];
-          "Wdk" = ["Win32_Foundation"];
-          "Wdk_Devices" = ["Wdk"];
-          "Wdk_Devices_HumanInterfaceDevice" = ["Wdk_Devices"];
-          "Wdk_Foundation" = ["Wdk"];
-          "Wdk_Graphics" = ["Wdk"];
-          "Wdk_Graphics_Direct3D" = ["Wdk_Graphics"];
-          "Wdk_NetworkManagement" = ["Wdk"];
-          "Wdk_NetworkManagement_Ndis" = ["Wdk_NetworkManagement"];
-          "Wdk_NetworkManagement_WindowsFilteringPlatform" = ["Wdk_NetworkManagement"];
-          "Wdk_Storage" = ["Wdk"];
-          "Wdk_Storage_FileSystem" = ["Wdk_Storage"];
-          "Wdk_Storage_FileSystem_Minifilters" = ["Wdk_Storage_FileSystem"];
-          "Wdk_System" = ["Wdk"];
-          "Wdk_System_IO" = ["Wdk_System"];
-          "Wdk_System_OfflineRegistry" = ["Wdk_System"];
-          "Wdk_System_Registry" = ["Wdk_System"];
-          "Wdk_System_SystemInformation" = ["Wdk_System"];
-          "Wdk_System_SystemServices" = ["Wdk_System"];
-          "Wdk_System_Threading" = ["Wdk_System"];
-          "Web" = ["Foundation"];
-          "Web_AtomPub" = ["Web"];
-          "Web_Http" = ["Web"];
-          "Web_Http_Diagnostics" = ["Web_Http"];
-          "Web_Http_Filters" = ["Web_Http"];
-          "Web_Http_Headers" = ["Web_Http"];
-          "Web_Syndication" = ["Web"];
-          "Web_UI" = ["Web"];
-          "Web_UI_Interop" = ["Web_UI"];
-          "Win32" = ["Win32_Foundation"];
-          "Win32_AI" = ["Win32"];
-          "Win32_AI_MachineLearning" = ["Win32_AI"];
-          "Win32_AI_MachineLearning_DirectML" = ["Win32_AI_MachineLearning"];
-          "Win32_AI_MachineLearning_WinML" = ["Win32_AI_MachineLearning"];
-          "Win32_Data" = ["Win32"];
-          "Win32_Data_HtmlHelp" = ["Win32_Data"];
-          "Win32_Data_RightsManagement" = ["Win32_Data"];
-          "Win32_Data_Xml" = ["Win32_Data"];
-          "Win32_Data_Xml_MsXml" = ["Win32_Data_Xml"];
-          "Win32_Data_Xml_XmlLite" = ["Win32_Data_Xml"];
-          "Win32_Devices" = ["Win32"];
-          "Win32_Devices_AllJoyn" = ["Win32_Devices"];
-          "Win32_Devices_BiometricFramework" = ["Win32_Devices"];
-          "Win32_Devices_Bluetooth" = ["Win32_Devices"];
-          "Win32_Devices_Communication" = ["Win32_Devices"];
-          "Win32_Devices_DeviceAccess" = ["Win32_Devices"];
-          "Win32_Devices_DeviceAndDriverInstallation" = ["Win32_Devices"];
-          "Win32_Devices_DeviceQuery" = ["Win32_Devices"];
-          "Win32_Devices_Display" = ["Win32_Devices"];
-          "Win32_Devices_Enumeration" = ["Win32_Devices"];
-          "Win32_Devices_Enumeration_Pnp" = ["Win32_Devices_Enumeration"];
-          "Win32_Devices_Fax" = ["Win32_Devices"];
-          "Win32_Devices_FunctionDiscovery" = ["Win32_Devices"];
-          "Win32_Devices_Geolocation" = ["Win32_Devices"];
-          "Win32_Devices_HumanInterfaceDevice" = ["Win32_Devices"];
-          "Win32_Devices_ImageAcquisition" = ["Win32_Devices"];
-          "Win32_Devices_PortableDevices" = ["Win32_Devices"];
-          "Win32_Devices_Properties" = ["Win32_Devices"];
-          "Win32_Devices_Pwm" = ["Win32_Devices"];
-          "Win32_Devices_Sensors" = ["Win32_Devices"];
-          "Win32_Devices_SerialCommunication" = ["Win32_Devices"];
-          "Win32_Devices_Tapi" = ["Win32_Devices"];
-          "Win32_Devices_Usb" = ["Win32_Devices"];
-          "Win32_Devices_WebServicesOnDevices" = ["Win32_Devices"];
-          "Win32_Foundation" = ["Win32"];
-          "Win32_Gaming" = ["Win32"];
-          "Win32_Globalization" = ["Win32"];
-          "Win32_Graphics" = ["Win32"];
-          "Win32_Graphics_CompositionSwapchain" = ["Win32_Graphics"];
-          "Win32_Graphics_DXCore" = ["Win32_Graphics"];
-          "Win32_Graphics_Direct2D" = ["Win32_Graphics"];
-          "Win32_Graphics_Direct2D_Common" = ["Win32_Graphics_Direct2D"];
-          "Win32_Graphics_Direct3D" = ["Win32_Graphics"];
-          "Win32_Graphics_Direct3D10" = ["Win32_Graphics"];
-          "Win32_Graphics_Direct3D11" = ["Win32_Graphics"];
-          "Win32_Graphics_Direct3D11on12" = ["Win32_Graphics"];
-          "Win32_Graphics_Direct3D12" = ["Win32_Graphics"];
-          "Win32_Graphics_Direct3D9" = ["Win32_Graphics"];
-          "Win32_Graphics_Direct3D9on12" = ["Win32_Graphics"];
-          "Win32_Graphics_Direct3D_Dxc" = ["Win32_Graphics_Direct3D"];
-          "Win32_Graphics_Direct3D_Fxc" = ["Win32_Graphics_Direct3D"];
-          "Win32_Graphics_DirectComposition" = ["Win32_Graphics"];
-          "Win32_Graphics_DirectDraw" = ["Win32_Graphics"];
-          "Win32_Graphics_DirectManipulation" = ["Win32_Graphics"];
-          "Win32_Graphics_DirectWrite" = ["Win32_Graphics"];
-          "Win32_Graphics_Dwm" = ["Win32_Graphics"];
-          "Win32_Graphics_Dxgi" = ["Win32_Graphics"];
-          "Win32_Graphics_Dxgi_Common" = ["Win32_Graphics_Dxgi"];
-          "Win32_Graphics_Gdi" = ["Win32_Graphics"];
-          "Win32_Graphics_GdiPlus" = ["Win32_Graphics"];
-          "Win32_Graphics_Hlsl" = ["Win32_Graphics"];
-          "Win32_Graphics_Imaging" = ["Win32_Graphics"];
-          "Win32_Graphics_Imaging_D2D" = ["Win32_Graphics_Imaging"];
-          "Win32_Graphics_OpenGL" = ["Win32_Graphics"];
-          "Win32_Graphics_Printing" = ["Win32_Graphics"];
-          "Win32_Graphics_Printing_PrintTicket" = ["Win32_Graphics_Printing"];
-          "Win32_Management" = ["Win32"];
-          "Win32_Management_MobileDeviceManagementRegistration" = ["Win32_Management"];
-          "Win32_Media" = ["Win32"];
-          "Win32_Media_Audio" = ["Win32_Media"];
-          "Win32_Media_Audio_Apo" = ["Win32_Media_Audio"];
-          "Win32_Media_Audio_DirectMusic" = ["Win32_Media_Audio"];
-          "Win32_Media_Audio_DirectSound" = ["Win32_Media_Audio"];
-          "Win32_Media_Audio_Endpoints" = ["Win32_Media_Audio"];
-          "Win32_Media_Audio_XAudio2" = ["Win32_Media_Audio"];
-          "Win32_Media_DeviceManager" = ["Win32_Media"];
-          "Win32_Media_DirectShow" = ["Win32_Media"];
-          "Win32_Media_DirectShow_Tv" = ["Win32_Media_DirectShow"];
-          "Win32_Media_DirectShow_Xml" = ["Win32_Media_DirectShow"];
-          "Win32_Media_DxMediaObjects" = ["Win32_Media"];
-          "Win32_Media_KernelStreaming" = ["Win32_Media"];
-          "Win32_Media_LibrarySharingServices" = ["Win32_Media"];
-          "Win32_Media_MediaFoundation" = ["Win32_Media"];
-          "Win32_Media_MediaPlayer" = ["Win32_Media"];
-          "Win32_Media_Multimedia" = ["Win32_Media"];
-          "Win32_Media_PictureAcquisition" = ["Win32_Media"];
-          "Win32_Media_Speech" = ["Win32_Media"];
-          "Win32_Media_Streaming" = ["Win32_Media"];
-          "Win32_Media_WindowsMediaFormat" = ["Win32_Media"];
-          "Win32_NetworkManagement" = ["Win32"];
-          "Win32_NetworkManagement_Dhcp" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_Dns" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_InternetConnectionWizard" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_IpHelper" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_MobileBroadband" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_Multicast" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_Ndis" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_NetBios" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_NetManagement" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_NetShell" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_NetworkDiagnosticsFramework" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_NetworkPolicyServer" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_P2P" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_QoS" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_Rras" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_Snmp" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_WNet" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_WebDav" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_WiFi" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_WindowsConnectNow" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_WindowsConnectionManager" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_WindowsFilteringPlatform" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_WindowsFirewall" = ["Win32_NetworkManagement"];
-          "Win32_NetworkManagement_WindowsNetworkVirtualization" = ["Win32_NetworkManagement"];
-          "Win32_Networking" = ["Win32"];
-          "Win32_Networking_ActiveDirectory" = ["Win32_Networking"];
-          "Win32_Networking_BackgroundIntelligentTransferService" = ["Win32_Networking"];
-          "Win32_Networking_Clustering" = ["Win32_Networking"];
-          "Win32_Networking_HttpServer" = ["Win32_Networking"];
-          "Win32_Networking_Ldap" = ["Win32_Networking"];
-          "Win32_Networking_NetworkListManager" = ["Win32_Networking"];
-          "Win32_Networking_RemoteDifferentialCompression" = ["Win32_Networking"];
-          "Win32_Networking_WebSocket" = ["Win32_Networking"];
-          "Win32_Networking_WinHttp" = ["Win32_Networking"];
-          "Win32_Networking_WinInet" = ["Win32_Networking"];
-          "Win32_Networking_WinSock" = ["Win32_Networking"];
-          "Win32_Networking_WindowsWebServices" = ["Win32_Networking"];
-          "Win32_Security" = ["Win32"];
-          "Win32_Security_AppLocker" = ["Win32_Security"];
-          "Win32_Security_Authentication" = ["Win32_Security"];
-          "Win32_Security_Authentication_Identity" = ["Win32_Security_Authentication"];
-          "Win32_Security_Authentication_Identity_Provider" = ["Win32_Security_Authentication_Identity"];
-          "Win32_Security_Authorization" = ["Win32_Security"];
-          "Win32_Security_Authorization_UI" = ["Win32_Security_Authorization"];
-          "Win32_Security_ConfigurationSnapin" = ["Win32_Security"];
-          "Win32_Security_Credentials" = ["Win32_Security"];
-          "Win32_Security_Cryptography" = ["Win32_Security"];
-          "Win32_Security_Cryptography_Catalog" = ["Win32_Security_Cryptography"];
-          "Win32_Security_Cryptography_Certificates" = ["Win32_Security_Cryptography"];
-          "Win32_Security_Cryptography_Sip" = ["Win32_Security_Cryptography"];
-          "Win32_Security_Cryptography_UI" = ["Win32_Security_Cryptography"];
-          "Win32_Security_DiagnosticDataQuery" = ["Win32_Security"];
-          "Win32_Security_DirectoryServices" = ["Win32_Security"];
-          "Win32_Security_EnterpriseData" = ["Win32_Security"];
-          "Win32_Security_ExtensibleAuthenticationProtocol" = ["Win32_Security"];
-          "Win32_Security_Isolation" = ["Win32_Security"];
-          "Win32_Security_LicenseProtection" = ["Win32_Security"];
-          "Win32_Security_NetworkAccessProtection" = ["Win32_Security"];
-          "Win32_Security_Tpm" = ["Win32_Security"];
-          "Win32_Security_WinTrust" = ["Win32_Security"];
-          "Win32_Security_WinWlx" = ["Win32_Security"];
-          "Win32_Storage" = ["Win32"];
-          "Win32_Storage_Cabinets" = ["Win32_Storage"];
-          "Win32_Storage_CloudFilters" = ["Win32_Storage"];
-          "Win32_Storage_Compression" = ["Win32_Storage"];
-          "Win32_Storage_DataDeduplication" = ["Win32_Storage"];
-          "Win32_Storage_DistributedFileSystem" = ["Win32_Storage"];
-          "Win32_Storage_EnhancedStorage" = ["Win32_Storage"];
-          "Win32_Storage_FileHistory" = ["Win32_Storage"];
-          "Win32_Storage_FileServerResourceManager" = ["Win32_Storage"];
-          "Win32_Storage_FileSystem" = ["Win32_Storage"];
-          "Win32_Storage_Imapi" = ["Win32_Storage"];
-          "Win32_Storage_IndexServer" = ["Win32_Storage"];
-          "Win32_Storage_InstallableFileSystems" = ["Win32_Storage"];
-          "Win32_Storage_IscsiDisc" = ["Win32_Storage"];
-          "Win32_Storage_Jet" = ["Win32_Storage"];
-          "Win32_Storage_Nvme" = ["Win32_Storage"];
-          "Win32_Storage_OfflineFiles" = ["Win32_Storage"];
-          "Win32_Storage_OperationRecorder" = ["Win32_Storage"];
-          "Win32_Storage_Packaging" = ["Win32_Storage"];
-          "Win32_Storage_Packaging_Appx" = ["Win32_Storage_Packaging"];
-          "Win32_Storage_Packaging_Opc" = ["Win32_Storage_Packaging"];
-          "Win32_Storage_ProjectedFileSystem" = ["Win32_Storage"];
-          "Win32_Storage_StructuredStorage" = ["Win32_Storage"];
-          "Win32_Storage_Vhd" = ["Win32_Storage"];
-          "Win32_Storage_VirtualDiskService" = ["Win32_Storage"];
-          "Win32_Storage_Vss" = ["Win32_Storage"];
-          "Win32_Storage_Xps" = ["Win32_Storage"];
-          "Win32_Storage_Xps_Printing" = ["Win32_Storage_Xps"];
-          "Win32_System" = ["Win32"];
-          "Win32_System_AddressBook" = ["Win32_System"];
-          "Win32_System_Antimalware" = ["Win32_System"];
-          "Win32_System_ApplicationInstallationAndServicing" = ["Win32_System"];
-          "Win32_System_ApplicationVerifier" = ["Win32_System"];
-          "Win32_System_AssessmentTool" = ["Win32_System"];
-          "Win32_System_ClrHosting" = ["Win32_System"];
-          "Win32_System_Com" = ["Win32_System"];
-          "Win32_System_Com_CallObj" = ["Win32_System_Com"];
-          "Win32_System_Com_ChannelCredentials" = ["Win32_System_Com"];
-          "Win32_System_Com_Events" = ["Win32_System_Com"];
-          "Win32_System_Com_Marshal" = ["Win32_System_Com"];
-          "Win32_System_Com_StructuredStorage" = ["Win32_System_Com"];
-          "Win32_System_Com_UI" = ["Win32_System_Com"];
-          "Win32_System_Com_Urlmon" = ["Win32_System_Com"];
-          "Win32_System_ComponentServices" = ["Win32_System"];
-          "Win32_System_Console" = ["Win32_System"];
-          "Win32_System_Contacts" = ["Win32_System"];
-          "Win32_System_CorrelationVector" = ["Win32_System"];
-          "Win32_System_DataExchange" = ["Win32_System"];
-          "Win32_System_DeploymentServices" = ["Win32_System"];
-          "Win32_System_DesktopSharing" = ["Win32_System"];
-          "Win32_System_DeveloperLicensing" = ["Win32_System"];
-          "Win32_System_Diagnostics" = ["Win32_System"];
-          "Win32_System_Diagnostics_Ceip" = ["Win32_System_Diagnostics"];
-          "Win32_System_Diagnostics_ClrProfiling" = ["Win32_System_Diagnostics"];
-          "Win32_System_Diagnostics_Debug" = ["Win32_System_Diagnostics"];
-          "Win32_System_Diagnostics_Debug_ActiveScript" = ["Win32_System_Diagnostics_Debug"];
-          "Win32_System_Diagnostics_Debug_Extensions" = ["Win32_System_Diagnostics_Debug"];
-          "Win32_System_Diagnostics_Etw" = ["Win32_System_Diagnostics"];
-          "Win32_System_Diagnostics_ProcessSnapshotting" = ["Win32_System_Diagnostics"];
-          "Win32_System_Diagnostics_ToolHelp" = ["Win32_System_Diagnostics"];
-          "Win32_System_Diagnostics_TraceLogging" = ["Win32_System_Diagnostics"];
-          "Win32_System_DistributedTransactionCoordinator" = ["Win32_System"];
-          "Win32_System_Environment" = ["Win32_System"];
-          "Win32_System_ErrorReporting" = ["Win32_System"];
-          "Win32_System_EventCollector" = ["Win32_System"];
-          "Win32_System_EventLog" = ["Win32_System"];
-          "Win32_System_EventNotificationService" = ["Win32_System"];
-          "Win32_System_GroupPolicy" = ["Win32_System"];
-          "Win32_System_HostCompute" = ["Win32_System"];
-          "Win32_System_HostComputeNetwork" = ["Win32_System"];
-          "Win32_System_HostComputeSystem" = ["Win32_System"];
-          "Win32_System_Hypervisor" = ["Win32_System"];
-          "Win32_System_IO" = ["Win32_System"];
-          "Win32_System_Iis" = ["Win32_System"];
-          "Win32_System_Ioctl" = ["Win32_System"];
-          "Win32_System_JobObjects" = ["Win32_System"];
-          "Win32_System_Js" = ["Win32_System"];
-          "Win32_System_Kernel" = ["Win32_System"];
-          "Win32_System_LibraryLoader" = ["Win32_System"];
-          "Win32_System_Mailslots" = ["Win32_System"];
-          "Win32_System_Mapi" = ["Win32_System"];
-          "Win32_System_Memory" = ["Win32_System"];
-          "Win32_System_Memory_NonVolatile" = ["Win32_System_Memory"];
-          "Win32_System_MessageQueuing" = ["Win32_System"];
-          "Win32_System_MixedReality" = ["Win32_System"];
-          "Win32_System_Mmc" = ["Win32_System"];
-          "Win32_System_Ole" = ["Win32_System"];
-          "Win32_System_ParentalControls" = ["Win32_System"];
-          "Win32_System_PasswordManagement" = ["Win32_System"];
-          "Win32_System_Performance" = ["Win32_System"];
-          "Win32_System_Performance_HardwareCounterProfiling" = ["Win32_System_Performance"];
-          "Win32_System_Pipes" = ["Win32_System"];
-          "Win32_System_Power" = ["Win32_System"];
-          "Win32_System_ProcessStatus" = ["Win32_System"];
-          "Win32_System_RealTimeCommunications" = ["Win32_System"];
-          "Win32_System_Recovery" = ["Win32_System"];
-          "Win32_System_Registry" = ["Win32_System"];
-          "Win32_System_RemoteAssistance" = ["Win32_System"];
-          "Win32_System_RemoteDesktop" = ["Win32_System"];
-          "Win32_System_RemoteManagement" = ["Win32_System"];
-          "Win32_System_RestartManager" = ["Win32_System"];
-          "Win32_System_Restore" = ["Win32_System"];
-          "Win32_System_Rpc" = ["Win32_System"];
-          "Win32_System_Search" = ["Win32_System"];
-          "Win32_System_Search_Common" = ["Win32_System_Search"];
-          "Win32_System_SecurityCenter" = ["Win32_System"];
-          "Win32_System_ServerBackup" = ["Win32_System"];
-          "Win32_System_Services" = ["Win32_System"];
-          "Win32_System_SettingsManagementInfrastructure" = ["Win32_System"];
-          "Win32_System_SetupAndMigration" = ["Win32_System"];
-          "Win32_System_Shutdown" = ["Win32_System"];
-          "Win32_System_SideShow" = ["Win32_System"];
-          "Win32_System_StationsAndDesktops" = ["Win32_System"];
-          "Win32_System_SubsystemForLinux" = ["Win32_System"];
-          "Win32_System_SystemInformation" = ["Win32_System"];
-          "Win32_System_SystemServices" = ["Win32_System"];
-          "Win32_System_TaskScheduler" = ["Win32_System"];
-          "Win32_System_Threading" = ["Win32_System"];
-          "Win32_System_Time" = ["Win32_System"];
-          "Win32_System_TpmBaseServices" = ["Win32_System"];
-          "Win32_System_TransactionServer" = ["Win32_System"];
-          "Win32_System_UpdateAgent" = ["Win32_System"];
-          "Win32_System_UpdateAssessment" = ["Win32_System"];
-          "Win32_System_UserAccessLogging" = ["Win32_System"];
-          "Win32_System_Variant" = ["Win32_System"];
-          "Win32_System_VirtualDosMachines" = ["Win32_System"];
-          "Win32_System_WinRT" = ["Win32_System"];
-          "Win32_System_WinRT_AllJoyn" = ["Win32_System_WinRT"];
-          "Win32_System_WinRT_Composition" = ["Win32_System_WinRT"];
-          "Win32_System_WinRT_CoreInputView" = ["Win32_System_WinRT"];
-          "Win32_System_WinRT_Direct3D11" = ["Win32_System_WinRT"];
-          "Win32_System_WinRT_Display" = ["Win32_System_WinRT"];
-          "Win32_System_WinRT_Graphics" = ["Win32_System_WinRT"];
-          "Win32_System_WinRT_Graphics_Capture" = ["Win32_System_WinRT_Graphics"];
-          "Win32_System_WinRT_Graphics_Direct2D" = ["Win32_System_WinRT_Graphics"];
-          "Win32_System_WinRT_Graphics_Imaging" = ["Win32_System_WinRT_Graphics"];
-          "Win32_System_WinRT_Holographic" = ["Win32_System_WinRT"];
-          "Win32_System_WinRT_Isolation" = ["Win32_System_WinRT"];
-          "Win32_System_WinRT_ML" = ["Win32_System_WinRT"];
-          "Win32_System_WinRT_Media" = ["Win32_System_WinRT"];
-          "Win32_System_WinRT_Metadata" = ["Win32_System_WinRT"];
-          "Win32_System_WinRT_Pdf" = ["Win32_System_WinRT"];
-          "Win32_System_WinRT_Printing" = ["Win32_System_WinRT"];
-          "Win32_System_WinRT_Shell" = ["Win32_System_WinRT"];
-          "Win32_System_WinRT_Storage" = ["Win32_System_WinRT"];
-          "Win32_System_WindowsProgramming" = ["Win32_System"];
-          "Win32_System_WindowsSync" = ["Win32_System"];
-          "Win32_System_Wmi" = ["Win32_System"];
-          "Win32_UI" = ["Win32"];
-          "Win32_UI_Accessibility" = ["Win32_UI"];
-          "Win32_UI_Animation" = ["Win32_UI"];
-          "Win32_UI_ColorSystem" = ["Win32_UI"];
-          "Win32_UI_Controls" = ["Win32_UI"];
-          "Win32_UI_Controls_Dialogs" = ["Win32_UI_Controls"];
-          "Win32_UI_Controls_RichEdit" = ["Win32_UI_Controls"];
-          "Win32_UI_HiDpi" = ["Win32_UI"];
-          "Win32_UI_Input" = ["Win32_UI"];
-          "Win32_UI_Input_Ime" = ["Win32_UI_Input"];
-          "Win32_UI_Input_Ink" = ["Win32_UI_Input"];
-          "Win32_UI_Input_KeyboardAndMouse" = ["Win32_UI_Input"];
-          "Win32_UI_Input_Pointer" = ["Win32_UI_Input"];
-          "Win32_UI_Input_Radial" = ["Win32_UI_Input"];
-          "Win32_UI_Input_Touch" = ["Win32_UI_Input"];
-          "Win32_UI_Input_XboxController" = ["Win32_UI_Input"];
-          "Win32_UI_InteractionContext" = ["Win32_UI"];
-          "Win32_UI_LegacyWindowsEnvironmentFeatures" = ["Win32_UI"];
-          "Win32_UI_Magnification" = ["Win32_UI"];
-          "Win32_UI_Notifications" = ["Win32_UI"];
-          "Win32_UI_Ribbon" = ["Win32_UI"];
-          "Win32_UI_Shell" = ["Win32_UI"];
-          "Win32_UI_Shell_Common" = ["Win32_UI_Shell"];
-          "Win32_UI_Shell_PropertiesSystem" = ["Win32_UI_Shell"];
-          "Win32_UI_TabletPC" = ["Win32_UI"];
-          "Win32_UI_TextServices" = ["Win32_UI"];
-          "Win32_UI_WindowsAndMessaging" = ["Win32_UI"];
-          "Win32_UI_Wpf" = ["Win32_UI"];
-          "Win32_Web" = ["Win32"];
-          "Win32_Web_InternetExplorer" = ["Win32_Web"];
-          "default" = ["std"];
-          "std" = ["windows-core/std"];
-        };
-        resolvedDefaultFeatures = ["Wdk" "Wdk_System" "Wdk_System_SystemInformation" "Wdk_System_SystemServices" "Wdk_System_Threading" "Win32" "Win32_Foundation" "Win32_NetworkManagement" "Win32_NetworkManagement_IpHelper" "Win32_NetworkManagement_Ndis" "Win32_NetworkManagement_NetManagement" "Win32_Networking" "Win32_Networking_WinSock" "Win32_Security" "Win32_Security_Authentication" "Win32_Security_Authentication_Identity" "Win32_Security_Authorization" "Win32_Storage" "Win32_Storage_FileSystem" "Win32_System" "Win32_System_Com" "Win32_System_Diagnostics" "Win32_System_Diagnostics_Debug" "Win32_System_IO" "Win32_System_Ioctl" "Win32_System_Kernel" "Win32_System_Memory" "Win32_System_Performance" "Win32_System_Power" "Win32_System_ProcessStatus" "Win32_System_Registry" "Win32_System_RemoteDesktop" "Win32_System_Rpc" "Win32_System_SystemInformation" "Win32_System_SystemServices" "Win32_System_Threading" "Win32_System_Variant" "Win32_System_WindowsProgramming" "Win32_System_Wmi" "Win32_UI" "Win32_UI_Shell" "default" "std"];
-      };
-      "windows-core" = rec {
-        crateName = "windows-core";
-        version = "0.57.0";
-        edition = "2021";
-        sha256 = "0bc3jxw2jw76xkk3ddvnp5b2m76qmbzv1qncgvb6qrlhl8wj9vfj";
-        libName = "windows_core";
-        authors = [
-          "Microsoft"
-        ];
-        dependencies = [
-          {
-            name = "windows-implement";
-            packageId = "windows-implement";
-          }
-          {
-            name = "windows-interface";
-            packageId = "windows-interface";
-          }
-          {
-            name = "windows-result";
-            packageId = "windows-result";
-          }
-          {
-            name = "windows-targets";
-            packageId = "windows-targets";
-          }
-        ];
-        features = {
-          "default" = ["std"];
-        };
-        resolvedDefaultFeatures = ["default" "std"];
-      };
-      "windows-implement" = rec {
-        crateName = "windows-implement";
-        version = "0.57.0";
-        edition = "2021";
-        sha256 = "1mqs7qypclnmx5r8yq5jy3g2d8i27vzag9yzzzxzpdnmb70ds1wi";
-        procMacro = true;
-        libName = "windows_implement";
-        authors = [
-          "Microsoft"
-        ];
-        dependencies = [
-          {
-            name = "proc-macro2";
-            packageId = "proc-macro2";
-          }
-          {
-            name = "quote";
-            packageId = "quote";
-          }
-          {
-            name = "syn";
-            packageId = "syn";
-            usesDefaultFeatures = false;
-            features = ["parsing" "proc-macro" "printing" "full" "derive"];
-          }
-        ];
-      };
-      "windows-interface" = rec {
-        crateName = "windows-interface";
-        version = "0.57.0";
-        edition = "2021";
-        sha256 = "19zwlzr0q1z9s692681yb5w2lhvwcyx4v95s25hfdkd3isry9gi9";
-        procMacro = true;
-        libName = "windows_interface";
-        authors = [
-          "Microsoft"
-        ];
-        dependencies = [
-          {
-            name = "proc-macro2";
-            packageId = "proc-macro2";
-          }
-          {
-            name = "quote";
-            packageId = "quote";
-          }
-          {
-            name = "syn";
-            packageId = "syn";
-            usesDefaultFeatures = false;
-            features = ["parsing" "proc-macro" "printing" "full" "derive" "clone-impls"];
-          }
-        ];
-      };
-      "windows-result" = rec {
-        crateName = "windows-result";
-        version = "0.1.2";
-        edition = "2021";
-        sha256 = "1y274q1v0vy21lhkgslpxpq1m08hvr1mcs2l88h1b1gcx0136f2y";
-        libName = "windows_result";
-        authors = [
-          "Microsoft"
-        ];
-        dependencies = [
-          {
-            name = "windows-targets";
-            packageId = "windows-targets";
-          }
-        ];
-        features = {
-          "default" = ["std"];
-        };
-        resolvedDefaultFeatures = ["default" "std"];
-      };
-      "windows-targets" = rec {
-        crateName = "windows-targets";
-        version = "0.52.6";
-        edition = "2021";
-        sha256 = "0wwrx625nwlfp7k93r2rra568gad1mwd888h1jwnl0vfg5r4ywlv";
-        libName = "windows_targets";
-        authors = [
-          "Microsoft"
-        ];
-        dependencies = [
-          {
-            name = "windows_aarch64_gnullvm";
-            packageId = "windows_aarch64_gnullvm";
-            target = {
-              target,
-              features,
-            }: (stdenv.hostPlatform.rust.rustcTarget == "aarch64-pc-windows-gnullvm");
-          }
-          {
-            name = "windows_aarch64_msvc";
-            packageId = "windows_aarch64_msvc";
-            target = {
-              target,
-              features,
-            }: (("aarch64" == target."arch" or null) && ("msvc" == target."env" or null) && (!(target."windows_raw_dylib" or false)));
-          }
-          {
-            name = "windows_i686_gnu";
-            packageId = "windows_i686_gnu";
-            target = {
-              target,
-              features,
-            }: (("x86" == target."arch" or null) && ("gnu" == target."env" or null) && (!("llvm" == target."abi" or null)) && (!(target."windows_raw_dylib" or false)));
-          }
-          {
-            name = "windows_i686_gnullvm";
-            packageId = "windows_i686_gnullvm";
-            target = {
-              target,
-              features,
-            }: (stdenv.hostPlatform.rust.rustcTarget == "i686-pc-windows-gnullvm");
-          }
-          {
-            name = "windows_i686_msvc";
-            packageId = "windows_i686_msvc";
-            target = {
-              target,
-              features,
-            }: (("x86" == target."arch" or null) && ("msvc" == target."env" or null) && (!(target."windows_raw_dylib" or false)));
-          }
-          {
-            name = "windows_x86_64_gnu";
-            packageId = "windows_x86_64_gnu";
-            target = {
-              target,
-              features,
-            }: (("x86_64" == target."arch" or null) && ("gnu" == target."env" or null) && (!("llvm" == target."abi" or null)) && (!(target."windows_raw_dylib" or false)));
-          }
-          {
-            name = "windows_x86_64_gnullvm";
-            packageId = "windows_x86_64_gnullvm";
-            target = {
-              target,
-              features,
-            }: (stdenv.hostPlatform.rust.rustcTarget == "x86_64-pc-windows-gnullvm");
-          }
-          {
-            name = "windows_x86_64_msvc";
-            packageId = "windows_x86_64_msvc";
-            target = {
-              target,
-              features,
-            }: ((("x86_64" == target."arch" or null) || ("arm64ec" == target."arch" or null)) && ("msvc" == target."env" or null) && (!(target."windows_raw_dylib" or false)));
-          }
-        ];
-      };
-      "windows_aarch64_gnullvm" = rec {
-        crateName = "windows_aarch64_gnullvm";
-        version = "0.52.6";
-        edition = "2021";
-        sha256 = "1lrcq38cr2arvmz19v32qaggvj8bh1640mdm9c2fr877h0hn591j";
-        authors = [
-          "Microsoft"
-        ];
-      };
-      "windows_aarch64_msvc" = rec {
-        crateName = "windows_aarch64_msvc";
-        version = "0.52.6";
-        edition = "2021";
-        sha256 = "0sfl0nysnz32yyfh773hpi49b1q700ah6y7sacmjbqjjn5xjmv09";
-        authors = [
-          "Microsoft"
-        ];
-      };
-      "windows_i686_gnu" = rec {
-        crateName = "windows_i686_gnu";
-        version = "0.52.6";
-        edition = "2021";
-        sha256 = "02zspglbykh1jh9pi7gn8g1f97jh1rrccni9ivmrfbl0mgamm6wf";
-        authors = [
-          "Microsoft"
-        ];
-      };
-      "windows_i686_gnullvm" = rec {
-        crateName = "windows_i686_gnullvm";
-        version = "0.52.6";
-        edition = "2021";
-        sha256 = "0rpdx1537mw6slcpqa0rm3qixmsb79nbhqy5fsm3q2q9ik9m5vhf";
-        authors = [
-          "Microsoft"
-        ];
-      };
-      "windows_i686_msvc" = rec {
-        crateName = "windows_i686_msvc";
-        version = "0.52.6";
-        edition = "2021";
-        sha256 = "0rkcqmp4zzmfvrrrx01260q3xkpzi6fzi2x2pgdcdry50ny4h294";
-        authors = [
-          "Microsoft"
-        ];
-      };
-      "windows_x86_64_gnu" = rec {
-        crateName = "windows_x86_64_gnu";
-        version = "0.52.6";
-        edition = "2021";
-        sha256 = "0y0sifqcb56a56mvn7xjgs8g43p33mfqkd8wj1yhrgxzma05qyhl";
-        authors = [
-          "Microsoft"
-        ];
-      };
-      "windows_x86_64_gnullvm" = rec {
-        crateName = "windows_x86_64_gnullvm";
-        version = "0.52.6";
-        edition = "2021";
-        sha256 = "03gda7zjx1qh8k9nnlgb7m3w3s1xkysg55hkd1wjch8pqhyv5m94";
-        authors = [
-          "Microsoft"
-        ];
-      };
-      "windows_x86_64_msvc" = rec {
-        crateName = "windows_x86_64_msvc";
-        version = "0.52.6";
-        edition = "2021";
-        sha256 = "1v7rb5cibyzx8vak29pdrk8nx9hycsjs4w0jgms08qk49jl6v7sq";
-        authors = [
-          "Microsoft"
-        ];
-      };
-    };
-
-    #
-    # crate2nix/default.nix (excerpt start)
-    #
-
-    /*
-     Target (platform) data for conditional dependencies.
-    This corresponds roughly to what buildRustCrate is setting.
-    */
-    makeDefaultTarget = platform: {
-      unix = platform.isUnix;
-      windows = platform.isWindows;
-      fuchsia = true;
-      test = false;
-
-      inherit
-        (platform.rust.platform)
-        arch
-        os
-        vendor
-        ;
-      family = platform.rust.platform.target-family;
-      env = "gnu";
-      endian =
-        if platform.parsed.cpu.significantByte.name == "littleEndian"
-        then "little"
-        else "big";
-      pointer_width = toString platform.parsed.cpu.bits;
-      debug_assertions = false;
-    };
-
-    /*
-    Filters common temp files and build files.
-    */
-    # TODO(pkolloch): Substitute with gitignore filter
-    sourceFilter = name: type: let
-      baseName = builtins.baseNameOf (builtins.toString name);
-    in
-      ! (
-        # Filter out git
-        baseName
-        == ".gitignore"
-        || (type == "directory" && baseName == ".git")
-        # Filter out build results
-        || (
-          type
-          == "directory"
-          && (
-            baseName
-            == "target"
-            || baseName == "_site"
-            || baseName == ".sass-cache"
-            || baseName == ".jekyll-metadata"
-            || baseName == "build-artifacts"
-          )
-        )
-        # Filter out nix-build result symlinks
-        || (
-          type == "symlink" && lib.hasPrefix "result" baseName
-        )
-        # Filter out IDE config
-        || (
-          type
-          == "directory"
-          && (
-            baseName == ".idea" || baseName == ".vscode"
-          )
-        )
-        || lib.hasSuffix ".iml" baseName
-        # Filter out nix build files
-        || baseName == "Cargo.nix"
-        # Filter out editor backup / swap files.
-        || lib.hasSuffix "~" baseName
-        || builtins.match "^\\.sw[a-z]$$" baseName != null
-        || builtins.match "^\\..*\\.sw[a-z]$$" baseName != null
-        || lib.hasSuffix ".tmp" baseName
-        || lib.hasSuffix ".bak" baseName
-        || baseName == "tests.nix"
-      );
-
-    /*
-     Returns a crate which depends on successful test execution
-    of crate given as the second argument.
-
-    testCrateFlags: list of flags to pass to the test exectuable
-    testInputs: list of packages that should be available during test execution
-    */
-    crateWithTest = {
-      crate,
-      testCrate,
-      testCrateFlags,
-      testInputs,
-      testPreRun,
-      testPostRun,
-    }:
-      assert builtins.typeOf testCrateFlags == "list";
-      assert builtins.typeOf testInputs == "list";
-      assert builtins.typeOf testPreRun == "string";
-      assert builtins.typeOf testPostRun == "string"; let
-        # override the `crate` so that it will build and execute tests instead of
-        # building the actual lib and bin targets We just have to pass `--test`
-        # to rustc and it will do the right thing.  We execute the tests and copy
-        # their log and the test executables to $out for later inspection.
-        test = let
-          drv =
-            testCrate.override
-            (
-              _: {
-                buildTests = true;
-              }
-            );
-          # If the user hasn't set any pre/post commands, we don't want to
-          # insert empty lines. This means that any existing users of crate2nix
-          # don't get a spurious rebuild unless they set these explicitly.
-          testCommand =
-            pkgs.lib.concatStringsSep "\n"
-            (pkgs.lib.filter (s: s != "") [
-              testPreRun
-              "$f $testCrateFlags 2>&1 | tee -a $out"
-              testPostRun
-            ]);
-        in
-          pkgs.runCommand "run-tests-${testCrate.name}"
-          {
-            inherit testCrateFlags;
-            buildInputs = testInputs;
-          } ''
-            set -e
-
-            export RUST_BACKTRACE=1
-
-            # recreate a file hierarchy as when running tests with cargo
-
-            # the source for test data
-            # It's necessary to locate the source in $NIX_BUILD_TOP/source/
-            # instead of $NIX_BUILD_TOP/
-            # because we compiled those test binaries in the former and not the latter.
-            # So all paths will expect source tree to be there and not in the build top directly.
-            # For example: $NIX_BUILD_TOP := /build in general, if you ask yourself.
-            # NOTE: There could be edge cases if `crate.sourceRoot` does exist but
-            # it's very hard to reason about them.
-            # Open a bug if you run into this!
-            mkdir -p source/
-            cd source/
-
-            ${pkgs.buildPackages.xorg.lndir}/bin/lndir ${crate.src}
-
-            # build outputs
-            testRoot=target/debug
-            mkdir -p $testRoot
-
-            # executables of the crate
-            # we copy to prevent std::env::current_exe() to resolve to a store location
-            for i in ${crate}/bin/*; do
-              cp "$i" "$testRoot"
-            done
-            chmod +w -R .
-
-            # test harness executables are suffixed with a hash, like cargo does
-            # this allows to prevent name collision with the main
-            # executables of the crate
-            hash=$(basename $out)
-            for file in ${drv}/tests/*; do
-              f=$testRoot/$(basename $file)-$hash
-              cp $file $f
-              ${testCommand}
-            done
-          '';
-      in
-        pkgs.runCommand "${crate.name}-linked"
-        {
-          inherit (crate) outputs crateName;
-          passthru =
-            (crate.passthru or {})
-            // {
-              inherit test;
-            };
-        }
-        (lib.optionalString (stdenv.buildPlatform.canExecute stdenv.hostPlatform) ''
-            echo tested by ${test}
-          ''
-          + ''
-            ${lib.concatMapStringsSep "\n" (output: "ln -s ${crate.${output}} ${"$"}${output}") crate.outputs}
-          '');
-
-    /*
-    A restricted overridable version of builtRustCratesWithFeatures.
-    */
-    buildRustCrateWithFeatures = {
-      packageId,
-      features ? rootFeatures,
-      crateOverrides ? defaultCrateOverrides,
-      buildRustCrateForPkgsFunc ? null,
-      runTests ? false,
-      testCrateFlags ? [],
-      testInputs ? [],
-      # Any command to run immediatelly before a test is executed.
-      testPreRun ? "",
-      # Any command run immediatelly after a test is executed.
-      testPostRun ? "",
-    }:
-      lib.makeOverridable
-      (
-        {
-          features,
-          crateOverrides,
-          runTests,
-          testCrateFlags,
-          testInputs,
-          testPreRun,
-          testPostRun,
-        }: let
-          buildRustCrateForPkgsFuncOverriden =
-            if buildRustCrateForPkgsFunc != null
-            then buildRustCrateForPkgsFunc
-            else
-              (
-                if crateOverrides == pkgs.defaultCrateOverrides
-                then buildRustCrateForPkgs
-                else
-                  pkgs:
-                    (buildRustCrateForPkgs pkgs).override {
-                      defaultCrateOverrides = crateOverrides;
-                    }
-              );
-          builtRustCrates = builtRustCratesWithFeatures {
-            inherit packageId features;
-            buildRustCrateForPkgsFunc = buildRustCrateForPkgsFuncOverriden;
-            runTests = false;
-          };
-          builtTestRustCrates = builtRustCratesWithFeatures {
-            inherit packageId features;
-            buildRustCrateForPkgsFunc = buildRustCrateForPkgsFuncOverriden;
-            runTests = true;
-          };
-          drv = builtRustCrates.crates.${packageId};
-          testDrv = builtTestRustCrates.crates.${packageId};
-          derivation =
-            if runTests
-            then
-              crateWithTest
-              {
-                crate = drv;
-                testCrate = testDrv;
-                inherit testCrateFlags testInputs testPreRun testPostRun;
-              }
-            else drv;
-        in
-          derivation
-      )
-      {inherit features crateOverrides runTests testCrateFlags testInputs testPreRun testPostRun;};
-
-    /*
-     Returns an attr set with packageId mapped to the result of buildRustCrateForPkgsFunc
-    for the corresponding crate.
-    */
-    builtRustCratesWithFeatures = {
-      packageId,
-      features,
-      crateConfigs ? crates,
-      buildRustCrateForPkgsFunc,
-      runTests,
-      makeTarget ? makeDefaultTarget,
-    } @ args:
-      assert (builtins.isAttrs crateConfigs);
-      assert (builtins.isString packageId);
-      assert (builtins.isList features);
-      assert (builtins.isAttrs (makeTarget stdenv.hostPlatform));
-      assert (builtins.isBool runTests); let
-        rootPackageId = packageId;
-        mergedFeatures =
-          mergePackageFeatures
-          (
-            args
-            // {
-              inherit rootPackageId;
-              target = makeTarget stdenv.hostPlatform // {test = runTests;};
-            }
-          );
-        # Memoize built packages so that reappearing packages are only built once.
-        builtByPackageIdByPkgs = mkBuiltByPackageIdByPkgs pkgs;
-        mkBuiltByPackageIdByPkgs = pkgs: let
-          self = {
-            crates = lib.mapAttrs (packageId: value: buildByPackageIdForPkgsImpl self pkgs packageId) crateConfigs;
-            target = makeTarget stdenv.hostPlatform;
-            build = mkBuiltByPackageIdByPkgs pkgs.buildPackages;
-          };
-        in
-          self;
-        buildByPackageIdForPkgsImpl = self: pkgs: packageId: let
-          features = mergedFeatures."${packageId}" or [];
-          crateConfig' = crateConfigs."${packageId}";
-          crateConfig =
-            builtins.removeAttrs crateConfig' ["resolvedDefaultFeatures" "devDependencies"];
-          devDependencies =
-            lib.optionals
-            (runTests && packageId == rootPackageId)
-            (crateConfig'.devDependencies or []);
-          dependencies = dependencyDerivations {
-            inherit features;
-            inherit (self) target;
-            buildByPackageId = depPackageId:
-            # proc_macro crates must be compiled for the build architecture
-              if crateConfigs.${depPackageId}.procMacro or false
-              then self.build.crates.${depPackageId}
-              else self.crates.${depPackageId};
-            dependencies =
-              (crateConfig.dependencies or [])
-              ++ devDependencies;
-          };
-          buildDependencies = dependencyDerivations {
-            inherit features;
-            inherit (self.build) target;
-            buildByPackageId = depPackageId:
-              self.build.crates.${depPackageId};
-            dependencies = crateConfig.buildDependencies or [];
-          };
-          dependenciesWithRenames = let
-            buildDeps = filterEnabledDependencies {
-              inherit features;
-              inherit (self) target;
-              dependencies = crateConfig.dependencies or [] ++ devDependencies;
-            };
-            hostDeps = filterEnabledDependencies {
-              inherit features;
-              inherit (self.build) target;
-              dependencies = crateConfig.buildDependencies or [];
-            };
-          in
-            lib.filter (d: d ? "rename") (hostDeps ++ buildDeps);
-          # Crate renames have the form:
-          #
-          # {
-          #    crate_name = [
-          #       { version = "1.2.3"; rename = "crate_name01"; }
-          #    ];
-          #    # ...
-          # }
-          crateRenames = let
-            grouped =
-              lib.groupBy
-              (dependency: dependency.name)
-              dependenciesWithRenames;
-            versionAndRename = dep: let
-              package = crateConfigs."${dep.packageId}";
-            in {
-              inherit (dep) rename;
-              inherit (package) version;
-            };
-          in
-            lib.mapAttrs (name: builtins.map versionAndRename) grouped;
-        in
-          buildRustCrateForPkgsFunc pkgs
-          (
-            crateConfig
-            // {
-              src =
-                crateConfig.src or (
-                  pkgs.fetchurl rec {
-                    name = "${crateConfig.crateName}-${crateConfig.version}.tar.gz";
-                    # https://www.pietroalbini.org/blog/downloading-crates-io/
-                    # Not rate-limited, CDN URL.
-                    url = "https://static.crates.io/crates/${crateConfig.crateName}/${crateConfig.crateName}-${crateConfig.version}.crate";
-                    sha256 = assert (lib.assertMsg (crateConfig ? sha256) "Missing sha256 for ${name}");
-                      crateConfig.sha256;
-                  }
-                );
-              extraRustcOpts = lib.lists.optional (targetFeatures != []) "-C target-feature=${lib.concatMapStringsSep "," (x: "+${x}") targetFeatures}";
-              inherit features dependencies buildDependencies crateRenames release;
-            }
-          );
-      in
-        builtByPackageIdByPkgs;
-
-    /*
-    Returns the actual derivations for the given dependencies.
-    */
-    dependencyDerivations = {
-      buildByPackageId,
-      features,
-      dependencies,
-      target,
-    }:
-      assert (builtins.isList features);
-      assert (builtins.isList dependencies);
-      assert (builtins.isAttrs target); let
-        enabledDependencies = filterEnabledDependencies {
-          inherit dependencies features target;
-        };
-        depDerivation = dependency: buildByPackageId dependency.packageId;
-      in
-        map depDerivation enabledDependencies;
-
-    /*
-     Returns a sanitized version of val with all values substituted that cannot
-    be serialized as JSON.
-    */
-    sanitizeForJson = val:
-      if builtins.isAttrs val
-      then lib.mapAttrs (n: sanitizeForJson) val
-      else if builtins.isList val
-      then builtins.map sanitizeForJson val
-      else if builtins.isFunction val
-      then "function"
-      else val;
-
-    /*
-    Returns various tools to debug a crate.
-    */
-    debugCrate = {
-      packageId,
-      target ? makeDefaultTarget stdenv.hostPlatform,
-    }:
-      assert (builtins.isString packageId); let
-        debug = rec {
-          # The built tree as passed to buildRustCrate.
-          buildTree = buildRustCrateWithFeatures {
-            buildRustCrateForPkgsFunc = _: lib.id;
-            inherit packageId;
-          };
-          sanitizedBuildTree = sanitizeForJson buildTree;
-          dependencyTree =
-            sanitizeForJson
-            (
-              buildRustCrateWithFeatures {
-                buildRustCrateForPkgsFunc = _: crate: {
-                  "01_crateName" = crate.crateName or false;
-                  "02_features" = crate.features or [];
-                  "03_dependencies" = crate.dependencies or [];
-                };
-                inherit packageId;
-              }
-            );
-          mergedPackageFeatures = mergePackageFeatures {
-            features = rootFeatures;
-            inherit packageId target;
-          };
-          diffedDefaultPackageFeatures = diffDefaultPackageFeatures {
-            inherit packageId target;
-          };
-        };
-      in {internal = debug;};
-
-    /*
-     Returns differences between cargo default features and crate2nix default
-    features.
-
-    This is useful for verifying the feature resolution in crate2nix.
-    */
-    diffDefaultPackageFeatures = {
-      crateConfigs ? crates,
-      packageId,
-      target,
-    }:
-      assert (builtins.isAttrs crateConfigs); let
-        prefixValues = prefix: lib.mapAttrs (n: v: {"${prefix}" = v;});
-        mergedFeatures =
-          prefixValues
-          "crate2nix"
-          (mergePackageFeatures {
-            inherit crateConfigs packageId target;
-            features = ["default"];
-          });
-        configs = prefixValues "cargo" crateConfigs;
-        combined = lib.foldAttrs (a: b: a // b) {} [mergedFeatures configs];
-        onlyInCargo =
-          builtins.attrNames
-          (lib.filterAttrs (n: v: !(v ? "crate2nix") && (v ? "cargo")) combined);
-        onlyInCrate2Nix =
-          builtins.attrNames
-          (lib.filterAttrs (n: v: (v ? "crate2nix") && !(v ? "cargo")) combined);
-        differentFeatures =
-          lib.filterAttrs
-          (
-            n: v:
-              (v ? "crate2nix")
-              && (v ? "cargo")
-              && (v.crate2nix.features or []) != (v."cargo".resolved_default_features or [])
-          )
-          combined;
-      in
-        builtins.toJSON {
-          inherit onlyInCargo onlyInCrate2Nix differentFeatures;
-        };
-
-    /*
-     Returns an attrset mapping packageId to the list of enabled features.
-
-    If multiple paths to a dependency enable different features, the
-    corresponding feature sets are merged. Features in rust are additive.
-    */
-    mergePackageFeatures = {
-      crateConfigs ? crates,
-      packageId,
-      rootPackageId ? packageId,
-      features ? rootFeatures,
-      dependencyPath ? [crates.${packageId}.crateName],
-      featuresByPackageId ? {},
-      target,
-      # Adds devDependencies to the crate with rootPackageId.
-      runTests ? false,
-      ...
-    } @ args:
-      assert (builtins.isAttrs crateConfigs);
-      assert (builtins.isString packageId);
-      assert (builtins.isString rootPackageId);
-      assert (builtins.isList features);
-      assert (builtins.isList dependencyPath);
-      assert (builtins.isAttrs featuresByPackageId);
-      assert (builtins.isAttrs target);
-      assert (builtins.isBool runTests); let
-        crateConfig = crateConfigs."${packageId}" or (builtins.throw "Package not found: ${packageId}");
-        expandedFeatures = expandFeatures (crateConfig.features or {}) features;
-        enabledFeatures = enableFeatures (crateConfig.dependencies or []) expandedFeatures;
-        depWithResolvedFeatures = dependency: let
-          inherit (dependency) packageId;
-          features = dependencyFeatures enabledFeatures dependency;
-        in {inherit packageId features;};
-        resolveDependencies = cache: path: dependencies:
-          assert (builtins.isAttrs cache);
-          assert (builtins.isList dependencies); let
-            enabledDependencies = filterEnabledDependencies {
-              inherit dependencies target;
-              features = enabledFeatures;
-            };
-            directDependencies = map depWithResolvedFeatures enabledDependencies;
-            foldOverCache = op: lib.foldl op cache directDependencies;
-          in
-            foldOverCache
-            (
-              cache: {
-                packageId,
-                features,
-              }: let
-                cacheFeatures = cache.${packageId} or [];
-                combinedFeatures = sortedUnique (cacheFeatures ++ features);
-              in
-                if cache ? ${packageId} && cache.${packageId} == combinedFeatures
-                then cache
-                else
-                  mergePackageFeatures {
-                    features = combinedFeatures;
-                    featuresByPackageId = cache;
-                    inherit crateConfigs packageId target runTests rootPackageId;
-                  }
-            );
-        cacheWithSelf = let
-          cacheFeatures = featuresByPackageId.${packageId} or [];
-          combinedFeatures = sortedUnique (cacheFeatures ++ enabledFeatures);
-        in
-          featuresByPackageId
-          // {
-            "${packageId}" = combinedFeatures;
-          };
-        cacheWithDependencies =
-          resolveDependencies cacheWithSelf "dep"
-          (
-            crateConfig.dependencies or []
-            ++ lib.optionals
-            (runTests && packageId == rootPackageId)
-            (crateConfig.devDependencies or [])
-          );
-        cacheWithAll =
-          resolveDependencies
-          cacheWithDependencies "build"
-          (crateConfig.buildDependencies or []);
-      in
-        cacheWithAll;
-
-    /*
-    Returns the enabled dependencies given the enabled features.
-    */
-    filterEnabledDependencies = {
-      dependencies,
-      features,
-      target,
-    }:
-      assert (builtins.isList dependencies);
-      assert (builtins.isList features);
-      assert (builtins.isAttrs target);
-        lib.filter
-        (
-          dep: let
-            targetFunc = dep.target or (features: true);
-          in
-            targetFunc {inherit features target;}
-            && (
-              !(dep.optional or false)
-              || builtins.any (doesFeatureEnableDependency dep) features
-            )
-        )
-        dependencies;
-
-    /*
-    Returns whether the given feature should enable the given dependency.
-    */
-    doesFeatureEnableDependency = dependency: feature: let
-      name = dependency.rename or dependency.name;
-      prefix = "${name}/";
-      len = builtins.stringLength prefix;
-      startsWithPrefix = builtins.substring 0 len feature == prefix;
-    in
-      feature == name || feature == "dep:" + name || startsWithPrefix;
-
-    /*
-     Returns the expanded features for the given inputFeatures by applying the
-    rules in featureMap.
-
-    featureMap is an attribute set which maps feature names to lists of further
-    feature names to enable in case this feature is selected.
-    */
-    expandFeatures = featureMap: inputFeatures:
-      assert (builtins.isAttrs featureMap);
-      assert (builtins.isList inputFeatures); let
-        expandFeaturesNoCycle = oldSeen: inputFeatures:
-          if inputFeatures != []
-          then let
-            # The feature we're currently expanding.
-            feature = builtins.head inputFeatures;
-            # All the features we've seen/expanded so far, including the one
-            # we're currently processing.
-            seen = oldSeen // {${feature} = 1;};
-            # Expand the feature but be careful to not re-introduce a feature
-            # that we've already seen: this can easily cause a cycle, see issue
-            # #209.
-            enables = builtins.filter (f: !(seen ? "${f}")) (featureMap."${feature}" or []);
-          in
-            [feature] ++ (expandFeaturesNoCycle seen (builtins.tail inputFeatures ++ enables))
-          # No more features left, nothing to expand to.
-          else [];
-        outFeatures = expandFeaturesNoCycle {} inputFeatures;
-      in
-        sortedUnique outFeatures;
-
-    /*
-     This function adds optional dependencies as features if they are enabled
-    indirectly by dependency features. This function mimics Cargo's behavior
-    described in a note at:
-    https://doc.rust-lang.org/nightly/cargo/reference/features.html#dependency-features
-    */
-    enableFeatures = dependencies: features:
-      assert (builtins.isList features);
-      assert (builtins.isList dependencies); let
-        additionalFeatures =
-          lib.concatMap
-          (
-            dependency:
-              assert (builtins.isAttrs dependency); let
-                enabled = builtins.any (doesFeatureEnableDependency dependency) features;
-              in
-                if (dependency.optional or false) && enabled
-                then [(dependency.rename or dependency.name)]
-                else []
-          )
-          dependencies;
-      in
-        sortedUnique (features ++ additionalFeatures);
-
-    /*
-    Returns the actual features for the given dependency.
-
-    features: The features of the crate that refers this dependency.
-    */
-    dependencyFeatures = features: dependency:
-      assert (builtins.isList features);
-      assert (builtins.isAttrs dependency); let
-        defaultOrNil =
-          if dependency.usesDefaultFeatures or true
-          then ["default"]
-          else [];
-        explicitFeatures = dependency.features or [];
-        additionalDependencyFeatures = let
-          name = dependency.rename or dependency.name;
-          stripPrefixMatch = prefix: s:
-            if lib.hasPrefix prefix s
-            then lib.removePrefix prefix s
-            else null;
-          extractFeature = feature:
-            lib.findFirst
-            (f: f != null)
-            null
-            (map (prefix: stripPrefixMatch prefix feature) [
-              (name + "/")
-              (name + "?/")
-            ]);
-          dependencyFeatures = lib.filter (f: f != null) (map extractFeature features);
-        in
-          dependencyFeatures;
-      in
-        defaultOrNil ++ explicitFeatures ++ additionalDependencyFeatures;
-
-    /*
-    Sorts and removes duplicates from a list of strings.
-    */
-    sortedUnique = features:
-      assert (builtins.isList features);
-      assert (builtins.all builtins.isString features); let
-        outFeaturesSet = lib.foldl (set: feature: set // {"${feature}" = 1;}) {} features;
-        outFeaturesUnique = builtins.attrNames outFeaturesSet;
-      in
-        builtins.sort (a: b: a < b) outFeaturesUnique;
-
-    deprecationWarning = message: value:
-      if strictDeprecation
-      then builtins.throw "strictDeprecation enabled, aborting: ${message}"
-      else builtins.trace message value;
-
-    #
-    # crate2nix/default.nix (excerpt end)
-    #
-  };
-}
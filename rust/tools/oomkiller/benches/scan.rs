@@ -0,0 +1,83 @@
+//! Cost of one process scan, as done on every iteration that breaches the threshold.
+//!
+//! Run with `cargo bench --bench scan`. Prints wall time, heap allocations and bytes
+//! allocated per scan for sysinfo's `refresh_all`, for the direct `/proc` scanner, and
+//! for the scanner plus the `smaps_rollup` reads that PSS/USS scoring adds.
+
+use oomkiller::{Host, ProcessTable, SysinfoProcesses, Target};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+struct Counting;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        // SAFETY: forwarded unchanged to the system allocator.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: `ptr` came from `alloc` above.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        // SAFETY: forwarded unchanged to the system allocator.
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const ITERATIONS: u32 = 50;
+
+/// Refreshes and lists candidates `ITERATIONS` times after one warm-up scan, reading
+/// every candidate's `smaps_rollup` too if `smaps` is set.
+fn bench(name: &str, table: &mut dyn ProcessTable, target: Target, smaps: bool) {
+    let scan = |table: &mut dyn ProcessTable| {
+        table.refresh(target);
+        for process in table.candidates(target).unwrap() {
+            if smaps {
+                black_box(table.smaps_rollup(process.pid));
+            }
+        }
+    };
+    scan(table);
+    let listed = table.candidates(target).unwrap().len();
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = BYTES.load(Ordering::Relaxed);
+    let started = Instant::now();
+    for _ in 0..ITERATIONS {
+        scan(table);
+    }
+    let elapsed = started.elapsed() / ITERATIONS;
+    let per_scan = |counter: &AtomicU64, before: u64| {
+        (counter.load(Ordering::Relaxed) - before) / u64::from(ITERATIONS)
+    };
+    println!(
+        "{name:<10} {target:<5} {listed:>5} processes  {:>9.2} ms  {:>7} allocs  {:>10} bytes",
+        elapsed.as_secs_f64() * 1000.0,
+        per_scan(&ALLOCATIONS, allocations),
+        per_scan(&BYTES, bytes),
+        target = target.as_str(),
+    );
+}
+
+fn main() {
+    for target in [Target::User, Target::All] {
+        bench("sysinfo", &mut SysinfoProcesses::default(), target, false);
+        bench("procfs", Host::default().processes.as_mut(), target, false);
+        bench("+smaps", Host::default().processes.as_mut(), target, true);
+    }
+}
@@ -1,19 +1,20 @@
-# Make the oomkiller tool available in the system.
+# Make the oomkiller tool available in the system (buildRustPackage).
 {
   lib,
-  pkgs,
-  ...
-}: let
-  cargoNix = import ./Cargo.nix {
-    inherit pkgs;
-    defaultCrateOverrides = pkgs.defaultCrateOverrides;
+  rustPlatform,
+}:
+rustPlatform.buildRustPackage {
+  pname = "oomkiller";
+  version = "0.1.0";
+
+  src = ./.;
+
+  cargoLock.lockFile = ./Cargo.lock;
+
+  meta = with lib; {
+    description = "A daemon that monitors system memory and kills the highest memory-consuming process when memory usage exceeds a configurable threshold";
+    license = licenses.mit;
+    mainProgram = "oomkiller";
+    platforms = platforms.linux;
   };
-in
-  cargoNix.workspaceMembers.oomkiller.build.overrideAttrs (oldAttrs: {
-    meta = with lib; {
-      description = "A daemon that monitors system memory and kills the highest memory-consuming process when memory usage exceeds 90%";
-      homepage = "";
-      license = licenses.mit;
-      maintainers = [];
-    };
-  })
+}
@@ -0,0 +1,391 @@
+use crate::{format_bytes, smaps::MemoryMetric, types::ProcessInfo};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Regexes matched against the process name (comm), compiled when the config is parsed.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(try_from = "Vec<String>")]
+pub struct Patterns(Vec<Regex>);
+
+impl Patterns {
+    pub fn new(patterns: &[&str]) -> Result<Self, String> {
+        Self::try_from(patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>())
+    }
+
+    pub fn is_match(&self, name: &str) -> bool {
+        self.0.iter().any(|re| re.is_match(name))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl TryFrom<Vec<String>> for Patterns {
+    type Error = String;
+
+    fn try_from(patterns: Vec<String>) -> Result<Self, String> {
+        patterns
+            .iter()
+            .map(|p| Regex::new(p).map_err(|e| format!("invalid regex '{p}': {e}")))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl PartialEq for Patterns {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+            && self
+                .0
+                .iter()
+                .zip(&other.0)
+                .all(|(a, b)| a.as_str() == b.as_str())
+    }
+}
+
+/// `[scoring]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoringConfig {
+    /// Which memory figure (plus swap) a process is scored and reported by.
+    pub memory_metric: MemoryMetric,
+    /// Multiplier for that memory expressed in permille of total RAM.
+    pub rss_weight: f64,
+    /// Multiplier for the kernel's `oom_score` (0..=2000).
+    pub oom_score_weight: f64,
+    /// Added to processes matching `prefer`.
+    pub prefer_bonus: i64,
+    /// Subtracted from processes matching `avoid`.
+    pub avoid_penalty: i64,
+    /// Names that should go first (build workers, compilers, runaway runtimes).
+    pub prefer: Patterns,
+    /// Names that should only go when nothing else is left (the desktop itself).
+    pub avoid: Patterns,
+    /// Names that are never killed.
+    pub never: Patterns,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self {
+            memory_metric: MemoryMetric::default(),
+            rss_weight: 1.0,
+            oom_score_weight: 1.0,
+            prefer_bonus: 300,
+            avoid_penalty: 300,
+            prefer: Patterns::new(&[r"^(cc1|cc1plus|rustc|ld|ld\.lld|clang(\+\+)?|node|java|nix)$"])
+                .expect("default prefer pattern"),
+            avoid: Patterns::new(&[
+                r"^(gnome-shell|Xwayland|kwin_wayland|plasmashell|pipewire|pipewire-pulse|wireplumber|ssh|sshd)$",
+            ])
+            .expect("default avoid pattern"),
+            never: Patterns::new(&[r"^(systemd|dbus-daemon|dbus-broker|oomkiller)$"])
+                .expect("default never pattern"),
+        }
+    }
+}
+
+impl ScoringConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        for (key, value) in [
+            ("scoring.rss_weight", self.rss_weight),
+            ("scoring.oom_score_weight", self.oom_score_weight),
+        ] {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(format!("{key} must be a non-negative number, got {value}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Which name rule, if any, applied to a process. `Never` beats `Avoid` beats `Prefer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rule {
+    None,
+    Prefer,
+    Avoid,
+    Never,
+    /// Protected at runtime with `oomkiller ctl protect`; set after scoring.
+    Protected,
+    /// Growing steadily per `[leak]`; set after scoring, on top of any `prefer` bonus.
+    Leaking,
+}
+
+impl Rule {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "-",
+            Self::Prefer => "prefer",
+            Self::Avoid => "avoid",
+            Self::Never => "never",
+            Self::Protected => "protected",
+            Self::Leaking => "leaking",
+        }
+    }
+}
+
+/// Score breakdown for one process; `total` is the sum of the parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Badness {
+    /// Bytes `mem_points` come from: the process's footprint under `memory_metric`,
+    /// i.e. what killing it is expected to free.
+    pub memory: u64,
+    pub mem_points: i64,
+    pub oom_points: i64,
+    pub adj_points: i64,
+    pub rule: Rule,
+    pub rule_points: i64,
+    pub total: i64,
+}
+
+/// A candidate process together with its score.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub process: ProcessInfo,
+    pub badness: Badness,
+}
+
+impl Candidate {
+    /// False for processes protected by a `never` rule or `oomkiller ctl protect`.
+    pub fn is_killable(&self) -> bool {
+        !matches!(self.badness.rule, Rule::Never | Rule::Protected)
+    }
+}
+
+/// Scores a process earlyoom-style.
+///
+/// `rss_weight * (memory + swap)‰ of total RAM + oom_score_weight * oom_score + oom_score_adj`,
+/// then `prefer_bonus` / `-avoid_penalty` from the name rules, where memory is
+/// RSS, PSS or USS per `memory_metric`. `oom_score_adj` is added on its own so
+/// that units configured with e.g. `OOMScoreAdjust=-900` stay protected even
+/// when they are large. Swapped-out memory counts like resident memory, since
+/// on zram hosts it still occupies (compressed) RAM.
+pub fn score_process(process: &ProcessInfo, total_memory: u64, config: &ScoringConfig) -> Badness {
+    let memory = process.footprint(config.memory_metric);
+    let mem_permille = if total_memory == 0 {
+        0.0
+    } else {
+        memory as f64 * 1000.0 / total_memory as f64
+    };
+    let mem_points = (mem_permille * config.rss_weight).round() as i64;
+    let oom_points = (f64::from(process.oom_score) * config.oom_score_weight).round() as i64;
+    let adj_points = i64::from(process.oom_score_adj);
+
+    let rule = if config.never.is_match(&process.name) {
+        Rule::Never
+    } else if config.avoid.is_match(&process.name) {
+        Rule::Avoid
+    } else if config.prefer.is_match(&process.name) {
+        Rule::Prefer
+    } else {
+        Rule::None
+    };
+    let rule_points = match rule {
+        Rule::Prefer => config.prefer_bonus,
+        Rule::Avoid => -config.avoid_penalty,
+        Rule::None | Rule::Never | Rule::Protected | Rule::Leaking => 0,
+    };
+
+    Badness {
+        memory,
+        mem_points,
+        oom_points,
+        adj_points,
+        rule,
+        rule_points,
+        total: mem_points + oom_points + adj_points + rule_points,
+    }
+}
+
+/// Scores every process and sorts by badness (highest first, larger footprint breaks ties).
+/// Processes matching `never` are kept, but sorted last, so `--explain` can show them.
+pub fn rank_candidates(
+    processes: Vec<ProcessInfo>,
+    total_memory: u64,
+    config: &ScoringConfig,
+) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = processes
+        .into_iter()
+        .map(|process| Candidate {
+            badness: score_process(&process, total_memory, config),
+            process,
+        })
+        .collect();
+    sort_candidates(&mut candidates);
+    candidates
+}
+
+/// Sorts killable candidates first, then by badness and footprint, highest first.
+pub fn sort_candidates(candidates: &mut [Candidate]) {
+    candidates.sort_by(|a, b| {
+        b.is_killable()
+            .cmp(&a.is_killable())
+            .then(b.badness.total.cmp(&a.badness.total))
+            .then(b.badness.memory.cmp(&a.badness.memory))
+    });
+}
+
+impl Badness {
+    /// `total = mem + oom + adj + rule (rule name)`, as shown by `--explain` and dry-run logs.
+    pub fn breakdown(&self) -> String {
+        format!(
+            "{} = {} + {} + {} + {} ({})",
+            self.total,
+            self.mem_points,
+            self.oom_points,
+            self.adj_points,
+            self.rule_points,
+            self.rule.as_str()
+        )
+    }
+}
+
+/// Header matching the columns of [`explain_candidate`].
+pub const EXPLAIN_HEADER: &str = "     PID  NAME                    RSS        SWAP       FREES   SCORE = MEM + OOM + ADJ + RULE";
+
+/// One `--explain` line: identity, RSS, swap, expected freed memory and the score breakdown.
+pub fn explain_candidate(candidate: &Candidate) -> String {
+    let p = &candidate.process;
+    let b = &candidate.badness;
+    let total = if candidate.is_killable() {
+        b.total.to_string()
+    } else {
+        b.rule.as_str().to_string()
+    };
+    format!(
+        "{:>8}  {:<15}  {:>10}  {:>10}  {:>10}  {:>6} = {} + {} + {} + {} ({})",
+        p.pid,
+        p.name,
+        format_bytes(p.memory),
+        format_bytes(p.swap),
+        format_bytes(b.memory),
+        total,
+        b.mem_points,
+        b.oom_points,
+        b.adj_points,
+        b.rule_points,
+        b.rule.as_str(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    fn process(name: &str, memory: u64, oom_score: i32, oom_score_adj: i32) -> ProcessInfo {
+        ProcessInfo {
+            pid: 100,
+            memory,
+            name: name.to_string(),
+            oom_score,
+            oom_score_adj,
+            ..ProcessInfo::default()
+        }
+    }
+
+    #[test]
+    fn test_score_process_sums_parts() {
+        let b = score_process(
+            &process("firefox", 4 * GIB, 250, 100),
+            16 * GIB,
+            &ScoringConfig::default(),
+        );
+        assert_eq!(b.mem_points, 250);
+        assert_eq!(b.oom_points, 250);
+        assert_eq!(b.adj_points, 100);
+        assert_eq!(b.rule, Rule::None);
+        assert_eq!(b.total, 600);
+    }
+
+    #[test]
+    fn test_score_process_applies_rules() {
+        let config = ScoringConfig::default();
+        let prefer = score_process(&process("cc1plus", GIB, 0, 0), 16 * GIB, &config);
+        assert_eq!(prefer.rule, Rule::Prefer);
+        assert_eq!(prefer.rule_points, 300);
+        let avoid = score_process(&process("gnome-shell", GIB, 0, 0), 16 * GIB, &config);
+        assert_eq!(avoid.rule, Rule::Avoid);
+        assert_eq!(avoid.rule_points, -300);
+        let never = score_process(&process("systemd", GIB, 0, 0), 16 * GIB, &config);
+        assert_eq!(never.rule, Rule::Never);
+    }
+
+    #[test]
+    fn test_rank_prefers_build_worker_over_larger_shell() {
+        let ranked = rank_candidates(
+            vec![
+                process("gnome-shell", 3 * GIB, 200, 0),
+                process("cc1plus", 2 * GIB, 130, 0),
+                process("systemd", 8 * GIB, 500, 0),
+            ],
+            16 * GIB,
+            &ScoringConfig::default(),
+        );
+        let names: Vec<_> = ranked.iter().map(|c| c.process.name.as_str()).collect();
+        assert_eq!(names, ["cc1plus", "gnome-shell", "systemd"]);
+        assert!(!ranked[2].is_killable());
+    }
+
+    #[test]
+    fn test_swapped_out_memory_counts() {
+        let swapped = ProcessInfo {
+            swap: 3 * GIB,
+            ..process("electron", GIB, 0, 0)
+        };
+        let ranked = rank_candidates(
+            vec![process("resident", 2 * GIB, 0, 0), swapped],
+            16 * GIB,
+            &ScoringConfig::default(),
+        );
+        assert_eq!(ranked[0].process.name, "electron");
+        assert_eq!(ranked[0].badness.mem_points, 250);
+    }
+
+    #[test]
+    fn test_oom_score_adj_protects_large_process() {
+        let ranked = rank_candidates(
+            vec![
+                process("important", 6 * GIB, 0, -900),
+                process("other", 2 * GIB, 0, 0),
+            ],
+            16 * GIB,
+            &ScoringConfig::default(),
+        );
+        assert_eq!(ranked[0].process.name, "other");
+    }
+
+    #[test]
+    fn test_patterns_from_toml_and_invalid_regex() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            p: Patterns,
+        }
+        let ok: Wrapper = toml::from_str("p = [\"^a$\", \"b\"]").unwrap();
+        assert!(ok.p.is_match("a") && ok.p.is_match("abc") && !ok.p.is_match("c"));
+        let err = toml::from_str::<Wrapper>("p = [\"(\"]").err().unwrap();
+        assert!(err.to_string().contains("invalid regex"), "{err}");
+    }
+
+    #[test]
+    fn test_explain_candidate_shows_breakdown() {
+        let candidate = Candidate {
+            badness: score_process(
+                &process("cc1plus", 2 * GIB, 130, 0),
+                16 * GIB,
+                &ScoringConfig::default(),
+            ),
+            process: process("cc1plus", 2 * GIB, 130, 0),
+        };
+        let line = explain_candidate(&candidate);
+        assert!(line.contains("cc1plus"), "{line}");
+        assert!(
+            line.contains("555 = 125 + 130 + 0 + 300 (prefer)"),
+            "{line}"
+        );
+    }
+}
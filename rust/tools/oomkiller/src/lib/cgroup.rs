@@ -0,0 +1,301 @@
+use crate::types::ProcessInfo;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Mount point of the unified (v2) cgroup hierarchy.
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Processes that share one systemd app unit (`app-*.scope`, or any unit under `app.slice`).
+#[derive(Debug, Clone, Default)]
+pub struct AppGroup {
+    /// Cgroup path of the unit relative to [`CGROUP_ROOT`], e.g.
+    /// `/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox-1234.scope`.
+    pub path: String,
+    /// `memory.current` of the unit (sum of member RSS if that cannot be read).
+    pub memory: u64,
+    /// Candidate processes inside the unit, largest RSS first.
+    pub processes: Vec<ProcessInfo>,
+}
+
+impl AppGroup {
+    /// Last path component, i.e. the systemd unit name.
+    pub fn unit(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+
+    /// Directory of the unit in the cgroup filesystem.
+    pub fn dir(&self) -> PathBuf {
+        Path::new(CGROUP_ROOT).join(self.path.trim_start_matches('/'))
+    }
+}
+
+/// Reads the cgroup v2 path of a process from `/proc/<pid>/cgroup`.
+pub fn read_cgroup_path(pid: u32) -> Result<String, String> {
+    let text = fs::read_to_string(format!("/proc/{pid}/cgroup"))
+        .map_err(|e| format!("Failed to read /proc/{pid}/cgroup: {e}"))?;
+    parse_cgroup_path(&text).ok_or_else(|| format!("pid {pid} has no cgroup v2 entry"))
+}
+
+/// Extracts the unified-hierarchy path (the `0::<path>` line) from `/proc/<pid>/cgroup`.
+pub fn parse_cgroup_path(text: &str) -> Option<String> {
+    text.lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(str::to_string)
+}
+
+/// Truncates a process's cgroup path to the app unit it belongs to.
+///
+/// The deepest `.scope` / `.service` component is the unit; it counts as an app
+/// when it is named `app-*` or sits below `app.slice`. Login sessions, `init.scope`
+/// and `user@UID.service` itself are never returned, so a whole session is never
+/// treated as one app.
+pub fn app_unit(path: &str) -> Option<&str> {
+    let (end, unit) = path
+        .match_indices('/')
+        .map(|(i, _)| i)
+        .chain([path.len()])
+        .zip(path.split('/'))
+        .filter(|(_, component)| component.ends_with(".scope") || component.ends_with(".service"))
+        .last()?;
+    let prefix = &path[..end];
+    let is_app = unit.starts_with("app-") || prefix.contains("/app.slice/");
+    is_app.then_some(prefix)
+}
+
+/// The systemd service a cgroup path belongs to, e.g. `syncthing.service`.
+///
+/// The deepest `.service` component wins; `user@UID.service` (the user manager)
+/// is not a service of its own here, so session and app-scope processes have none.
+pub fn service_unit(path: &str) -> Option<&str> {
+    path.rsplit('/')
+        .find(|c| c.ends_with(".service") && !c.starts_with("user@"))
+}
+
+/// Groups `(process, cgroup path)` pairs by app unit; processes outside an app unit are dropped.
+///
+/// `memory` is initialised to the sum of member RSS; callers replace it with
+/// `memory.current` when the cgroup can be read.
+pub fn group_by_app(processes: Vec<(ProcessInfo, String)>) -> Vec<AppGroup> {
+    let mut groups: Vec<AppGroup> = Vec::new();
+    for (process, cgroup) in processes {
+        let Some(unit_path) = app_unit(&cgroup) else {
+            continue;
+        };
+        let group = match groups.iter_mut().position(|g| g.path == unit_path) {
+            Some(i) => &mut groups[i],
+            None => {
+                groups.push(AppGroup {
+                    path: unit_path.to_string(),
+                    ..AppGroup::default()
+                });
+                groups.last_mut().expect("just pushed")
+            }
+        };
+        group.memory += process.memory;
+        group.processes.push(process);
+    }
+    for group in &mut groups {
+        group.processes.sort_by_key(|p| std::cmp::Reverse(p.memory));
+    }
+    groups
+}
+
+/// Reads `memory.current` (bytes charged to the cgroup, page cache included).
+pub fn read_memory_current(dir: &Path) -> Result<u64, String> {
+    let path = dir.join("memory.current");
+    fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?
+        .trim()
+        .parse()
+        .map_err(|e| format!("Malformed {}: {e}", path.display()))
+}
+
+/// True when the daemon may write `cgroup.kill` (kernel 5.14+, cgroup delegated to the user).
+pub fn is_delegated(dir: &Path) -> bool {
+    fs::OpenOptions::new()
+        .write(true)
+        .open(dir.join("cgroup.kill"))
+        .is_ok()
+}
+
+/// Reads the PIDs currently in the cgroup (`cgroup.procs`).
+pub fn read_cgroup_pids(dir: &Path) -> Result<Vec<u32>, String> {
+    let path = dir.join("cgroup.procs");
+    fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?
+        .lines()
+        .map(|line| {
+            line.trim()
+                .parse()
+                .map_err(|e| format!("Malformed {}: {e}", path.display()))
+        })
+        .collect()
+}
+
+/// False once every process in the cgroup (and its children) has exited (`cgroup.events`).
+pub fn is_populated(dir: &Path) -> Result<bool, String> {
+    let path = dir.join("cgroup.events");
+    let text =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    text.lines()
+        .find_map(|line| line.strip_prefix("populated "))
+        .map(|v| v.trim() != "0")
+        .ok_or_else(|| format!("Malformed {}", path.display()))
+}
+
+/// SIGKILLs every process in the cgroup at once by writing `1` to `cgroup.kill`.
+pub fn kill_cgroup(dir: &Path) -> Result<(), String> {
+    let path = dir.join("cgroup.kill");
+    fs::write(&path, "1").map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Freezes (`true`) or thaws every process in the cgroup through `cgroup.freeze` (Linux 5.2+).
+///
+/// Frozen processes keep their memory but stop running until thawed; `cgroup.kill`
+/// still works on a frozen cgroup.
+pub fn freeze_cgroup(dir: &Path, frozen: bool) -> Result<(), String> {
+    let path = dir.join("cgroup.freeze");
+    fs::write(&path, if frozen { "1" } else { "0" })
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIREFOX: &str =
+        "/user.slice/user-1000.slice/user@1000.service/app.slice/app-gnome-firefox-4242.scope";
+
+    fn process(pid: u32, memory: u64) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            memory,
+            name: format!("p{pid}"),
+            ..ProcessInfo::default()
+        }
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("oomkiller-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_parse_cgroup_path_picks_unified_line() {
+        let text = "12:memory:/legacy\n0::/user.slice/user-1000.slice/session-2.scope\n";
+        assert_eq!(
+            parse_cgroup_path(text).as_deref(),
+            Some("/user.slice/user-1000.slice/session-2.scope")
+        );
+        assert_eq!(parse_cgroup_path("1:name=systemd:/\n"), None);
+    }
+
+    #[test]
+    fn test_app_unit_recognises_app_scopes() {
+        assert_eq!(app_unit(FIREFOX), Some(FIREFOX));
+        let nested = "/user.slice/user-1000.slice/user@1000.service/app.slice/app-org.gnome.Terminal.slice/vte-spawn-1.scope";
+        assert_eq!(app_unit(nested), Some(nested));
+        let sub = "/user.slice/user-1000.slice/user@1000.service/app.slice/code.service/worker";
+        assert_eq!(
+            app_unit(sub),
+            Some("/user.slice/user-1000.slice/user@1000.service/app.slice/code.service")
+        );
+    }
+
+    #[test]
+    fn test_app_unit_rejects_sessions_and_services() {
+        assert_eq!(
+            app_unit("/user.slice/user-1000.slice/session-2.scope"),
+            None
+        );
+        assert_eq!(app_unit("/init.scope"), None);
+        assert_eq!(
+            app_unit("/user.slice/user-1000.slice/user@1000.service/init.scope"),
+            None
+        );
+        assert_eq!(app_unit("/system.slice/sshd.service"), None);
+        assert_eq!(app_unit("/"), None);
+    }
+
+    #[test]
+    fn test_service_unit_skips_user_manager() {
+        assert_eq!(
+            service_unit("/system.slice/nginx.service"),
+            Some("nginx.service")
+        );
+        assert_eq!(
+            service_unit(
+                "/user.slice/user-1000.slice/user@1000.service/app.slice/syncthing.service"
+            ),
+            Some("syncthing.service")
+        );
+        assert_eq!(
+            service_unit(
+                "/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox-1.scope"
+            ),
+            None
+        );
+        assert_eq!(
+            service_unit("/user.slice/user-1000.slice/session-2.scope"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_group_by_app_sums_members() {
+        let other = "/user.slice/user-1000.slice/user@1000.service/app.slice/app-code-1.scope";
+        let groups = group_by_app(vec![
+            (process(1, 100), FIREFOX.to_string()),
+            (process(2, 300), FIREFOX.to_string()),
+            (process(3, 50), other.to_string()),
+            (
+                process(4, 999),
+                "/user.slice/user-1000.slice/session-2.scope".to_string(),
+            ),
+        ]);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].unit(), "app-gnome-firefox-4242.scope");
+        assert_eq!(groups[0].memory, 400);
+        assert_eq!(groups[0].processes[0].pid, 2);
+        assert_eq!(groups[1].memory, 50);
+    }
+
+    #[test]
+    fn test_cgroup_files_round_trip() {
+        let dir = scratch_dir("cgroup-files");
+        fs::write(dir.join("memory.current"), "123456\n").unwrap();
+        fs::write(dir.join("cgroup.procs"), "10\n20\n").unwrap();
+        fs::write(dir.join("cgroup.events"), "populated 1\nfrozen 0\n").unwrap();
+        fs::write(dir.join("cgroup.kill"), "").unwrap();
+
+        assert_eq!(read_memory_current(&dir).unwrap(), 123456);
+        assert_eq!(read_cgroup_pids(&dir).unwrap(), vec![10, 20]);
+        assert!(is_populated(&dir).unwrap());
+        assert!(is_delegated(&dir));
+        kill_cgroup(&dir).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("cgroup.kill")).unwrap(), "1");
+        freeze_cgroup(&dir, true).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("cgroup.freeze")).unwrap(), "1");
+        freeze_cgroup(&dir, false).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("cgroup.freeze")).unwrap(), "0");
+
+        fs::write(dir.join("cgroup.events"), "populated 0\nfrozen 0\n").unwrap();
+        assert!(!is_populated(&dir).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_cgroup_is_not_delegated() {
+        assert!(!is_delegated(Path::new("/nonexistent/oomkiller")));
+        assert!(read_memory_current(Path::new("/nonexistent/oomkiller")).is_err());
+    }
+
+    #[test]
+    fn test_read_cgroup_path_own_process() {
+        // Only cgroup v2 (or hybrid) systems have a `0::` line.
+        if let Ok(path) = read_cgroup_path(std::process::id()) {
+            assert!(path.starts_with('/'));
+        }
+    }
+}
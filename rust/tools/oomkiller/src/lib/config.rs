@@ -0,0 +1,593 @@
+use crate::badness::ScoringConfig;
+use crate::control::ControlConfig;
+use crate::effective_memory::MemoryConfig;
+use crate::freeze::FreezeConfig;
+use crate::harden::HardeningConfig;
+use crate::journal::JournalConfig;
+use crate::kill_history::KillLoopConfig;
+use crate::leak::LeakConfig;
+use crate::metrics::MetricsConfig;
+use crate::nix_build::NixConfig;
+use crate::notify::NotifyConfig;
+use crate::poll_interval::PollConfig;
+use crate::process_tree::TreeConfig;
+use crate::psi::PsiConfig;
+use crate::snapshot::SnapshotConfig;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default memory threshold (override via config file, CLI or env).
+pub const MEMORY_THRESHOLD_PERCENT: f64 = 90.0;
+
+/// Default poll interval between memory checks.
+pub const DEFAULT_INTERVAL_SECS: f64 = 5.0;
+
+/// Which processes are eligible to be killed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Target {
+    /// Only processes owned by the user the daemon runs as.
+    #[default]
+    User,
+    /// Every process the daemon can see (needs privilege to signal them).
+    All,
+}
+
+impl Target {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "user" => Ok(Self::User),
+            "all" => Ok(Self::All),
+            other => Err(format!("unknown target '{other}', expected user|all")),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::All => "all",
+        }
+    }
+}
+
+/// What is chosen and killed: a single process or a whole app cgroup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    /// The highest-badness process.
+    #[default]
+    Process,
+    /// The app unit (`app-*.scope`) with the largest `memory.current`, killed via
+    /// `cgroup.kill`; falls back to `Process` when no unit is delegated.
+    Cgroup,
+}
+
+impl GroupBy {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "process" => Ok(Self::Process),
+            "cgroup" => Ok(Self::Cgroup),
+            other => Err(format!(
+                "unknown group_by '{other}', expected process|cgroup"
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Process => "process",
+            Self::Cgroup => "cgroup",
+        }
+    }
+}
+
+/// Which signal(s) decide that memory is critical.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TriggerMode {
+    /// Used memory percentage only.
+    #[default]
+    Percent,
+    /// PSI memory pressure only (falls back to percent when PSI is unavailable).
+    Psi,
+    /// Either check tripping is enough.
+    Either,
+    /// Both checks must trip.
+    Both,
+}
+
+impl TriggerMode {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "percent" => Ok(Self::Percent),
+            "psi" => Ok(Self::Psi),
+            "either" => Ok(Self::Either),
+            "both" => Ok(Self::Both),
+            other => Err(format!(
+                "unknown trigger '{other}', expected percent|psi|either|both"
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Percent => "percent",
+            Self::Psi => "psi",
+            Self::Either => "either",
+            Self::Both => "both",
+        }
+    }
+
+    pub fn uses_psi(self) -> bool {
+        self != Self::Percent
+    }
+}
+
+/// `[escalation]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EscalationConfig {
+    /// Send SIGTERM first and only SIGKILL after the grace period.
+    pub enabled: bool,
+    /// How long the victim gets to exit (or memory to recover) after SIGTERM.
+    pub grace_ms: u64,
+    /// How often memory is re-checked during the grace period.
+    pub poll_ms: u64,
+    /// PSI `full avg10` at or above which SIGTERM is skipped and SIGKILL sent at once.
+    pub critical_full_avg10: f64,
+}
+
+impl Default for EscalationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            grace_ms: 3000,
+            poll_ms: 100,
+            critical_full_avg10: 40.0,
+        }
+    }
+}
+
+impl EscalationConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.grace_ms > 60_000 {
+            return Err(format!(
+                "escalation.grace_ms must be at most 60000, got {}",
+                self.grace_ms
+            ));
+        }
+        if self.poll_ms == 0 || self.poll_ms > 5000 {
+            return Err(format!(
+                "escalation.poll_ms must be between 1 and 5000, got {}",
+                self.poll_ms
+            ));
+        }
+        if !(self.critical_full_avg10 > 0.0 && self.critical_full_avg10 <= 100.0) {
+            return Err(format!(
+                "escalation.critical_full_avg10 must be in (0, 100], got {}",
+                self.critical_full_avg10
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn grace(&self) -> Duration {
+        Duration::from_millis(self.grace_ms)
+    }
+
+    pub fn poll(&self) -> Duration {
+        Duration::from_millis(self.poll_ms)
+    }
+}
+
+/// Daemon configuration, read from a TOML file and overridable from CLI/env.
+///
+/// ```toml
+/// threshold_percent = 90.0
+/// interval_secs = 5.0
+/// target = "user"
+/// dry_run = false
+/// trigger = "either"
+/// group_by = "cgroup"
+///
+/// [poll]
+/// min_secs = 0.1
+/// max_secs = 10.0
+///
+/// [memory]
+/// accounting = "effective"
+///
+/// [psi]
+/// some_avg10 = 20.0
+/// full_avg10 = 5.0
+///
+/// [escalation]
+/// grace_ms = 3000
+///
+/// [notify]
+/// on_sigterm = true
+///
+/// [journal]
+/// path = "/var/lib/oomkiller/decisions.jsonl"
+///
+/// [metrics]
+/// textfile = "/var/lib/oomkiller/metrics/oomkiller.prom"
+///
+/// [kill_loop]
+/// action = "stop_unit"
+///
+/// [leak]
+/// enabled = true
+/// action = "kill"
+///
+/// [nix]
+/// enabled = true
+///
+/// [tree]
+/// enabled = true
+/// apps = ["^(make|ninja|cargo|nix)$"]
+///
+/// [snapshot]
+/// enabled = true
+/// keep = 50
+///
+/// [freeze]
+/// enabled = true
+/// apps = ["^(gimp|darktable)$"]
+///
+/// [scoring]
+/// memory_metric = "pss"
+/// prefer = ["^(cc1plus|rustc|node)$"]
+/// avoid = ["^gnome-shell$"]
+/// never = ["^sshd$"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Used memory percentage at or above which a process is killed.
+    pub threshold_percent: f64,
+    /// Seconds to sleep between iterations when `poll.adaptive` is off.
+    pub interval_secs: f64,
+    /// Sleep between iterations derived from headroom and memory trend.
+    pub poll: PollConfig,
+    /// Which processes may be chosen as victims.
+    pub target: Target,
+    /// Log the victim that would be chosen but never signal it.
+    pub dry_run: bool,
+    /// How the percent and PSI checks combine.
+    pub trigger: TriggerMode,
+    /// Kill single processes or whole app cgroups.
+    pub group_by: GroupBy,
+    /// What `threshold_percent` is measured against (RAM, or RAM + swap).
+    pub memory: MemoryConfig,
+    /// PSI limits and wake-up trigger.
+    pub psi: PsiConfig,
+    /// SIGTERM grace period before SIGKILL.
+    pub escalation: EscalationConfig,
+    /// Memory metric, badness weights and prefer/avoid/never name rules.
+    pub scoring: ScoringConfig,
+    /// Desktop notifications for kills.
+    pub notify: NotifyConfig,
+    /// JSON-lines log of every kill decision.
+    pub journal: JournalConfig,
+    /// Prometheus textfile and/or HTTP listener.
+    pub metrics: MetricsConfig,
+    /// mlockall and oom_score_adj for the daemon itself (startup only).
+    pub hardening: HardeningConfig,
+    /// Backing off from targets that come back right after being killed.
+    pub kill_loop: KillLoopConfig,
+    /// Unix socket for `oomkiller ctl` (startup only).
+    pub control: ControlConfig,
+    /// Freezing victims and asking before killing them.
+    pub freeze: FreezeConfig,
+    /// Reports of the memory state written before every kill.
+    pub snapshot: SnapshotConfig,
+    /// Killing the victim's descendants along with it.
+    pub tree: TreeConfig,
+    /// Catching processes that keep growing before memory runs out.
+    pub leak: LeakConfig,
+    /// Stopping Nix builds before interactive apps.
+    pub nix: NixConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            threshold_percent: MEMORY_THRESHOLD_PERCENT,
+            interval_secs: DEFAULT_INTERVAL_SECS,
+            poll: PollConfig::default(),
+            target: Target::default(),
+            dry_run: false,
+            trigger: TriggerMode::default(),
+            group_by: GroupBy::default(),
+            memory: MemoryConfig::default(),
+            psi: PsiConfig::default(),
+            escalation: EscalationConfig::default(),
+            scoring: ScoringConfig::default(),
+            notify: NotifyConfig::default(),
+            journal: JournalConfig::default(),
+            metrics: MetricsConfig::default(),
+            hardening: HardeningConfig::default(),
+            kill_loop: KillLoopConfig::default(),
+            control: ControlConfig::default(),
+            freeze: FreezeConfig::default(),
+            snapshot: SnapshotConfig::default(),
+            tree: TreeConfig::default(),
+            leak: LeakConfig::default(),
+            nix: NixConfig::default(),
+        }
+    }
+}
+
+/// Values from the command line or environment; each one beats the file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigOverrides {
+    pub threshold_percent: Option<f64>,
+    pub interval_secs: Option<f64>,
+    pub target: Option<Target>,
+    pub dry_run: Option<bool>,
+    pub trigger: Option<TriggerMode>,
+    pub group_by: Option<GroupBy>,
+    pub journal_path: Option<PathBuf>,
+}
+
+impl Config {
+    /// Builds the effective configuration: defaults, then `path` (if any), then `overrides`.
+    ///
+    /// # Returns
+    /// * `Ok(Config)` if the file parses and the merged values are valid
+    /// * `Err(String)` naming the file or the offending key otherwise
+    pub fn load(path: Option<&Path>, overrides: &ConfigOverrides) -> Result<Self, String> {
+        let mut config = match path {
+            Some(path) => {
+                let text = std::fs::read_to_string(path)
+                    .map_err(|e| format!("Failed to read config {}: {e}", path.display()))?;
+                Self::from_toml(&text).map_err(|e| format!("{}: {e}", path.display()))?
+            }
+            None => Self::default(),
+        };
+        config.apply(overrides);
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parses a TOML document; missing keys keep their defaults.
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| format!("Invalid config: {e}"))
+    }
+
+    pub fn apply(&mut self, overrides: &ConfigOverrides) {
+        if let Some(v) = overrides.threshold_percent {
+            self.threshold_percent = v;
+        }
+        if let Some(v) = overrides.interval_secs {
+            self.interval_secs = v;
+        }
+        if let Some(v) = overrides.target {
+            self.target = v;
+        }
+        if let Some(v) = overrides.dry_run {
+            self.dry_run = v;
+        }
+        if let Some(v) = overrides.trigger {
+            self.trigger = v;
+        }
+        if let Some(v) = overrides.group_by {
+            self.group_by = v;
+        }
+        if let Some(v) = &overrides.journal_path {
+            self.journal.path = Some(v.clone());
+        }
+    }
+
+    /// Rejects values that would make the daemon useless or spin.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.threshold_percent > 0.0 && self.threshold_percent <= 100.0) {
+            return Err(format!(
+                "threshold_percent must be in (0, 100], got {}",
+                self.threshold_percent
+            ));
+        }
+        if !(self.interval_secs >= 0.05 && self.interval_secs <= 3600.0) {
+            return Err(format!(
+                "interval_secs must be between 0.05 and 3600, got {}",
+                self.interval_secs
+            ));
+        }
+        self.poll.validate()?;
+        self.memory.validate()?;
+        self.psi.validate()?;
+        self.escalation.validate()?;
+        self.scoring.validate()?;
+        self.notify.validate()?;
+        self.journal.validate()?;
+        self.metrics.validate()?;
+        self.hardening.validate()?;
+        self.kill_loop.validate()?;
+        self.freeze.validate()?;
+        self.snapshot.validate()?;
+        self.tree.validate()?;
+        self.leak.validate()?;
+        self.nix.validate()
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs_f64(self.interval_secs)
+    }
+
+    /// The longest sleep between iterations: `poll.max_secs`, or `interval_secs`.
+    pub fn longest_interval(&self) -> Duration {
+        if self.poll.adaptive {
+            Duration::from_secs_f64(self.poll.max_secs)
+        } else {
+            self.interval()
+        }
+    }
+
+    /// One-line summary for startup and reload logs.
+    pub fn summary(&self) -> String {
+        let interval = if self.poll.adaptive {
+            format!("{}-{}s (adaptive)", self.poll.min_secs, self.poll.max_secs)
+        } else {
+            format!("{}s", self.interval_secs)
+        };
+        format!(
+            "threshold={}% ({}) interval={interval} target={} dry_run={} trigger={} group_by={}",
+            self.threshold_percent,
+            self.memory.accounting.as_str(),
+            self.target.as_str(),
+            self.dry_run,
+            self.trigger.as_str(),
+            self.group_by.as_str(),
+        )
+    }
+}
+
+/// `$XDG_CONFIG_HOME/oomkiller/config.toml` (or `~/.config/...`) when that file exists.
+pub fn default_config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    let path = base.join("oomkiller").join("config.toml");
+    path.is_file().then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smaps::MemoryMetric;
+
+    #[test]
+    fn test_default_config() {
+        let mut config = Config::default();
+        assert_eq!(config.threshold_percent, 90.0);
+        assert_eq!(config.target, Target::User);
+        assert!(!config.dry_run);
+        assert!(config.validate().is_ok());
+
+        // Adaptive polling between 0.1 and 10 s; the fixed 5 s only without it.
+        assert!(config.poll.adaptive);
+        assert_eq!((config.poll.min_secs, config.poll.max_secs), (0.1, 10.0));
+        assert_eq!(config.longest_interval(), Duration::from_secs(10));
+        config.poll.adaptive = false;
+        assert_eq!(config.longest_interval(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_from_toml_partial_keeps_defaults() {
+        let config = Config::from_toml("threshold_percent = 80.5\ntarget = \"all\"\n").unwrap();
+        assert_eq!(config.threshold_percent, 80.5);
+        assert_eq!(config.target, Target::All);
+        assert_eq!(config.interval_secs, DEFAULT_INTERVAL_SECS);
+    }
+
+    #[test]
+    fn test_from_toml_rejects_unknown_keys() {
+        let err = Config::from_toml("treshold_percent = 80\n").unwrap_err();
+        assert!(err.contains("treshold_percent"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn test_overrides_beat_file() {
+        let mut config = Config::from_toml("threshold_percent = 80\ndry_run = true\n").unwrap();
+        config.apply(&ConfigOverrides {
+            threshold_percent: Some(70.0),
+            dry_run: Some(false),
+            ..ConfigOverrides::default()
+        });
+        assert_eq!(config.threshold_percent, 70.0);
+        assert!(!config.dry_run);
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range() {
+        let bad_threshold = Config {
+            threshold_percent: 120.0,
+            ..Config::default()
+        };
+        assert!(bad_threshold.validate().is_err());
+        let bad_interval = Config {
+            interval_secs: 0.0,
+            ..Config::default()
+        };
+        assert!(bad_interval.validate().is_err());
+        let nan = Config {
+            threshold_percent: f64::NAN,
+            ..Config::default()
+        };
+        assert!(nan.validate().is_err());
+    }
+
+    #[test]
+    fn test_load_reports_missing_file() {
+        let err = Config::load(
+            Some(Path::new("/nonexistent/oomkiller.toml")),
+            &ConfigOverrides::default(),
+        )
+        .unwrap_err();
+        assert!(err.contains("/nonexistent/oomkiller.toml"));
+    }
+
+    #[test]
+    fn test_from_toml_psi_section() {
+        let config =
+            Config::from_toml("trigger = \"psi\"\n[psi]\nfull_avg10 = 2.5\nwindow_ms = 4000\n")
+                .unwrap();
+        assert_eq!(config.trigger, TriggerMode::Psi);
+        assert_eq!(config.psi.full_avg10, 2.5);
+        assert_eq!(config.psi.window_ms, 4000);
+        assert_eq!(config.psi.some_avg10, PsiConfig::default().some_avg10);
+    }
+
+    #[test]
+    fn test_validate_checks_psi_section() {
+        let config = Config::from_toml("[psi]\nwindow_ms = 50\n").unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_escalation_config_validation() {
+        assert!(EscalationConfig::default().validate().is_ok());
+        let config = Config::from_toml("[escalation]\npoll_ms = 0\n").unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_from_toml_scoring_rules_replace_defaults() {
+        let config = Config::from_toml("[scoring]\nnever = [\"^important$\"]\n").unwrap();
+        assert!(config.scoring.never.is_match("important"));
+        assert!(!config.scoring.never.is_match("systemd"));
+        assert!(config.scoring.avoid.is_match("gnome-shell"));
+        assert_eq!(config.scoring.memory_metric, MemoryMetric::Uss);
+        let config = Config::from_toml("[scoring]\nmemory_metric = \"pss\"\n").unwrap();
+        assert_eq!(config.scoring.memory_metric, MemoryMetric::Pss);
+        assert!(Config::from_toml("[scoring]\nmemory_metric = \"vss\"\n").is_err());
+    }
+
+    #[test]
+    fn test_from_toml_rejects_bad_regex() {
+        let err = Config::from_toml("[scoring]\nprefer = [\"[\"]\n").unwrap_err();
+        assert!(err.contains("invalid regex"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn test_target_parse() {
+        assert_eq!(Target::parse("user"), Ok(Target::User));
+        assert_eq!(Target::parse("all"), Ok(Target::All));
+        assert!(Target::parse("root").is_err());
+    }
+
+    #[test]
+    fn test_group_by_from_toml_and_override() {
+        let mut config = Config::from_toml("group_by = \"cgroup\"\n").unwrap();
+        assert_eq!(config.group_by, GroupBy::Cgroup);
+        config.apply(&ConfigOverrides {
+            group_by: Some(GroupBy::Process),
+            ..ConfigOverrides::default()
+        });
+        assert_eq!(config.group_by, GroupBy::Process);
+        assert!(GroupBy::parse("scope").is_err());
+    }
+}
@@ -0,0 +1,623 @@
+use crate::{
+    badness::{Candidate, EXPLAIN_HEADER, Rule, explain_candidate},
+    config::Config,
+    format_bytes,
+    host::Host,
+    ranked_candidates,
+    types::ProcessInfo,
+};
+use serde::Deserialize;
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::Thread;
+use std::time::{Duration, Instant};
+
+/// `[control]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ControlConfig {
+    /// Listen for `oomkiller ctl` requests.
+    pub enabled: bool,
+    /// Unix socket to listen on; default `$XDG_RUNTIME_DIR/oomkiller.sock`.
+    /// Bound once at startup.
+    pub socket: Option<PathBuf>,
+}
+
+impl Default for ControlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            socket: None,
+        }
+    }
+}
+
+impl ControlConfig {
+    /// `socket`, or the default path.
+    pub fn socket_path(&self) -> Option<PathBuf> {
+        self.socket.clone().or_else(default_socket_path)
+    }
+}
+
+/// `$XDG_RUNTIME_DIR/oomkiller.sock`, or `/run/user/UID/oomkiller.sock` when the
+/// daemon runs as a system service without a runtime directory in its environment.
+pub fn default_socket_path() -> Option<PathBuf> {
+    let dir = match std::env::var_os("XDG_RUNTIME_DIR").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(format!("/run/user/{}", crate::get_current_uid().ok()?)),
+    };
+    Some(dir.join("oomkiller.sock"))
+}
+
+/// A process to leave alone, by PID or by exact process name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtectTarget {
+    Pid(u32),
+    Name(String),
+}
+
+impl ProtectTarget {
+    /// A number is a PID, anything else a process name.
+    pub fn parse(target: &str) -> Self {
+        target
+            .parse()
+            .map_or_else(|_| Self::Name(target.to_string()), Self::Pid)
+    }
+
+    pub fn matches(&self, process: &ProcessInfo) -> bool {
+        match self {
+            Self::Pid(pid) => process.pid == *pid,
+            Self::Name(name) => process.name == *name,
+        }
+    }
+}
+
+impl fmt::Display for ProtectTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pid(pid) => write!(f, "{pid}"),
+            Self::Name(name) => f.write_str(name),
+        }
+    }
+}
+
+/// One `oomkiller ctl` command; sent over the socket as a single line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    /// Memory, PSI, pause and protection state, top candidates.
+    Status,
+    /// Do not kill for this long (`kill-now` still works).
+    Pause(Duration),
+    Resume,
+    Protect(ProtectTarget),
+    /// Drop one protection, or all of them.
+    Unprotect(Option<ProtectTarget>),
+    /// Run the normal victim selection now, whatever the memory reading.
+    KillNow,
+}
+
+impl Request {
+    /// Parses a request line, e.g. `pause 30m` or `protect Web Content`.
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim();
+        let (command, arg) = match line.split_once(' ') {
+            Some((command, arg)) => (command, Some(arg.trim()).filter(|a| !a.is_empty())),
+            None => (line, None),
+        };
+        let request = match (command, arg) {
+            ("status", None) => Self::Status,
+            ("pause", Some(duration)) => Self::Pause(parse_duration(duration)?),
+            ("resume", None) => Self::Resume,
+            ("protect", Some(target)) => Self::Protect(ProtectTarget::parse(target)),
+            ("unprotect", target) => Self::Unprotect(target.map(ProtectTarget::parse)),
+            ("kill-now", None) => Self::KillNow,
+            _ => return Err(format!("unknown request: {line}")),
+        };
+        Ok(request)
+    }
+}
+
+impl fmt::Display for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status => f.write_str("status"),
+            Self::Pause(duration) => write!(f, "pause {}s", duration.as_secs_f64()),
+            Self::Resume => f.write_str("resume"),
+            Self::Protect(target) => write!(f, "protect {target}"),
+            Self::Unprotect(Some(target)) => write!(f, "unprotect {target}"),
+            Self::Unprotect(None) => f.write_str("unprotect"),
+            Self::KillNow => f.write_str("kill-now"),
+        }
+    }
+}
+
+/// Parses `90`, `90s`, `30m`, `2h` or `1d`; a bare number is seconds.
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let (number, unit) = match text.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(i) => text.split_at(i),
+        None => (text, "s"),
+    };
+    let scale = match unit {
+        "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        "d" => 86_400.0,
+        _ => return Err(format!("invalid duration {text:?}: use s, m, h or d")),
+    };
+    match number
+        .parse::<f64>()
+        .map(|n| (n, Duration::try_from_secs_f64(n * scale)))
+    {
+        Ok((n, Ok(duration))) if n > 0.0 => Ok(duration),
+        _ => Err(format!(
+            "invalid duration {text:?}: expected e.g. 90s, 30m, 2h"
+        )),
+    }
+}
+
+#[derive(Default)]
+struct ControlState {
+    paused_until: Option<Instant>,
+    protected: Vec<ProtectTarget>,
+    kill_requested: bool,
+    /// Manual kills handled so far; a requester waits for this to pass its ticket.
+    kills_done: u64,
+    last_kill: String,
+    /// The main loop, woken for `kill-now`.
+    waker: Option<Thread>,
+    /// The main loop's current sleep between checks.
+    interval: Option<Duration>,
+}
+
+/// Runtime overrides set through the control socket and read by the daemon loop.
+#[derive(Default)]
+pub struct Control {
+    state: Mutex<ControlState>,
+    kill_done: Condvar,
+    /// Current settings, for `status` and the `kill-now` timeout.
+    config: Mutex<Config>,
+}
+
+impl Control {
+    fn state(&self) -> MutexGuard<'_, ControlState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Called at startup and after every reload.
+    pub fn set_config(&self, config: &Config) {
+        *self.config.lock().unwrap_or_else(PoisonError::into_inner) = config.clone();
+    }
+
+    /// Called after every iteration with the sleep before the next one, for `status`.
+    pub fn set_interval(&self, interval: Duration) {
+        self.state().interval = Some(interval);
+    }
+
+    /// The thread to unpark when a manual kill is requested.
+    pub fn set_waker(&self, thread: Thread) {
+        self.state().waker = Some(thread);
+    }
+
+    pub fn pause(&self, duration: Duration) {
+        // Absurdly long pauses are capped at a year rather than overflowing `Instant`.
+        let now = Instant::now();
+        let until = now
+            .checked_add(duration)
+            .or_else(|| now.checked_add(Duration::from_secs(365 * 86_400)));
+        self.state().paused_until = until;
+    }
+
+    /// Ends a pause; returns whether one was active.
+    pub fn resume(&self) -> bool {
+        self.state()
+            .paused_until
+            .take()
+            .is_some_and(|until| until > Instant::now())
+    }
+
+    /// Time left in the current pause, if any.
+    pub fn paused_for(&self) -> Option<Duration> {
+        let until = self.state().paused_until?;
+        until
+            .checked_duration_since(Instant::now())
+            .filter(|left| !left.is_zero())
+    }
+
+    pub fn protect(&self, target: ProtectTarget) {
+        let mut state = self.state();
+        if !state.protected.contains(&target) {
+            state.protected.push(target);
+        }
+    }
+
+    /// Drops `target`, or every protection; returns how many were removed.
+    pub fn unprotect(&self, target: Option<&ProtectTarget>) -> usize {
+        let mut state = self.state();
+        let before = state.protected.len();
+        state.protected.retain(|p| target.is_some_and(|t| t != p));
+        before - state.protected.len()
+    }
+
+    pub fn protected(&self) -> Vec<ProtectTarget> {
+        self.state().protected.clone()
+    }
+
+    /// Marks protected candidates with [`Rule::Protected`], which makes them unkillable.
+    pub fn mark_protected(&self, candidates: &mut [Candidate]) {
+        let state = self.state();
+        for candidate in candidates {
+            if state
+                .protected
+                .iter()
+                .any(|t| t.matches(&candidate.process))
+            {
+                candidate.badness.rule = Rule::Protected;
+            }
+        }
+    }
+
+    pub fn is_protected(&self, process: &ProcessInfo) -> bool {
+        self.state().protected.iter().any(|t| t.matches(process))
+    }
+
+    /// Asks the daemon loop for a kill and waits up to `timeout` for the result.
+    pub fn request_kill(&self, timeout: Duration) -> Option<String> {
+        let mut state = self.state();
+        state.kill_requested = true;
+        let ticket = state.kills_done + 1;
+        if let Some(waker) = &state.waker {
+            waker.unpark();
+        }
+        let (state, _) = self
+            .kill_done
+            .wait_timeout_while(state, timeout, |s| s.kills_done < ticket)
+            .unwrap_or_else(PoisonError::into_inner);
+        (state.kills_done >= ticket).then(|| state.last_kill.clone())
+    }
+
+    /// True once per `kill-now` request; the daemon loop then runs selection unconditionally.
+    pub fn take_kill_request(&self) -> bool {
+        std::mem::take(&mut self.state().kill_requested)
+    }
+
+    /// Hands the outcome of a manual kill to the waiting requester.
+    pub fn finish_kill(&self, result: String) {
+        let mut state = self.state();
+        state.kills_done += 1;
+        state.last_kill = result;
+        self.kill_done.notify_all();
+    }
+
+    /// Handles one request and returns the reply text (`error: ...` on failure).
+    pub fn handle(&self, request: &Request, host: &mut Host) -> String {
+        let config = self
+            .config
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        match request {
+            Request::Status => self
+                .status(host, &config)
+                .unwrap_or_else(|e| format!("error: {e}")),
+            Request::Pause(duration) => {
+                self.pause(*duration);
+                println!("Control: killing paused for {:.0}s", duration.as_secs_f64());
+                format!("paused for {:.0}s", duration.as_secs_f64())
+            }
+            Request::Resume => {
+                println!("Control: killing resumed");
+                if self.resume() {
+                    "resumed".to_string()
+                } else {
+                    "not paused".to_string()
+                }
+            }
+            Request::Protect(target) => {
+                println!("Control: protecting {target}");
+                self.protect(target.clone());
+                format!("protected {target}")
+            }
+            Request::Unprotect(target) => {
+                let removed = self.unprotect(target.as_ref());
+                println!("Control: removed {removed} protection(s)");
+                format!("removed {removed} protection(s)")
+            }
+            Request::KillNow => {
+                println!("Control: manual kill requested");
+                let timeout =
+                    config.longest_interval() + config.escalation.grace() + Duration::from_secs(5);
+                self.request_kill(timeout).unwrap_or_else(|| {
+                    format!(
+                        "error: no result within {:.0}s; see the daemon log",
+                        timeout.as_secs_f64()
+                    )
+                })
+            }
+        }
+    }
+
+    fn status(&self, host: &mut Host, config: &Config) -> Result<String, String> {
+        let psi = host.memory.pressure();
+        let memory = host.memory.snapshot(&config.memory)?;
+        host.processes.refresh(config.target);
+        let mut ranked = ranked_candidates(host.processes.as_ref(), config)?;
+        self.mark_protected(&mut ranked);
+
+        let mut out = format!(
+            "memory: {} / {} used ({:.1}%, {}), threshold {}%\n",
+            format_bytes(memory.used),
+            format_bytes(memory.total),
+            memory.used_percent(),
+            config.memory.accounting.as_str(),
+            config.threshold_percent
+        );
+        out.push_str(&match psi {
+            Some(psi) => format!("{psi}\n"),
+            None => "psi unavailable\n".to_string(),
+        });
+        if let Some(interval) = self.state().interval {
+            out.push_str(&format!(
+                "poll interval: {:.2}s{}\n",
+                interval.as_secs_f64(),
+                if config.poll.adaptive {
+                    " (adaptive)"
+                } else {
+                    ""
+                }
+            ));
+        }
+        out.push_str(&match self.paused_for() {
+            Some(left) => format!("killing: paused for {:.0}s\n", left.as_secs_f64()),
+            None => "killing: active\n".to_string(),
+        });
+        let protected = self.protected();
+        if protected.is_empty() {
+            out.push_str("protected: none\n");
+        } else {
+            let list: Vec<_> = protected.iter().map(ProtectTarget::to_string).collect();
+            out.push_str(&format!("protected: {}\n", list.join(", ")));
+        }
+        out.push('\n');
+        out.push_str(EXPLAIN_HEADER);
+        out.push('\n');
+        for candidate in ranked.iter().take(config.journal.top_n) {
+            out.push_str(&explain_candidate(candidate));
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+/// Listens on `path` for `oomkiller ctl` requests, one per connection, from a
+/// background thread.
+///
+/// The socket is created `0600` and every peer's UID is checked against the
+/// daemon's, so only the owning user can pause or protect. A stale socket file
+/// is replaced; one a running daemon still answers on is an error.
+pub fn serve_control(path: &Path, control: Arc<Control>) -> Result<(), String> {
+    if UnixStream::connect(path).is_ok() {
+        return Err(format!("{} is in use by another daemon", path.display()));
+    }
+    let _ = std::fs::remove_file(path);
+    let listener = UnixListener::bind(path)
+        .map_err(|e| format!("Failed to listen on {}: {e}", path.display()))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to restrict {}: {e}", path.display()))?;
+    // SAFETY: geteuid has no preconditions.
+    let uid = unsafe { libc::geteuid() };
+    std::thread::Builder::new()
+        .name("control".to_string())
+        .spawn(move || {
+            let mut host = Host::default();
+            for stream in listener.incoming().flatten() {
+                if let Err(e) = respond(stream, uid, &control, &mut host) {
+                    eprintln!("Control request failed: {e}");
+                }
+            }
+        })
+        .map(drop)
+        .map_err(|e| format!("Failed to spawn control thread: {e}"))
+}
+
+fn respond(
+    mut stream: UnixStream,
+    uid: u32,
+    control: &Control,
+    host: &mut Host,
+) -> Result<(), String> {
+    let peer = peer_uid(&stream)?;
+    let reply = if peer != uid {
+        format!("error: permission denied for uid {peer}")
+    } else {
+        stream
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .map_err(|e| e.to_string())?;
+        let mut line = String::new();
+        BufReader::new((&stream).take(4096))
+            .read_line(&mut line)
+            .map_err(|e| e.to_string())?;
+        match Request::parse(&line) {
+            Ok(request) => control.handle(&request, host),
+            Err(e) => format!("error: {e}"),
+        }
+    };
+    stream
+        .write_all(reply.as_bytes())
+        .map_err(|e| e.to_string())
+}
+
+/// UID of the process at the other end of `stream`.
+fn peer_uid(stream: &UnixStream) -> Result<u32, String> {
+    #[cfg(target_os = "linux")]
+    {
+        let mut cred = libc::ucred {
+            pid: 0,
+            uid: 0,
+            gid: 0,
+        };
+        let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+        // SAFETY: `cred` and `len` describe a valid ucred buffer for SO_PEERCRED.
+        let rc = unsafe {
+            libc::getsockopt(
+                stream.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_PEERCRED,
+                (&raw mut cred).cast(),
+                &mut len,
+            )
+        };
+        if rc != 0 {
+            return Err(format!("SO_PEERCRED: {}", std::io::Error::last_os_error()));
+        }
+        Ok(cred.uid)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let (mut uid, mut gid) = (0, 0);
+        // SAFETY: getpeereid only writes the two ids.
+        if unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) } != 0 {
+            return Err(format!("getpeereid: {}", std::io::Error::last_os_error()));
+        }
+        Ok(uid)
+    }
+}
+
+/// Sends `request` to the daemon at `path` and returns its reply.
+///
+/// Returns `Err` if the daemon cannot be reached or answers with an error.
+pub fn send_request(path: &Path, request: &Request) -> Result<String, String> {
+    let mut stream = UnixStream::connect(path)
+        .map_err(|e| format!("Cannot reach oomkiller at {}: {e}", path.display()))?;
+    writeln!(stream, "{request}").map_err(|e| format!("Failed to send request: {e}"))?;
+    let mut reply = String::new();
+    stream
+        .read_to_string(&mut reply)
+        .map_err(|e| format!("Failed to read reply: {e}"))?;
+    match reply.strip_prefix("error: ") {
+        Some(e) => Err(e.trim_end().to_string()),
+        None => Ok(reply),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_host::{FakeMemory, FakeProcesses, GIB, fake_host};
+
+    #[test]
+    fn test_request_round_trip() {
+        for request in [
+            Request::Status,
+            Request::Pause(Duration::from_secs(1800)),
+            Request::Resume,
+            Request::Protect(ProtectTarget::Pid(1234)),
+            Request::Protect(ProtectTarget::Name("Web Content".to_string())),
+            Request::Unprotect(None),
+            Request::Unprotect(Some(ProtectTarget::Name("rustc".to_string()))),
+            Request::KillNow,
+        ] {
+            assert_eq!(Request::parse(&request.to_string()), Ok(request));
+        }
+        assert!(Request::parse("pause").is_err());
+        assert!(Request::parse("kill 1").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(1800)));
+        assert_eq!(parse_duration("1.5h"), Ok(Duration::from_secs(5400)));
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("5w").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("1e30d").is_err());
+    }
+
+    #[test]
+    fn test_pause_resume_and_protect() {
+        let control = Control::default();
+        assert_eq!(control.paused_for(), None);
+        control.pause(Duration::from_secs(60));
+        assert!(control.paused_for().unwrap() > Duration::from_secs(59));
+        assert!(control.resume());
+        assert!(!control.resume());
+
+        let firefox = ProcessInfo {
+            pid: 2000,
+            name: "firefox".to_string(),
+            ..ProcessInfo::default()
+        };
+        control.protect(ProtectTarget::parse("firefox"));
+        control.protect(ProtectTarget::parse("3000"));
+        assert!(control.is_protected(&firefox));
+        assert_eq!(
+            control.unprotect(Some(&ProtectTarget::Name("firefox".to_string()))),
+            1
+        );
+        assert!(!control.is_protected(&firefox));
+        assert_eq!(control.unprotect(None), 1);
+    }
+
+    #[test]
+    fn test_kill_request_waits_for_result() {
+        let control = Arc::new(Control::default());
+        let daemon = {
+            let control = Arc::clone(&control);
+            std::thread::spawn(move || {
+                while !control.take_kill_request() {
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+                control.finish_kill("killed firefox".to_string());
+            })
+        };
+        assert_eq!(
+            control.request_kill(Duration::from_secs(5)).as_deref(),
+            Some("killed firefox")
+        );
+        daemon.join().unwrap();
+        assert_eq!(control.request_kill(Duration::from_millis(10)), None);
+    }
+
+    #[test]
+    fn test_status_lists_protected_candidates() {
+        let mut processes = FakeProcesses::default();
+        processes
+            .add(2000, "firefox", 3 * GIB)
+            .add(3000, "bash", GIB);
+        let (mut host, _) = fake_host(FakeMemory::new(&[50.0]), processes);
+        let control = Control::default();
+        control.protect(ProtectTarget::Pid(2000));
+        let status = control.handle(&Request::Status, &mut host);
+        assert!(status.starts_with("memory: 8.00 GiB / 16.00 GiB used (50.0%, ram)"));
+        assert!(status.contains("killing: active\nprotected: 2000\n"));
+        assert!(status.contains("protected)\n"), "{status}");
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_socket_round_trip() {
+        let path = std::env::temp_dir().join(format!("oomkiller-ctl-{}.sock", std::process::id()));
+        let control = Arc::new(Control::default());
+        serve_control(&path, Arc::clone(&control)).unwrap();
+        assert!(serve_control(&path, Arc::clone(&control)).is_err());
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(
+            send_request(&path, &Request::Pause(Duration::from_secs(600))).as_deref(),
+            Ok("paused for 600s")
+        );
+        assert!(control.paused_for().is_some());
+        assert_eq!(
+            send_request(&path, &Request::Resume).as_deref(),
+            Ok("resumed")
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
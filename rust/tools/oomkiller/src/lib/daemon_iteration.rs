@@ -1,8 +1,28 @@
 use crate::{
-    MEMORY_THRESHOLD_PERCENT, find_highest_memory_process, format_bytes,
-    is_memory_threshold_exceeded, kill_process,
+    badness::{Candidate, score_process},
+    cgroup::AppGroup,
+    config::{Config, GroupBy},
+    daemon_state::DaemonState,
+    evaluate_trigger, format_bytes,
+    freeze::{Answer, Frozen, FrozenTarget},
+    host::{Host, MemorySource, ProcessTable, SignalSink},
+    journal::{CandidateRecord, Decision, TreeRecord, VictimRecord, append_decision},
+    kill_history::{LoopAction, LoopKey},
+    leak::{Leak, LeakAction},
+    metrics::write_textfile,
+    nix_build::{NixTarget, select_nix_build},
+    process_tree::{descendants, tree_footprint},
+    psi::PsiSnapshot,
+    ranked_candidates,
+    sd_notify::SystemdNotifier,
+    select_app,
+    smaps::MemoryMetric,
+    snapshot::{SnapshotInput, write_snapshot},
+    types::{Bytes, KillOutcome, ProcessInfo},
 };
-use sysinfo::System;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 /// Truncate cmdline for journal readability.
 fn truncate_cmdline(cmdline: &str, max_chars: usize) -> String {
@@ -15,94 +35,1982 @@ fn truncate_cmdline(cmdline: &str, max_chars: usize) -> String {
 
 /// Performs one iteration of the daemon loop.
 ///
-/// Checks if memory threshold is exceeded, and if so, finds and kills the highest memory process.
-/// Uses a reused System object to avoid expensive initialization.
+/// Reads memory (used percent and/or PSI, per `config.trigger`) and, when it is
+/// critical or `oomkiller ctl kill-now` asked for it, stops one victim: a Nix build,
+/// an app unit or a process (see [`act_on_breach`]). While a victim is frozen it is
+/// watched instead, and between breaches leaking processes are acted on. Every
+/// decision is journaled, and the metrics are published after every iteration.
 ///
 /// # Arguments
-/// * `system` - A mutable reference to a System object (should be reused across calls)
+/// * `host` - Memory source, process table and signal sink (should be reused across calls)
+/// * `config` - Threshold, target selection, scoring and dry-run setting
+/// * `state` - Notifier, metrics and other state kept across iterations
 ///
 /// # Returns
 /// * `Ok(())` if the iteration completed successfully
 /// * `Err(String)` if memory reading failed (should cause daemon to exit)
-pub fn daemon_iteration(system: &mut System) -> Result<(), String> {
-    let threshold_exceeded = is_memory_threshold_exceeded(system)?;
+pub fn daemon_iteration(
+    host: &mut Host,
+    config: &Config,
+    state: &mut DaemonState,
+) -> Result<(), String> {
+    let result = check_and_act(host, config, state);
+    publish_metrics(config, state);
+    result
+}
+
+/// What every action of one check needs besides the config.
+struct Check {
+    /// Pressure is past `escalation.critical_full_avg10`: no SIGTERM, no freezing.
+    critical: bool,
+    /// Requested with `oomkiller ctl kill-now`: never frozen or called off.
+    manual: bool,
+    /// The reading, for notifications.
+    memory: MemoryLine,
+}
+
+/// `Memory 15.20 GiB / 16.00 GiB used (95.0%, threshold 90%) psi some=...`; displaying it
+/// does not allocate, so it is only rendered once a victim has been signalled.
+struct MemoryLine {
+    used: u64,
+    total: u64,
+    percent: f64,
+    threshold: f64,
+    psi: Pressure,
+}
+
+impl fmt::Display for MemoryLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Memory {} / {} used ({:.1}%, threshold {}%){}",
+            Bytes(self.used),
+            Bytes(self.total),
+            self.percent,
+            self.threshold,
+            self.psi
+        )
+    }
+}
+
+/// ` psi some=...` after a memory reading, or nothing without PSI.
+#[derive(Clone, Copy)]
+struct Pressure(Option<PsiSnapshot>);
+
+impl fmt::Display for Pressure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(psi) => write!(f, " {psi}"),
+            None => Ok(()),
+        }
+    }
+}
+
+fn check_and_act(host: &mut Host, config: &Config, state: &mut DaemonState) -> Result<(), String> {
+    let manual = state.control.take_kill_request();
+    let psi = host.memory.pressure();
+    let memory = host.memory.snapshot(&config.memory)?;
+    let threshold_exceeded = evaluate_trigger(&memory, config, psi.as_ref());
 
+    let (used, total) = (memory.used, memory.total);
+    let usage_percent = memory.used_percent();
+    state.metrics.iterations += 1;
+    state.metrics.used_percent = usage_percent;
+    state.metrics.psi = psi;
     if threshold_exceeded {
-        let total = system.total_memory();
-        let used = system.used_memory();
-        let usage_percent = if total == 0 {
-            0.0
+        state.metrics.breaches += 1;
+    }
+    // Every reading also sets the sleep before the next one (see `[poll]`).
+    let interval = state.poll.observe(Instant::now(), usage_percent, config);
+    state.metrics.poll_interval_secs = interval.as_secs_f64();
+    state.control.set_interval(interval);
+    if config.poll.adaptive && state.poll.should_log() {
+        println!(
+            "Poll interval {:.2}s (used {usage_percent:.1}%, {:+.2}%/s)",
+            interval.as_secs_f64(),
+            state.poll.rate()
+        );
+    }
+
+    let pressure = Pressure(psi);
+    let check = Check {
+        // Past the hard PSI limit there is no time for a graceful exit.
+        critical: psi.is_some_and(|p| p.full.avg10 >= config.escalation.critical_full_avg10),
+        manual,
+        memory: MemoryLine {
+            used,
+            total,
+            percent: usage_percent,
+            threshold: config.threshold_percent,
+            psi: pressure,
+        },
+    };
+    let decision = Decision::new(config, used, total, psi);
+
+    if state.freeze.frozen.is_some() {
+        match act_on_frozen(
+            host,
+            config,
+            state,
+            threshold_exceeded,
+            &check,
+            decision.clone(),
+        ) {
+            FrozenStep::Gone => {}
+            FrozenStep::Waiting => return Ok(()),
+            FrozenStep::Done(decision) => {
+                record(config, state, &decision);
+                return Ok(());
+            }
+        }
+    }
+
+    // Leak sampling needs a full rescan, so it only happens every `leak.sample_secs`.
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0);
+    let sampled = state.leaks.due(now, &config.leak);
+    if sampled {
+        host.processes.refresh(config.target);
+        match host.processes.candidates(config.target) {
+            Ok(processes) => state.leaks.sample(now, &processes, &config.leak),
+            Err(e) => eprintln!("Failed to sample processes for leak detection: {e}"),
+        }
+        state.metrics.leaking = state.leaks.leaking(&config.leak).len();
+    }
+
+    if threshold_exceeded || manual {
+        let reason = if manual {
+            "Manual kill requested via oomkiller ctl"
         } else {
-            (used as f64 / total as f64) * 100.0
+            "Memory threshold exceeded"
         };
+        println!(
+            "{reason} ({threshold}% {accounting}, trigger={trigger}): used={} / total={} ({usage_percent:.1}%){pressure}",
+            Bytes(used),
+            Bytes(total),
+            threshold = config.threshold_percent,
+            accounting = config.memory.accounting.as_str(),
+            trigger = config.trigger.as_str(),
+        );
+        let decision = act_on_breach(host, config, state, &check, sampled, decision);
+        record(config, state, &decision);
+    } else if sampled {
+        act_on_leaks(host, config, state, &check, &decision);
+    }
+
+    Ok(())
+}
 
+/// Chooses and stops the victim of a critical reading or a manual kill: a running
+/// Nix build per `[nix]`, else with `group_by = "cgroup"` the heaviest app unit,
+/// else the highest-badness process. Nothing is killed while killing is paused.
+///
+/// Choosing the victim allocates, but whatever is only logged, shown or journaled
+/// (snapshot, notifications, candidate records) waits until it has been signalled.
+///
+/// # Arguments
+/// * `sampled` - The process table was just refreshed for leak sampling
+fn act_on_breach(
+    host: &mut Host,
+    config: &Config,
+    state: &mut DaemonState,
+    check: &Check,
+    sampled: bool,
+    mut decision: Decision,
+) -> Decision {
+    if check.manual {
+        decision.trigger = "manual";
+    } else if let Some(left) = state.control.paused_for() {
         println!(
-            "Memory threshold exceeded ({threshold}%): used={} / total={} ({usage_percent:.1}%)",
-            format_bytes(used),
-            format_bytes(total),
-            threshold = MEMORY_THRESHOLD_PERCENT as u32,
+            "Killing is paused for {:.0}s more (oomkiller ctl resume)",
+            left.as_secs_f64()
         );
+        decision.action = "paused";
+        return decision;
+    }
 
-        // Only now refresh all processes (expensive; only when needed)
-        system.refresh_all();
+    // Only now refresh all processes (expensive; only when needed)
+    if !sampled {
+        host.processes.refresh(config.target);
+    }
 
-        match find_highest_memory_process(system) {
-            Ok(Some(process)) => {
-                let cmdline = if process.cmdline.is_empty() {
-                    "(no cmdline)".to_string()
-                } else {
-                    truncate_cmdline(&process.cmdline, 240)
-                };
-                if let Err(e) = kill_process(&process) {
-                    eprintln!(
-                        "Failed to kill pid={} name={} rss={}: {e}",
-                        process.pid,
-                        process.name,
-                        format_bytes(process.memory),
-                    );
+    let mut ranked = match ranked_candidates(host.processes.as_ref(), config) {
+        Ok(ranked) => ranked,
+        Err(e) => {
+            eprintln!("Failed to select a process to kill: {e}");
+            decision.action = "failed";
+            decision.outcome = Some(e);
+            return decision;
+        }
+    };
+    state.leaks.mark_leaking(&mut ranked, &config.leak);
+    state.control.mark_protected(&mut ranked);
+
+    // One Nix build per check; apps are only considered once none is left, or
+    // if the build could not be stopped.
+    if let Some(target) = select_nix_build(host.processes.as_ref(), &ranked, &config.nix) {
+        let mut stopped = act_on_nix_build(host, config, state, check, target, &ranked, &decision);
+        stopped.candidates = candidate_records(config, &ranked);
+        if stopped.action != "failed" {
+            return stopped;
+        }
+        println!("Could not stop the Nix build; choosing among the other candidates");
+        record(config, state, &stopped);
+    }
+
+    let app = if config.group_by == GroupBy::Cgroup {
+        act_on_app_group(host, config, state, check, &ranked, &decision)
+    } else {
+        None
+    };
+    let mut decision = match app {
+        Some(decision) => decision,
+        None => act_on_process(host, config, state, check, &ranked, decision),
+    };
+    decision.candidates = candidate_records(config, &ranked);
+    decision
+}
+
+/// The top `journal.top_n` of the ranking, for the journal.
+fn candidate_records(config: &Config, ranked: &[Candidate]) -> Vec<CandidateRecord> {
+    ranked
+        .iter()
+        .take(config.journal.top_n)
+        .map(CandidateRecord::from)
+        .collect()
+}
+
+/// Stops (or freezes, per `[freeze]`) the heaviest delegated app unit as a whole.
+///
+/// Returns `None` to fall back to per-process selection: no unit is delegated, one
+/// of its processes is protected, or it is in a kill-loop cooldown and
+/// `kill_loop.action` is not `cooldown`.
+fn act_on_app_group(
+    host: &mut Host,
+    config: &Config,
+    state: &mut DaemonState,
+    check: &Check,
+    ranked: &[Candidate],
+    template: &Decision,
+) -> Option<Decision> {
+    let group = match select_app(host.processes.as_ref(), config) {
+        Ok(Some(group)) => group,
+        Ok(None) => {
+            println!("No delegated app cgroup to kill; falling back to per-process selection");
+            return None;
+        }
+        Err(e) => {
+            eprintln!("Failed to select an app cgroup, falling back to per-process selection: {e}");
+            return None;
+        }
+    };
+    if group
+        .processes
+        .iter()
+        .any(|p| state.control.is_protected(p))
+    {
+        println!(
+            "App unit {} has a protected process; falling back to per-process selection",
+            group.unit()
+        );
+        return None;
+    }
+
+    let first = group.processes.first()?;
+    let mut decision = template.clone();
+    let key = loop_key(host.processes.as_ref(), first);
+    if let Some(until) = state.history.cooldown_until(&key.key, decision.timestamp) {
+        if config.kill_loop.action != LoopAction::Cooldown {
+            println!(
+                "Kill loop: {} is cooling down for {:.0}s more; falling back to per-process selection",
+                key.key,
+                until - decision.timestamp
+            );
+            return None;
+        }
+        println!(
+            "Kill loop: {} is cooling down for {:.0}s more, killing nothing",
+            key.key,
+            until - decision.timestamp
+        );
+        decision.action = "cooldown";
+        decision.outcome = Some(format!("{} in cooldown", key.key));
+        return Some(decision);
+    }
+
+    let freeze = freezes(config, state, group.unit(), check, decision.timestamp);
+    let frozen = if freeze && !config.dry_run {
+        let frozen = Frozen::new(
+            FrozenTarget::Cgroup {
+                path: group.path.clone(),
+            },
+            VictimRecord::cgroup(&group),
+            key.clone(),
+            app_detail(&group),
+            decision.timestamp,
+            &config.freeze,
+        );
+        let memory_state = check.memory.to_string();
+        freeze_victim(host.signals.as_mut(), config, state, frozen, &memory_state)
+    } else {
+        None
+    };
+    if let Some(outcome) = frozen {
+        println!(
+            "Froze cgroup unit={} memory={} processes={}",
+            group.unit(),
+            format_bytes(group.memory),
+            group.processes.len(),
+        );
+        decision.action = "frozen";
+        decision.outcome = Some(outcome);
+    } else if freeze && config.dry_run {
+        println!(
+            "Dry run: would freeze cgroup unit={} memory={} processes={}",
+            group.unit(),
+            format_bytes(group.memory),
+            group.processes.len(),
+        );
+        decision.action = "dry_run";
+    } else {
+        capture(config, state, Some(first.pid), Some(&group.path));
+        (decision.action, decision.outcome) = handle_app(host, config, state, &group, check);
+    }
+    decision.victim = Some(VictimRecord::cgroup(&group));
+    decision.snapshot = snapshot(config, state, &decision, ranked);
+    if !config.dry_run && !matches!(decision.action, "failed" | "frozen") {
+        decision.kill_loop = note_kill(
+            host.signals.as_mut(),
+            config,
+            state,
+            &key,
+            decision.timestamp,
+        );
+    }
+    Some(decision)
+}
+
+/// Stops the highest-badness killable candidate. Candidates in a kill-loop cooldown
+/// are passed over (or, with `kill_loop.action = "cooldown"`, hold back the whole
+/// kill). A victim matching `[freeze]` is frozen and the user asked about it instead;
+/// one matching `[tree]` is killed together with its descendants, leaves first. In
+/// dry-run mode the victim is only logged, with its score breakdown.
+fn act_on_process(
+    host: &mut Host,
+    config: &Config,
+    state: &mut DaemonState,
+    check: &Check,
+    ranked: &[Candidate],
+    mut decision: Decision,
+) -> Decision {
+    let mut victim = None;
+    for candidate in ranked.iter().filter(|c| c.is_killable()) {
+        let key = loop_key(host.processes.as_ref(), &candidate.process);
+        let Some(until) = state.history.cooldown_until(&key.key, decision.timestamp) else {
+            victim = Some((candidate, key));
+            break;
+        };
+        let hold = config.kill_loop.action == LoopAction::Cooldown;
+        println!(
+            "Kill loop: {} (pid {}) is cooling down for {:.0}s more; {}",
+            key.key,
+            candidate.process.pid,
+            until - decision.timestamp,
+            if hold {
+                "killing nothing"
+            } else {
+                "trying the next candidate"
+            }
+        );
+        if hold {
+            decision.action = "cooldown";
+            decision.outcome = Some(format!("{} in cooldown", key.key));
+            return decision;
+        }
+    }
+    let Some((candidate, key)) = victim else {
+        eprintln!("Memory threshold exceeded but no killable process found");
+        return decision;
+    };
+
+    let process = &candidate.process;
+    let freeze = freezes(config, state, &process.name, check, decision.timestamp);
+    let metric = config.scoring.memory_metric;
+    // A frozen victim is killed alone. Descendants are spared like any
+    // candidate: `never` rules and `oomkiller ctl protect`.
+    let tree = if freeze {
+        Vec::new()
+    } else {
+        descendants(
+            host.processes.as_ref(),
+            process,
+            &config.tree,
+            metric,
+            &|p| config.scoring.never.is_match(&p.name) || state.control.is_protected(p),
+        )
+    };
+    let tree_frees = tree_footprint(&tree, metric);
+    if !tree.is_empty() {
+        decision.tree = Some(TreeRecord {
+            pids: tree.iter().map(|p| p.pid).collect(),
+            frees: tree_frees,
+        });
+    }
+    let victim = VictimRecord::process(candidate);
+
+    if config.dry_run {
+        let text = ProcessText::new(candidate, &tree, tree_frees, metric);
+        println!(
+            "Dry run: would {} process name={} pid={} rss={} swap={} frees={}{} score={} cmdline={}",
+            if freeze { "freeze" } else { "kill" },
+            process.name,
+            process.pid,
+            Bytes(process.memory),
+            Bytes(process.swap),
+            text.frees,
+            text.with_tree,
+            candidate.badness.breakdown(),
+            text.cmdline,
+        );
+        decision.victim = Some(victim);
+        decision.action = "dry_run";
+        return decision;
+    }
+    if freeze {
+        let text = ProcessText::new(candidate, &tree, tree_frees, metric);
+        let frozen = Frozen::new(
+            FrozenTarget::Process {
+                pid: process.pid,
+                start_time: process.start_time,
+            },
+            victim.clone(),
+            key.clone(),
+            text.detail,
+            decision.timestamp,
+            &config.freeze,
+        );
+        let memory_state = check.memory.to_string();
+        if let Some(outcome) =
+            freeze_victim(host.signals.as_mut(), config, state, frozen, &memory_state)
+        {
+            println!(
+                "Froze process name={} pid={} rss={} swap={} frees={} score={} cmdline={}",
+                process.name,
+                process.pid,
+                Bytes(process.memory),
+                Bytes(process.swap),
+                text.frees,
+                candidate.badness.total,
+                text.cmdline,
+            );
+            decision.victim = Some(victim);
+            decision.action = "frozen";
+            decision.outcome = Some(outcome);
+            return decision;
+        }
+    }
+
+    capture(config, state, Some(process.pid), None);
+    let stopped = {
+        let Host {
+            memory, signals, ..
+        } = host;
+        let mut grace = grace_period(memory.as_mut(), state, config, check, &process.name, || {
+            ProcessText::new(candidate, &tree, tree_frees, metric).detail
+        });
+        if tree.is_empty() {
+            signals.stop_process(process, &config.escalation, check.critical, &mut grace)
+        } else {
+            signals.stop_tree(
+                process,
+                &tree,
+                &config.escalation,
+                check.critical,
+                &mut grace,
+            )
+        }
+    };
+
+    decision.victim = Some(victim);
+    decision.snapshot = snapshot(config, state, &decision, ranked);
+    let text = ProcessText::new(candidate, &tree, tree_frees, metric);
+    match stopped.map(describe_outcome) {
+        Ok((kind, outcome)) => {
+            println!(
+                "Killed process name={} pid={} rss={} swap={} frees={}{} score={} outcome={outcome} cmdline={}",
+                process.name,
+                process.pid,
+                Bytes(process.memory),
+                Bytes(process.swap),
+                text.frees,
+                text.with_tree,
+                candidate.badness.total,
+                text.cmdline,
+            );
+            state.notifier.killed(
+                &config.notify,
+                &process.name,
+                &text.detail,
+                &outcome,
+                &check.memory.to_string(),
+            );
+            decision.action = kind;
+            decision.outcome = Some(outcome);
+            decision.kill_loop = note_kill(
+                host.signals.as_mut(),
+                config,
+                state,
+                &key,
+                decision.timestamp,
+            );
+        }
+        Err(e) => {
+            eprintln!(
+                "Failed to kill pid={} name={} rss={}: {e}",
+                process.pid,
+                process.name,
+                Bytes(process.memory),
+            );
+            decision.action = "failed";
+            decision.outcome = Some(e);
+        }
+    }
+    decision
+}
+
+/// Log and notification text about a process victim, built only when it is needed.
+struct ProcessText {
+    /// What the kill is expected to free, per `scoring.memory_metric`.
+    frees: String,
+    /// ` descendants=N` for the log line, if it has any.
+    with_tree: String,
+    cmdline: String,
+    /// Notification line, e.g. `PID 1234, RSS 3.20 GiB, swap 0 B, frees ~3.10 GiB (uss)`.
+    detail: String,
+}
+
+impl ProcessText {
+    fn new(
+        candidate: &Candidate,
+        tree: &[ProcessInfo],
+        tree_frees: u64,
+        metric: MemoryMetric,
+    ) -> Self {
+        let process = &candidate.process;
+        let frees = format!(
+            "{} ({})",
+            Bytes(candidate.badness.memory + tree_frees),
+            metric.as_str()
+        );
+        let (with_tree, detail_tree) = if tree.is_empty() {
+            (String::new(), String::new())
+        } else {
+            (
+                format!(" descendants={}", tree.len()),
+                format!(", with {} child processes", tree.len()),
+            )
+        };
+        let cmdline = if process.cmdline.is_empty() {
+            "(no cmdline)".to_string()
+        } else {
+            truncate_cmdline(&process.cmdline, 240)
+        };
+        let detail = format!(
+            "PID {}, RSS {}, swap {}, frees ~{frees}{detail_tree}",
+            process.pid,
+            Bytes(process.memory),
+            Bytes(process.swap)
+        );
+        Self {
+            frees,
+            with_tree,
+            cmdline,
+            detail,
+        }
+    }
+}
+
+/// Acts on every process growing per `[leak]` (see [`act_on_leak`]), killing at
+/// most one per check, and journals each step.
+fn act_on_leaks(
+    host: &mut Host,
+    config: &Config,
+    state: &mut DaemonState,
+    check: &Check,
+    template: &Decision,
+) {
+    let mut may_kill = true;
+    for leak in state.leaks.leaking(&config.leak) {
+        if let Some(decision) = act_on_leak(host, config, state, check, leak, may_kill, template) {
+            may_kill &= decision.action == "leak_warning";
+            record(config, state, &decision);
+        }
+    }
+}
+
+/// Warns once about a leaking process; with `action = "kill"`, kills it once the
+/// warning is `kill_after_secs` old and `may_kill`. Processes protected by `never`
+/// rules or `oomkiller ctl protect` are left alone, and nothing is killed while
+/// killing is paused. The kill is never called off, since memory was not critical
+/// to begin with, and the decision has `trigger = "leak"`.
+///
+/// Returns `None` when there is nothing to do about it in this check.
+fn act_on_leak(
+    host: &mut Host,
+    config: &Config,
+    state: &mut DaemonState,
+    check: &Check,
+    leak: Leak,
+    may_kill: bool,
+    template: &Decision,
+) -> Option<Decision> {
+    let Leak {
+        process,
+        growth,
+        warned,
+    } = leak;
+    if config.scoring.never.is_match(&process.name) || state.control.is_protected(&process) {
+        return None;
+    }
+    let now = template.timestamp;
+    let candidate = Candidate {
+        badness: score_process(&process, host.processes.total_memory(), &config.scoring),
+        process,
+    };
+    let process = &candidate.process;
+    let mut decision = template.clone();
+    decision.trigger = "leak";
+    decision.victim = Some(VictimRecord::process(&candidate));
+    let growing = format!(
+        "growing {}/min to {} over {}s",
+        format_bytes(growth.per_minute()),
+        format_bytes(growth.size),
+        config.leak.window_secs
+    );
+    let detail = format!("PID {}, {growing}", process.pid);
+    let kills = config.leak.action == LeakAction::Kill;
+    match warned {
+        None => {
+            println!(
+                "Leak: name={} pid={} {growing}; {}",
+                process.name,
+                process.pid,
+                if kills {
+                    format!("killing it in {}s", config.leak.kill_after_secs)
                 } else {
-                    println!(
-                        "Killed process name={} pid={} rss={} cmdline={cmdline}",
-                        process.name,
-                        process.pid,
-                        format_bytes(process.memory),
-                    );
+                    "preferring it as the victim".to_string()
+                }
+            );
+            state.notifier.leaking(
+                &config.notify,
+                &process.name,
+                &detail,
+                kills.then(|| Duration::from_secs_f64(config.leak.kill_after_secs)),
+            );
+            state.leaks.warn(process, now);
+            decision.action = "leak_warning";
+        }
+        Some(at) if kills && may_kill && now - at >= config.leak.kill_after_secs => {
+            if let Some(left) = state.control.paused_for() {
+                println!(
+                    "Leak: not killing name={} pid={}, killing is paused for {:.0}s more",
+                    process.name,
+                    process.pid,
+                    left.as_secs_f64()
+                );
+                decision.action = "paused";
+            } else if config.dry_run {
+                println!(
+                    "Dry run: would kill leaking process name={} pid={} {growing}",
+                    process.name, process.pid
+                );
+                decision.action = "dry_run";
+            } else {
+                let key = loop_key(host.processes.as_ref(), process);
+                capture(config, state, Some(process.pid), None);
+                let Host {
+                    memory, signals, ..
+                } = host;
+                let systemd = state.systemd.as_ref();
+                let stopped = signals.stop_process(process, &config.escalation, false, &mut || {
+                    grace_poll(memory.as_mut(), systemd, config, false)
+                });
+                decision.snapshot =
+                    snapshot(config, state, &decision, std::slice::from_ref(&candidate));
+                match stopped.map(describe_outcome) {
+                    Ok((kind, outcome)) => {
+                        println!(
+                            "Killed leaking process name={} pid={} {growing} outcome={outcome}",
+                            process.name, process.pid
+                        );
+                        state.notifier.killed(
+                            &config.notify,
+                            &process.name,
+                            &detail,
+                            &outcome,
+                            &check.memory.to_string(),
+                        );
+                        decision.action = kind;
+                        decision.outcome = Some(outcome);
+                        decision.kill_loop =
+                            note_kill(host.signals.as_mut(), config, state, &key, now);
+                    }
+                    Err(e) => {
+                        eprintln!(
+                            "Failed to kill leaking process pid={} name={}: {e}",
+                            process.pid, process.name
+                        );
+                        decision.action = "failed";
+                        decision.outcome = Some(e);
+                    }
                 }
             }
-            Ok(None) => {
-                eprintln!("Memory threshold exceeded but no killable process found");
+        }
+        Some(_) => return None,
+    }
+    Some(decision)
+}
+
+/// Counts the decision in the metrics and appends it to the journal, if one is configured;
+/// journal failures are only logged. A manual kill's result goes back to `oomkiller ctl`.
+fn record(config: &Config, state: &mut DaemonState, decision: &Decision) {
+    if let Some(victim) = &decision.victim {
+        state.metrics.record_kill(victim.name(), decision.action);
+    }
+    if decision.trigger == "manual" {
+        let mut result = decision.action.to_string();
+        match &decision.victim {
+            Some(victim) => result.push_str(&format!(" {}", victim.name())),
+            None if decision.action == "none" => result.push_str(": no killable process"),
+            None => {}
+        }
+        if let Some(outcome) = &decision.outcome {
+            result.push_str(&format!(": {outcome}"));
+        }
+        state.control.finish_kill(result);
+    }
+    if let Some(path) = &config.journal.path
+        && let Err(e) = append_decision(path, decision)
+    {
+        eprintln!("{e}");
+    }
+}
+
+/// Renders the metrics for the HTTP listener and, if configured, the node_exporter textfile.
+fn publish_metrics(config: &Config, state: &mut DaemonState) {
+    let text = state.metrics.render();
+    if let Some(path) = &config.metrics.textfile
+        && let Err(e) = write_textfile(path, &text)
+    {
+        eprintln!("{e}");
+    }
+    if let Ok(mut shared) = state.metrics_text.lock() {
+        *shared = text;
+    }
+}
+
+/// Logs (dry run) or stops the chosen app unit. A manual kill is not called off
+/// when memory looks fine during the grace period.
+///
+/// Returns the journal action and outcome.
+fn handle_app(
+    host: &mut Host,
+    config: &Config,
+    state: &mut DaemonState,
+    group: &AppGroup,
+    check: &Check,
+) -> (&'static str, Option<String>) {
+    if config.dry_run {
+        println!(
+            "Dry run: would kill cgroup unit={} memory={} processes={} top={}",
+            group.unit(),
+            format_bytes(group.memory),
+            group.processes.len(),
+            top_members(group),
+        );
+        return ("dry_run", None);
+    }
+    let stopped = {
+        let Host {
+            memory, signals, ..
+        } = host;
+        let mut grace = grace_period(memory.as_mut(), state, config, check, group.unit(), || {
+            app_detail(group)
+        });
+        signals.stop_app(group, &config.escalation, check.critical, &mut grace)
+    };
+    match stopped.map(describe_outcome) {
+        Ok((kind, outcome)) => {
+            println!(
+                "Killed cgroup unit={} memory={} processes={} outcome={outcome} top={}",
+                group.unit(),
+                format_bytes(group.memory),
+                group.processes.len(),
+                top_members(group),
+            );
+            state.notifier.killed(
+                &config.notify,
+                group.unit(),
+                &app_detail(group),
+                &outcome,
+                &check.memory.to_string(),
+            );
+            (kind, Some(outcome))
+        }
+        Err(e) => {
+            eprintln!(
+                "Failed to kill cgroup unit={} memory={}: {e}",
+                group.unit(),
+                format_bytes(group.memory),
+            );
+            ("failed", Some(e))
+        }
+    }
+}
+
+/// Stops the Nix build chosen by [`select_nix_build`]. A build cgroup goes through
+/// the same escalation as an app unit, a client or builder through the per-process
+/// one; either counts towards kill loops.
+///
+/// Returns the decision; its action is `failed` if the build could not be stopped.
+fn act_on_nix_build(
+    host: &mut Host,
+    config: &Config,
+    state: &mut DaemonState,
+    check: &Check,
+    target: NixTarget,
+    ranked: &[Candidate],
+    template: &Decision,
+) -> Decision {
+    let mut decision = template.clone();
+    decision.nix = Some(target.kind());
+    let key = match &target {
+        NixTarget::Cgroup(group) => {
+            let Some(first) = group.processes.first() else {
+                decision.action = "failed";
+                decision.outcome = Some(format!("{} has no processes", group.path));
+                return decision;
+            };
+            println!(
+                "Stopping Nix build cgroup={} memory={} processes={}",
+                group.path,
+                Bytes(group.memory),
+                group.processes.len()
+            );
+            let key = loop_key(host.processes.as_ref(), first);
+            capture(config, state, Some(first.pid), Some(&group.path));
+            (decision.action, decision.outcome) = handle_app(host, config, state, group, check);
+            decision.victim = Some(VictimRecord::cgroup(group));
+            key
+        }
+        NixTarget::Client(candidate) | NixTarget::Builder(candidate) => {
+            let process = &candidate.process;
+            println!(
+                "Stopping Nix {} name={} pid={} rss={}",
+                target.kind(),
+                process.name,
+                process.pid,
+                Bytes(process.memory)
+            );
+            let key = loop_key(host.processes.as_ref(), process);
+            capture(config, state, Some(process.pid), None);
+            (decision.action, decision.outcome) =
+                handle_process(host, config, state, process, check);
+            decision.victim = Some(VictimRecord::process(candidate));
+            key
+        }
+    };
+    decision.snapshot = snapshot(config, state, &decision, ranked);
+    if !config.dry_run && decision.action != "failed" {
+        decision.kill_loop = note_kill(
+            host.signals.as_mut(),
+            config,
+            state,
+            &key,
+            decision.timestamp,
+        );
+    }
+    decision
+}
+
+/// Logs (dry run) or stops a single process outside the normal victim path, like
+/// [`handle_app`] does for a unit.
+///
+/// Returns the journal action and outcome.
+fn handle_process(
+    host: &mut Host,
+    config: &Config,
+    state: &mut DaemonState,
+    process: &ProcessInfo,
+    check: &Check,
+) -> (&'static str, Option<String>) {
+    if config.dry_run {
+        println!(
+            "Dry run: would kill process name={} pid={} rss={}",
+            process.name,
+            process.pid,
+            format_bytes(process.memory),
+        );
+        return ("dry_run", None);
+    }
+    let detail = || format!("PID {}, RSS {}", process.pid, Bytes(process.memory));
+    let stopped = {
+        let Host {
+            memory, signals, ..
+        } = host;
+        let mut grace = grace_period(memory.as_mut(), state, config, check, &process.name, detail);
+        signals.stop_process(process, &config.escalation, check.critical, &mut grace)
+    };
+    match stopped.map(describe_outcome) {
+        Ok((kind, outcome)) => {
+            println!(
+                "Killed process name={} pid={} rss={} outcome={outcome}",
+                process.name,
+                process.pid,
+                Bytes(process.memory),
+            );
+            state.notifier.killed(
+                &config.notify,
+                &process.name,
+                &detail(),
+                &outcome,
+                &check.memory.to_string(),
+            );
+            (kind, Some(outcome))
+        }
+        Err(e) => {
+            eprintln!(
+                "Failed to kill pid={} name={} rss={}: {e}",
+                process.pid,
+                process.name,
+                Bytes(process.memory),
+            );
+            ("failed", Some(e))
+        }
+    }
+}
+
+/// The three largest members of the unit, e.g. `firefox(2000),Web Content(2001)`.
+fn top_members(group: &AppGroup) -> String {
+    group
+        .processes
+        .iter()
+        .take(3)
+        .map(|p| format!("{}({})", p.name, p.pid))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Notification line for an app unit: member count, top members and memory.
+fn app_detail(group: &AppGroup) -> String {
+    format!(
+        "{} processes ({}), {}",
+        group.processes.len(),
+        top_members(group),
+        format_bytes(group.memory)
+    )
+}
+
+/// Whether the victim called `name` is frozen instead of killed: never for a manual
+/// kill, past the critical PSI limit, or within `freeze.rearm_secs` of the user resuming it.
+fn freezes(config: &Config, state: &mut DaemonState, name: &str, check: &Check, now: f64) -> bool {
+    config.freeze.applies_to(name)
+        && !check.manual
+        && !check.critical
+        && !state.freeze.recently_resumed(name, now)
+}
+
+/// Freezes `frozen.target`, remembers it and asks the user what to do with it.
+///
+/// Returns the journal outcome, or `None` if it could not be frozen (the caller kills it instead).
+fn freeze_victim(
+    signals: &mut dyn SignalSink,
+    config: &Config,
+    state: &mut DaemonState,
+    frozen: Frozen,
+    memory_state: &str,
+) -> Option<String> {
+    if let Err(e) = signals.freeze(&frozen.target, true) {
+        eprintln!(
+            "Failed to freeze {}, killing it instead: {e}",
+            frozen.target
+        );
+        return None;
+    }
+    let now = frozen.since;
+    state.freeze.frozen = Some(frozen);
+    ask_about_frozen(config, state, memory_state, now);
+    save_freeze_state(state);
+    Some(format!(
+        "waiting up to {}s for an answer",
+        config.freeze.timeout_secs
+    ))
+}
+
+/// Shows the notification with the Kill and Resume buttons for the frozen victim.
+fn ask_about_frozen(config: &Config, state: &mut DaemonState, memory_state: &str, now: f64) {
+    let Some(frozen) = &state.freeze.frozen else {
+        return;
+    };
+    let left = Duration::from_secs_f64((frozen.deadline - now).max(0.0));
+    state.freeze.answers = state.notifier.frozen(
+        &config.notify,
+        frozen.name(),
+        &frozen.detail,
+        left,
+        memory_state,
+    );
+    state.freeze.asked = true;
+}
+
+/// What [`act_on_frozen`] did with the frozen victim.
+enum FrozenStep {
+    /// It has exited; the normal selection runs.
+    Gone,
+    /// It stays frozen; nothing else is selected.
+    Waiting,
+    /// It was killed or resumed, or that failed; the decision is journaled.
+    Done(Box<Decision>),
+}
+
+/// Decides what happens to the frozen victim; nothing else is selected while one is frozen.
+///
+/// It is killed on a Kill answer, on `oomkiller ctl kill-now`, when nobody answers by its
+/// deadline, once memory has stayed critical for `freeze.critical_secs`, or straight away
+/// when memory reaches `freeze.ceiling_percent` or PSI turns critical. A Resume answer
+/// thaws it. A daemon restarted with a frozen victim asks again.
+///
+/// Returns [`FrozenStep::Gone`] if the victim has exited meanwhile, so the normal
+/// selection runs.
+fn act_on_frozen(
+    host: &mut Host,
+    config: &Config,
+    state: &mut DaemonState,
+    threshold_exceeded: bool,
+    check: &Check,
+    mut decision: Decision,
+) -> FrozenStep {
+    let Some(mut frozen) = state.freeze.frozen.clone() else {
+        return FrozenStep::Gone;
+    };
+    let now = decision.timestamp;
+    if !host.processes.is_alive(&frozen.target) {
+        println!("Frozen {} ({}) has exited", frozen.name(), frozen.target);
+        release_frozen(state, None);
+        return FrozenStep::Gone;
+    }
+    if !state.freeze.asked {
+        ask_about_frozen(config, state, &check.memory.to_string(), now);
+    }
+    let critical_since = threshold_exceeded.then(|| frozen.critical_since.unwrap_or(now));
+    if critical_since != frozen.critical_since {
+        frozen.critical_since = critical_since;
+        state.freeze.frozen = Some(frozen.clone());
+        save_freeze_state(state);
+    }
+
+    decision.victim = Some(frozen.victim.clone());
+    let answer = if check.manual {
+        decision.trigger = "manual";
+        None
+    } else {
+        state.freeze.take_answer()
+    };
+    if answer == Some(Answer::Resume) {
+        match host.signals.freeze(&frozen.target, false) {
+            Ok(()) => {
+                println!(
+                    "Resumed {} ({}) as requested; it is killed rather than frozen for the next {}s",
+                    frozen.name(),
+                    frozen.target,
+                    config.freeze.rearm_secs
+                );
+                release_frozen(state, Some(now + config.freeze.rearm_secs));
+                decision.action = "resumed";
             }
             Err(e) => {
-                eprintln!("Failed to find highest memory process: {e}");
+                eprintln!(
+                    "Failed to resume {} ({}): {e}",
+                    frozen.name(),
+                    frozen.target
+                );
+                decision.action = "failed";
+                decision.outcome = Some(e);
             }
         }
+        return FrozenStep::Done(Box::new(decision));
     }
 
-    Ok(())
+    let reason = if check.manual {
+        "manual kill requested via oomkiller ctl".to_string()
+    } else if answer == Some(Answer::Kill) {
+        "kill requested from the notification".to_string()
+    } else if check.critical {
+        format!(
+            "PSI full avg10 past {}",
+            config.escalation.critical_full_avg10
+        )
+    } else if check.memory.percent >= config.freeze.ceiling_percent {
+        format!(
+            "memory at {:.1}%, past the {}% ceiling",
+            check.memory.percent, config.freeze.ceiling_percent
+        )
+    } else if now >= frozen.deadline {
+        format!("no answer within {}s", config.freeze.timeout_secs)
+    } else if critical_since.is_some_and(|since| now - since >= config.freeze.critical_secs) {
+        format!(
+            "memory still critical after {}s",
+            config.freeze.critical_secs
+        )
+    } else {
+        if threshold_exceeded {
+            println!(
+                "Memory threshold exceeded; {} is frozen, killing it in {:.0}s without an answer",
+                frozen.name(),
+                frozen.deadline - now
+            );
+        }
+        return FrozenStep::Waiting;
+    };
+
+    let (pid, cgroup) = match &frozen.target {
+        FrozenTarget::Process { pid, .. } => (Some(*pid), None),
+        FrozenTarget::Cgroup { path } => (None, Some(path.as_str())),
+    };
+    capture(config, state, pid, cgroup);
+    // Ranked before the kill as well, so the report shows what filled memory.
+    let candidates = if state.capture.is_taken() {
+        host.processes.refresh(config.target);
+        ranked_candidates(host.processes.as_ref(), config).unwrap_or_else(|e| {
+            eprintln!("Failed to rank candidates for the snapshot: {e}");
+            Vec::new()
+        })
+    } else {
+        Vec::new()
+    };
+    let killed = frozen
+        .target
+        .kill(frozen.name(), host.signals.as_mut(), &config.escalation);
+    decision.snapshot = snapshot(config, state, &decision, &candidates);
+
+    let signals = host.signals.as_mut();
+    match killed.map(describe_outcome) {
+        Ok((kind, outcome)) => {
+            println!(
+                "Killed frozen {} ({}): {reason}, outcome={outcome}",
+                frozen.name(),
+                frozen.target
+            );
+            state.notifier.killed(
+                &config.notify,
+                frozen.name(),
+                &frozen.detail,
+                &outcome,
+                &check.memory.to_string(),
+            );
+            release_frozen(state, None);
+            decision.action = kind;
+            decision.outcome = Some(format!("{reason}; {outcome}"));
+            decision.kill_loop = note_kill(signals, config, state, &frozen.loop_key, now);
+        }
+        Err(e) => {
+            eprintln!(
+                "Failed to kill frozen {} ({}): {e}",
+                frozen.name(),
+                frozen.target
+            );
+            decision.action = "failed";
+            decision.outcome = Some(e);
+        }
+    }
+    FrozenStep::Done(Box::new(decision))
+}
+
+/// Reads the files of the report into `state.capture` when `snapshot.enabled` (never
+/// in dry-run mode), right before the victim is signalled; does not allocate.
+///
+/// # Arguments
+/// * `pid` - Victim process, or an app unit's largest member
+/// * `cgroup` - Victim's cgroup path; `None` reads `pid`'s
+fn capture(config: &Config, state: &mut DaemonState, pid: Option<u32>, cgroup: Option<&str>) {
+    if config.snapshot.enabled && !config.dry_run {
+        state.capture.take(pid, cgroup);
+    }
+}
+
+/// Writes the report from the files [`capture`] read, once the victim has been
+/// signalled; failures are only logged.
+///
+/// # Arguments
+/// * `candidates` - The ranking the victim was chosen from
+///
+/// # Returns
+/// The report's path, for the journal, or `None` without a capture.
+fn snapshot(
+    config: &Config,
+    state: &mut DaemonState,
+    decision: &Decision,
+    candidates: &[Candidate],
+) -> Option<PathBuf> {
+    if !state.capture.is_taken() {
+        return None;
+    }
+    let input = SnapshotInput {
+        decision,
+        candidates,
+    };
+    match write_snapshot(&config.snapshot, &input, &mut state.capture) {
+        Ok(path) => {
+            println!("Wrote snapshot {}", path.display());
+            Some(path)
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            None
+        }
+    }
+}
+
+/// Forgets the frozen victim (see [`FreezeState::release`](crate::freeze::FreezeState::release)), closes its notification
+/// and saves the freeze state.
+fn release_frozen(state: &mut DaemonState, resumed_until: Option<f64>) {
+    state.freeze.release(resumed_until);
+    state.notifier.close_frozen();
+    save_freeze_state(state);
+}
+
+/// Saves the freeze state if it has a path; failures are only logged.
+fn save_freeze_state(state: &DaemonState) {
+    if let Some(path) = &state.freeze_path
+        && let Err(e) = state.freeze.save(path)
+    {
+        eprintln!("{e}");
+    }
+}
+
+/// What kills of `process` count against in the kill history; read before it is killed.
+fn loop_key(processes: &dyn ProcessTable, process: &ProcessInfo) -> LoopKey {
+    LoopKey::new(
+        process,
+        processes.cgroup_path(process.pid).as_deref(),
+        processes.executable(process.pid).as_deref(),
+    )
+}
+
+/// Records a successful kill of `key` at `now` and backs off if it completed a kill loop:
+/// the unit is stopped (`stop_unit`) or the target is left alone for `cooldown_secs`.
+/// The history is saved either way.
+///
+/// Returns the loop description for the journal, if a loop was detected.
+fn note_kill(
+    signals: &mut dyn SignalSink,
+    config: &Config,
+    state: &mut DaemonState,
+    key: &LoopKey,
+    now: f64,
+) -> Option<String> {
+    let loop_config = &config.kill_loop;
+    let detected = state
+        .history
+        .record(&key.key, now, loop_config)
+        .map(|kills| {
+            let mut response = None;
+            if loop_config.action == LoopAction::StopUnit
+                && let Some(unit) = &key.unit
+            {
+                match signals.stop_unit(unit) {
+                    Ok(()) => {
+                        state.history.forget(&key.key);
+                        response = Some(format!("stopped unit {}", unit.name));
+                    }
+                    Err(e) => eprintln!("Kill loop: {e}; cooling down instead"),
+                }
+            }
+            let response = response.unwrap_or_else(|| {
+                state
+                    .history
+                    .start_cooldown(&key.key, now + loop_config.cooldown_secs);
+                format!("not killing it again for {}s", loop_config.cooldown_secs)
+            });
+            println!(
+                "Kill loop: {} killed {kills} times within {}s, {response}",
+                key.key, loop_config.window_secs
+            );
+            state
+                .notifier
+                .kill_loop(&config.notify, &key.key, kills, &response);
+            state.metrics.kill_loops += 1;
+            format!(
+                "{kills} kills within {}s, {response}",
+                loop_config.window_secs
+            )
+        });
+    if let Some(path) = &state.history_path
+        && let Err(e) = state.history.save(path)
+    {
+        eprintln!("{e}");
+    }
+    detected
+}
+
+/// What a kill of `victim` asks every `escalation.poll_ms` of its grace period (see
+/// [`grace_poll`]). The first call, right after SIGTERM, also sends the SIGTERM warning;
+/// its `detail` is only built then, so nothing is allocated for it before the signal.
+fn grace_period<'a>(
+    memory: &'a mut dyn MemorySource,
+    state: &'a mut DaemonState,
+    config: &'a Config,
+    check: &'a Check,
+    victim: &'a str,
+    detail: impl FnOnce() -> String + 'a,
+) -> impl FnMut() -> bool + 'a {
+    let mut detail = Some(detail);
+    move || {
+        if let Some(detail) = detail.take() {
+            state.notifier.on_sigterm(
+                &config.notify,
+                victim,
+                &detail(),
+                config.escalation.grace(),
+                &check.memory.to_string(),
+            );
+        }
+        grace_poll(memory, state.systemd.as_ref(), config, !check.manual)
+    }
+}
+
+/// Asked every `escalation.poll_ms` of a grace period: pings the systemd watchdog, which a
+/// long grace period would otherwise outlast, and, if the kill may be called off, re-checks
+/// memory.
+fn grace_poll(
+    memory: &mut dyn MemorySource,
+    systemd: Option<&SystemdNotifier>,
+    config: &Config,
+    call_off: bool,
+) -> bool {
+    if let Some(Err(e)) = systemd.map(SystemdNotifier::ping) {
+        eprintln!("{e}");
+    }
+    call_off && memory_recovered(memory, config)
+}
+
+/// Re-checks memory during a grace period; a failed read is treated as still critical.
+fn memory_recovered(memory: &mut dyn MemorySource, config: &Config) -> bool {
+    let psi = memory.pressure();
+    match memory.snapshot(&config.memory) {
+        Ok(snapshot) => !evaluate_trigger(&snapshot, config, psi.as_ref()),
+        Err(_) => false,
+    }
+}
+
+/// Splits a kill outcome into its kind and `<kind> after=<secs>s` for the kill log line.
+fn describe_outcome(outcome: KillOutcome) -> (&'static str, String) {
+    let after = match outcome {
+        KillOutcome::Terminated { after }
+        | KillOutcome::Recovered { after }
+        | KillOutcome::Killed { after, .. } => after,
+    };
+    let kind = outcome.as_str();
+    (kind, format!("{kind} after={:.2}s", after.as_secs_f64()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::control::ProtectTarget;
+    use crate::fake_host::{FakeMemory, FakeProcesses, GIB, RecordingSink, Stopped, fake_host};
+    use crate::notify::{KillNotifier, Notice};
+    use crate::psi::PsiSnapshot;
+    use std::sync::{Arc, Mutex};
+
+    const FIREFOX: &str =
+        "/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox.scope";
+    const SYNC: &str = "/user.slice/user-1000.slice/user@1000.service/app.slice/sync.service";
+
+    /// firefox (3 GiB) is the obvious victim; systemd is larger but protected.
+    fn desktop() -> FakeProcesses {
+        let mut processes = FakeProcesses::default();
+        processes
+            .add(1000, "systemd", 8 * GIB)
+            .add_in(2000, "firefox", 3 * GIB, Some(FIREFOX))
+            .add(3000, "bash", GIB / 10);
+        processes
+    }
+
+    fn state() -> (DaemonState, Arc<Mutex<Vec<Notice>>>) {
+        let notices = Arc::new(Mutex::new(Vec::new()));
+        let state = DaemonState {
+            notifier: KillNotifier::new(Box::new(RecordingSink(Arc::clone(&notices)))),
+            ..DaemonState::default()
+        };
+        (state, notices)
+    }
+
+    fn stopped_pid(pid: u32) -> Stopped {
+        Stopped::Process {
+            pid,
+            critical: false,
+        }
+    }
+
+    #[test]
+    fn test_below_threshold_stops_nothing() {
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[50.0]), desktop());
+        let (mut state, notices) = state();
+        daemon_iteration(&mut host, &Config::default(), &mut state).unwrap();
+        assert!(stopped.lock().unwrap().is_empty());
+        assert!(notices.lock().unwrap().is_empty());
+        assert_eq!(state.metrics.iterations, 1);
+        assert_eq!(state.metrics.breaches, 0);
+        assert_eq!(state.metrics.used_percent, 50.0);
+    }
+
+    #[test]
+    fn test_largest_process_killed_protected_survives() {
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), desktop());
+        let (mut state, notices) = state();
+        daemon_iteration(&mut host, &Config::default(), &mut state).unwrap();
+
+        assert_eq!(*stopped.lock().unwrap(), [stopped_pid(2000)]);
+        assert_eq!(state.metrics.breaches, 1);
+        let notices = notices.lock().unwrap();
+        assert!(
+            notices
+                .iter()
+                .any(|n| n.critical && n.summary.contains("firefox"))
+        );
+    }
+
+    #[test]
+    fn test_memory_recovering_during_grace_is_recorded() {
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0, 60.0]), desktop());
+        let (mut state, _) = state();
+        let path =
+            std::env::temp_dir().join(format!("oomkiller-recover-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut config = Config::default();
+        config.journal.path = Some(path.clone());
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+
+        assert_eq!(*stopped.lock().unwrap(), [stopped_pid(2000)]);
+        let text = std::fs::read_to_string(&path).unwrap();
+        let value: serde_json::Value = serde_json::from_str(text.trim()).unwrap();
+        assert_eq!(value["action"], "recovered");
+        assert_eq!(value["victim"]["pid"], 2000);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_grace_period_pings_the_watchdog() {
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), desktop());
+        let (mut state, _) = state();
+        let path = std::env::temp_dir().join(format!("oomkiller-grace-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let systemd = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+        systemd.set_nonblocking(true).unwrap();
+        state.systemd = Some(
+            SystemdNotifier::connect(path.to_str().unwrap(), Some(Duration::from_secs(30)))
+                .unwrap(),
+        );
+        daemon_iteration(&mut host, &Config::default(), &mut state).unwrap();
+
+        assert_eq!(*stopped.lock().unwrap(), [stopped_pid(2000)]);
+        let mut buf = [0; 64];
+        let n = systemd.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"WATCHDOG=1");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_critical_pressure_skips_grace_period() {
+        let mut memory = FakeMemory::new(&[95.0]);
+        let mut psi = PsiSnapshot::default();
+        psi.full.avg10 = 100.0;
+        memory.psi = Some(psi);
+        let (mut host, stopped) = fake_host(memory, desktop());
+        let (mut state, notices) = state();
+        daemon_iteration(&mut host, &Config::default(), &mut state).unwrap();
+
+        assert_eq!(
+            *stopped.lock().unwrap(),
+            [Stopped::Process {
+                pid: 2000,
+                critical: true
+            }]
+        );
+        // No SIGTERM warning, only the kill itself.
+        assert_eq!(notices.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_only_protected_processes_stops_nothing() {
+        let mut processes = FakeProcesses::default();
+        processes.add(1000, "systemd", 8 * GIB);
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), processes);
+        let (mut state, _) = state();
+        daemon_iteration(&mut host, &Config::default(), &mut state).unwrap();
+        assert!(stopped.lock().unwrap().is_empty());
+        assert_eq!(state.metrics.breaches, 1);
+    }
+
+    #[test]
+    fn test_dry_run_writes_journal_but_never_signals() {
+        let path =
+            std::env::temp_dir().join(format!("oomkiller-iter-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut config = Config {
+            dry_run: true,
+            ..Config::default()
+        };
+        config.journal.path = Some(path.clone());
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), desktop());
+        let (mut state, notices) = state();
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+
+        assert!(stopped.lock().unwrap().is_empty());
+        assert!(notices.lock().unwrap().is_empty());
+        let text = std::fs::read_to_string(&path).unwrap();
+        let value: serde_json::Value = serde_json::from_str(text.trim()).unwrap();
+        assert_eq!(value["dry_run"], true);
+        assert_eq!(value["action"], "dry_run");
+        assert_eq!(value["victim"]["name"], "firefox");
+        assert_eq!(value["candidates"].as_array().unwrap().len(), 3);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_cgroup_mode_stops_app_unit() {
+        let mut processes = desktop();
+        processes.delegated = true;
+        processes.add_in(2001, "Web Content", GIB, Some(FIREFOX));
+        let config = Config {
+            group_by: GroupBy::Cgroup,
+            ..Config::default()
+        };
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), processes);
+        let (mut state, _) = state();
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+        assert_eq!(
+            *stopped.lock().unwrap(),
+            [Stopped::App {
+                unit: "app-firefox.scope".to_string(),
+                critical: false
+            }]
+        );
+    }
+
+    #[test]
+    fn test_cgroup_mode_falls_back_without_delegation() {
+        let config = Config {
+            group_by: GroupBy::Cgroup,
+            ..Config::default()
+        };
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), desktop());
+        let (mut state, _) = state();
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+        assert_eq!(*stopped.lock().unwrap(), [stopped_pid(2000)]);
+    }
+
+    #[test]
+    fn test_nix_build_stopped_before_apps() {
+        let mut processes = desktop();
+        processes
+            .add_in(
+                5000,
+                "nix-daemon",
+                GIB / 10,
+                Some("/system.slice/nix-daemon.service"),
+            )
+            .add_in(5001, "make", GIB, Some("/system.slice/nix-daemon.service"));
+        let mut config = Config::default();
+        config.nix.enabled = true;
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0, 95.0]), processes);
+        let (mut state, _) = state();
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+        // firefox is larger, but the build goes first.
+        assert_eq!(*stopped.lock().unwrap(), [stopped_pid(5001)]);
+
+        config.nix.enabled = false;
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+        assert_eq!(stopped.lock().unwrap()[1], stopped_pid(2000));
+    }
+
+    /// A user service that is restarted after every kill, plus a small shell.
+    fn restarting_service() -> FakeProcesses {
+        let mut processes = FakeProcesses::default();
+        processes
+            .add_in(4000, "sync", 4 * GIB, Some(SYNC))
+            .add(3000, "bash", GIB / 10);
+        processes
+    }
+
+    fn kill_loop_config(action: LoopAction) -> Config {
+        let mut config = Config::default();
+        config.kill_loop.action = action;
+        config
+    }
+
+    #[test]
+    fn test_kill_loop_skips_to_next_candidate() {
+        let config = kill_loop_config(LoopAction::Skip);
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), restarting_service());
+        let (mut state, notices) = state();
+        for _ in 0..4 {
+            daemon_iteration(&mut host, &config, &mut state).unwrap();
+        }
+
+        assert_eq!(
+            *stopped.lock().unwrap(),
+            [
+                stopped_pid(4000),
+                stopped_pid(4000),
+                stopped_pid(4000),
+                stopped_pid(3000)
+            ]
+        );
+        assert_eq!(state.metrics.kill_loops, 1);
+        assert!(state.history.cooldown_until("sync.service", 0.0).is_some());
+        assert!(
+            notices
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|n| n.summary == "oomkiller: sync.service keeps restarting")
+        );
+    }
+
+    #[test]
+    fn test_kill_loop_cooldown_kills_nothing() {
+        let path =
+            std::env::temp_dir().join(format!("oomkiller-loop-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut config = kill_loop_config(LoopAction::Cooldown);
+        config.journal.path = Some(path.clone());
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), restarting_service());
+        let (mut state, _) = state();
+        for _ in 0..4 {
+            daemon_iteration(&mut host, &config, &mut state).unwrap();
+        }
+
+        assert_eq!(stopped.lock().unwrap().len(), 3);
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert!(lines[1]["kill_loop"].is_null());
+        assert!(
+            lines[2]["kill_loop"]
+                .as_str()
+                .unwrap()
+                .starts_with("3 kills")
+        );
+        assert_eq!(lines[3]["action"], "cooldown");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_kill_loop_stops_unit() {
+        let config = kill_loop_config(LoopAction::StopUnit);
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), restarting_service());
+        let (mut state, _) = state();
+        for _ in 0..3 {
+            daemon_iteration(&mut host, &config, &mut state).unwrap();
+        }
+
+        assert_eq!(
+            stopped.lock().unwrap().last(),
+            Some(&Stopped::Unit {
+                name: "sync.service".to_string(),
+                user: true
+            })
+        );
+        assert_eq!(state.metrics.kill_loops, 1);
+    }
+
+    #[test]
+    fn test_paused_kills_nothing() {
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), desktop());
+        let (mut state, _) = state();
+        state.control.pause(std::time::Duration::from_secs(60));
+        daemon_iteration(&mut host, &Config::default(), &mut state).unwrap();
+        assert!(stopped.lock().unwrap().is_empty());
+        assert_eq!(state.metrics.breaches, 1);
+    }
 
     #[test]
-    fn test_daemon_iteration_returns_result() {
-        let mut system = System::new_all();
-        let result = daemon_iteration(&mut system);
-        assert!(result.is_ok() || result.is_err());
+    fn test_protected_process_is_passed_over() {
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), desktop());
+        let (mut state, _) = state();
+        state.control.protect(ProtectTarget::parse("firefox"));
+        daemon_iteration(&mut host, &Config::default(), &mut state).unwrap();
+        assert_eq!(*stopped.lock().unwrap(), [stopped_pid(3000)]);
     }
 
     #[test]
-    fn test_daemon_iteration_handles_memory_check() {
-        let mut system = System::new_all();
-        let result = daemon_iteration(&mut system);
-        assert!(result.is_ok() || result.is_err());
+    fn test_kill_now_below_threshold_runs_selection() {
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[50.0]), desktop());
+        let (mut state, _) = state();
+        let control = Arc::clone(&state.control);
+        let requester =
+            std::thread::spawn(move || control.request_kill(std::time::Duration::from_secs(5)));
+        while !requester.is_finished() {
+            daemon_iteration(&mut host, &Config::default(), &mut state).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+
+        assert_eq!(*stopped.lock().unwrap(), [stopped_pid(2000)]);
+        assert_eq!(state.metrics.breaches, 0);
+        assert_eq!(
+            requester.join().unwrap().as_deref(),
+            Some("killed firefox: killed after=3.00s")
+        );
+    }
+
+    fn freeze_config() -> Config {
+        let mut config = Config::default();
+        config.freeze.enabled = true;
+        config.freeze.apps = crate::badness::Patterns::new(&["^firefox$"]).unwrap();
+        config
+    }
+
+    const FROZEN_FIREFOX: FrozenTarget = FrozenTarget::Process {
+        pid: 2000,
+        start_time: 2000,
+    };
+
+    /// Runs one iteration with `action` pressed on the freeze notification.
+    fn answer(host: &mut Host, config: &Config, state: &mut DaemonState, action: &str) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        tx.send(action.to_string()).unwrap();
+        state.freeze.answers = Some(rx);
+        daemon_iteration(host, config, state).unwrap();
+    }
+
+    #[test]
+    fn test_freeze_then_kill_on_answer() {
+        let config = freeze_config();
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0, 80.0]), desktop());
+        let (mut state, notices) = state();
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+        assert_eq!(
+            *stopped.lock().unwrap(),
+            [Stopped::Frozen {
+                target: FROZEN_FIREFOX,
+                frozen: true
+            }]
+        );
+        assert_eq!(state.freeze.frozen.as_ref().unwrap().name(), "firefox");
+        assert_eq!(notices.lock().unwrap()[0].actions.len(), 2);
+
+        // Nothing else is selected while waiting.
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+        assert_eq!(stopped.lock().unwrap().len(), 1);
+
+        answer(&mut host, &config, &mut state, "kill");
+        assert_eq!(
+            stopped.lock().unwrap().last(),
+            Some(&Stopped::Process {
+                pid: 2000,
+                critical: true
+            })
+        );
+        assert!(state.freeze.frozen.is_none());
+    }
+
+    #[test]
+    fn test_resumed_victim_is_killed_next_time() {
+        let config = freeze_config();
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0, 60.0, 95.0]), desktop());
+        let (mut state, _) = state();
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+        answer(&mut host, &config, &mut state, "resume");
+        assert!(state.freeze.frozen.is_none());
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+        assert_eq!(
+            stopped.lock().unwrap()[1..],
+            [
+                Stopped::Frozen {
+                    target: FROZEN_FIREFOX,
+                    frozen: false
+                },
+                stopped_pid(2000)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_frozen_victim_killed_without_answer() {
+        for (timeout_secs, critical_secs) in [(0.0, 3600.0), (3600.0, 0.0)] {
+            let mut config = freeze_config();
+            config.freeze.timeout_secs = timeout_secs;
+            config.freeze.critical_secs = critical_secs;
+            let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), desktop());
+            let (mut state, _) = state();
+            daemon_iteration(&mut host, &config, &mut state).unwrap();
+            daemon_iteration(&mut host, &config, &mut state).unwrap();
+            assert_eq!(
+                stopped.lock().unwrap().last(),
+                Some(&Stopped::Process {
+                    pid: 2000,
+                    critical: true
+                })
+            );
+            assert!(state.freeze.frozen.is_none());
+        }
+    }
+
+    #[test]
+    fn test_frozen_victim_killed_at_ceiling() {
+        let mut critical = PsiSnapshot::default();
+        critical.full.avg10 = 100.0;
+        for (percent, psi) in [(98.0, None), (95.0, Some(critical))] {
+            let config = freeze_config();
+            let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), desktop());
+            let (mut state, _) = state();
+            daemon_iteration(&mut host, &config, &mut state).unwrap();
+            daemon_iteration(&mut host, &config, &mut state).unwrap();
+            assert_eq!(stopped.lock().unwrap().len(), 1);
+
+            // Something else keeps allocating: no waiting out `critical_secs`.
+            let mut memory = FakeMemory::new(&[percent]);
+            memory.psi = psi;
+            host.memory = Box::new(memory);
+            daemon_iteration(&mut host, &config, &mut state).unwrap();
+            assert_eq!(
+                stopped.lock().unwrap().last(),
+                Some(&Stopped::Process {
+                    pid: 2000,
+                    critical: true
+                })
+            );
+            assert!(state.freeze.frozen.is_none());
+        }
+    }
+
+    #[test]
+    fn test_exited_frozen_victim_is_forgotten() {
+        let config = freeze_config();
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), desktop());
+        let (mut state, _) = state();
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+        let mut processes = FakeProcesses::default();
+        processes.add(3000, "bash", GIB / 10);
+        host.processes = Box::new(processes);
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+        assert!(state.freeze.frozen.is_none());
+        assert_eq!(stopped.lock().unwrap().last(), Some(&stopped_pid(3000)));
+    }
+
+    #[test]
+    fn test_snapshot_written_before_kill() {
+        let dir = std::env::temp_dir().join(format!("oomkiller-iter-snap-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("decisions.jsonl");
+        let mut config = Config::default();
+        config.snapshot.enabled = true;
+        config.snapshot.dir = Some(dir.clone());
+        config.journal.path = Some(path.clone());
+        std::fs::create_dir_all(&dir).unwrap();
+        let (mut host, _) = fake_host(FakeMemory::new(&[95.0]), desktop());
+        let (mut state, _) = state();
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let value: serde_json::Value = serde_json::from_str(text.trim()).unwrap();
+        let report = std::fs::read_to_string(value["snapshot"].as_str().unwrap()).unwrap();
+        assert!(report.contains(r#"victim {"kind":"process","pid":2000,"name":"firefox""#));
+        assert!(report.contains("=== top 20 by uss ==="));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_tree_kills_descendants_with_victim() {
+        let mut processes = FakeProcesses::default();
+        processes
+            .add(100, "make", GIB / 10)
+            .add_child(100, 101, "cc1plus", 2 * GIB)
+            .add_child(100, 102, "cc1plus", 2 * GIB)
+            .add_child(100, 103, "kitty", GIB / 10);
+        let mut config = Config::default();
+        config.tree.enabled = true;
+        config.tree.apps = crate::badness::Patterns::new(&["^cc1plus$"]).unwrap();
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[95.0]), processes);
+        let (mut state, _) = state();
+        // A cc1plus has no children; a matching victim with none is killed alone.
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+        assert_eq!(*stopped.lock().unwrap(), [stopped_pid(101)]);
+
+        config.tree.apps = crate::badness::Patterns::default();
+        config.scoring.prefer = crate::badness::Patterns::new(&["^make$"]).unwrap();
+        config.scoring.prefer_bonus = 2000;
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+        assert_eq!(
+            stopped.lock().unwrap()[1],
+            Stopped::Tree {
+                pid: 100,
+                descendants: vec![101, 102],
+                critical: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_leaking_process_is_warned_then_killed() {
+        let mut processes = desktop();
+        processes.add(4000, "electron", 2 * GIB);
+        let mut config = Config::default();
+        config.leak.enabled = true;
+        config.leak.action = LeakAction::Kill;
+        config.leak.kill_after_secs = 0.0;
+        // Sample on every iteration.
+        config.leak.sample_secs = 0.0;
+        let (mut host, stopped) = fake_host(FakeMemory::new(&[50.0]), processes);
+        let (mut state, notices) = state();
+        // Ten minutes of history, 50 MiB more every 30s, up to the current 2 GiB.
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs_f64();
+        for i in 0..20 {
+            let electron = ProcessInfo {
+                pid: 4000,
+                memory: GIB + i * 50 * 1024 * 1024,
+                name: "electron".to_string(),
+                start_time: 4000,
+                ..ProcessInfo::default()
+            };
+            state
+                .leaks
+                .sample(now - 600.0 + i as f64 * 30.0, &[electron], &config.leak);
+        }
+
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+        assert!(stopped.lock().unwrap().is_empty());
+        assert_eq!(state.metrics.leaking, 1);
+        assert_eq!(
+            notices.lock().unwrap()[0].summary,
+            "electron keeps using more memory"
+        );
+
+        daemon_iteration(&mut host, &config, &mut state).unwrap();
+        assert_eq!(*stopped.lock().unwrap(), [stopped_pid(4000)]);
+        assert_eq!(
+            state.metrics.kills[&("electron".into(), "killed".into())],
+            1
+        );
     }
 
     #[test]
-    fn test_daemon_iteration_completes() {
-        let mut system = System::new_all();
-        let _result = daemon_iteration(&mut system);
+    fn test_memory_read_failure_is_error() {
+        let mut memory = FakeMemory::new(&[95.0]);
+        memory.broken = true;
+        let (mut host, stopped) = fake_host(memory, desktop());
+        let (mut state, _) = state();
+        assert!(daemon_iteration(&mut host, &Config::default(), &mut state).is_err());
+        assert!(stopped.lock().unwrap().is_empty());
     }
 
     #[test]
@@ -0,0 +1,40 @@
+use crate::control::Control;
+use crate::freeze::FreezeState;
+use crate::kill_history::KillHistory;
+use crate::leak::LeakTracker;
+use crate::metrics::Metrics;
+use crate::notify::KillNotifier;
+use crate::poll_interval::AdaptiveInterval;
+use crate::sd_notify::SystemdNotifier;
+use crate::snapshot::Capture;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// State the daemon keeps between iterations (config reloads do not reset it).
+#[derive(Default)]
+pub struct DaemonState {
+    /// Rate-limited desktop notifications for kills.
+    pub notifier: KillNotifier,
+    /// Counters and gauges for Prometheus.
+    pub metrics: Metrics,
+    /// Latest rendered metrics, shared with the HTTP listener.
+    pub metrics_text: Arc<Mutex<String>>,
+    /// Recent kills and cooldowns, for kill-loop detection.
+    pub history: KillHistory,
+    /// Where `history` is saved after each kill; `None` keeps it in memory only.
+    pub history_path: Option<PathBuf>,
+    /// Pause, protections and manual kills from `oomkiller ctl`, shared with the socket thread.
+    pub control: Arc<Control>,
+    /// The victim frozen while the user is asked about it, if any.
+    pub freeze: FreezeState,
+    /// Where `freeze` is saved whenever it changes; `None` keeps it in memory only.
+    pub freeze_path: Option<PathBuf>,
+    /// Memory history of every candidate, for leak detection.
+    pub leaks: LeakTracker,
+    /// Sleep before the next iteration, from headroom and memory trend.
+    pub poll: AdaptiveInterval,
+    /// Files of the next snapshot, read right before a kill (see `[snapshot]`).
+    pub capture: Capture,
+    /// `$NOTIFY_SOCKET` under `Type=notify`; the watchdog is also pinged during grace periods.
+    pub systemd: Option<SystemdNotifier>,
+}
@@ -0,0 +1,289 @@
+use serde::Deserialize;
+use std::fs;
+
+/// How "used memory" is measured for `threshold_percent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Accounting {
+    /// RAM only: used / total as reported by sysinfo.
+    #[default]
+    Ram,
+    /// RAM plus swap: `MemAvailable` + free swap, with zram weighted by its compression ratio.
+    Effective,
+}
+
+impl Accounting {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ram => "ram",
+            Self::Effective => "effective",
+        }
+    }
+}
+
+/// `[memory]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MemoryConfig {
+    /// What `threshold_percent` is measured against.
+    pub accounting: Accounting,
+    /// zram compression ratio assumed while the device holds too little data to measure one.
+    pub zram_ratio: f64,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            accounting: Accounting::Ram,
+            zram_ratio: 2.0,
+        }
+    }
+}
+
+impl MemoryConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.zram_ratio >= 1.0 && self.zram_ratio <= 20.0) {
+            return Err(format!(
+                "memory.zram_ratio must be between 1 and 20, got {}",
+                self.zram_ratio
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// One line of `/proc/swaps`; sizes in bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapDevice {
+    pub name: String,
+    pub size: u64,
+    pub used: u64,
+}
+
+impl SwapDevice {
+    pub fn is_zram(&self) -> bool {
+        self.name.starts_with("/dev/zram")
+    }
+
+    pub fn free(&self) -> u64 {
+        self.size.saturating_sub(self.used)
+    }
+}
+
+/// RAM plus swap, with zram counted at what it can actually free.
+///
+/// Swapping `n` bytes into zram frees `n` bytes of RAM but costs `n / ratio`
+/// bytes of compressed storage, so free zram swap is worth `free * (1 - 1/ratio)`.
+/// Disk swap counts in full.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EffectiveMemory {
+    pub total: u64,
+    pub available: u64,
+    /// Measured (or assumed) zram compression ratio; `None` without zram swap.
+    pub zram_ratio: Option<f64>,
+}
+
+impl EffectiveMemory {
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.available)
+    }
+
+    pub fn used_percent(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.used() as f64 / self.total as f64 * 100.0
+    }
+}
+
+/// Reads `/proc/meminfo`, `/proc/swaps` and zram `mm_stat` and combines them.
+///
+/// # Returns
+/// * `Ok(EffectiveMemory)` with RAM and weighted swap
+/// * `Err(String)` if `/proc/meminfo` or `/proc/swaps` cannot be read
+pub fn read_effective_memory(config: &MemoryConfig) -> Result<EffectiveMemory, String> {
+    let meminfo = fs::read_to_string("/proc/meminfo")
+        .map_err(|e| format!("Failed to read /proc/meminfo: {e}"))?;
+    let swaps = fs::read_to_string("/proc/swaps")
+        .map_err(|e| format!("Failed to read /proc/swaps: {e}"))?;
+    let mem_total = meminfo_field(&meminfo, "MemTotal").ok_or("/proc/meminfo has no MemTotal")?;
+    let mem_available =
+        meminfo_field(&meminfo, "MemAvailable").ok_or("/proc/meminfo has no MemAvailable")?;
+    let devices = parse_swaps(&swaps);
+    let zram_ratio = if devices.iter().any(SwapDevice::is_zram) {
+        Some(read_zram_ratio().unwrap_or(config.zram_ratio))
+    } else {
+        None
+    };
+    Ok(combine(mem_total, mem_available, &devices, zram_ratio))
+}
+
+/// Pure combination rule behind `read_effective_memory`.
+pub fn combine(
+    mem_total: u64,
+    mem_available: u64,
+    devices: &[SwapDevice],
+    zram_ratio: Option<f64>,
+) -> EffectiveMemory {
+    let zram_weight = zram_ratio.map_or(0.0, |r| 1.0 - 1.0 / r.max(1.0));
+    let mut total = mem_total as f64;
+    let mut available = mem_available as f64;
+    for device in devices {
+        let weight = if device.is_zram() { zram_weight } else { 1.0 };
+        total += device.size as f64 * weight;
+        available += device.free() as f64 * weight;
+    }
+    EffectiveMemory {
+        total: total as u64,
+        available: (available as u64).min(total as u64),
+        zram_ratio,
+    }
+}
+
+/// Reads a `Key:  1234 kB` line of `/proc/meminfo` (or `/proc/<pid>/status`) as bytes.
+pub fn meminfo_field(text: &str, key: &str) -> Option<u64> {
+    text.lines().find_map(|line| {
+        let value = line.strip_prefix(key)?.strip_prefix(':')?;
+        let kib: u64 = value.split_whitespace().next()?.parse().ok()?;
+        Some(kib * 1024)
+    })
+}
+
+/// Parses `/proc/swaps` (sizes in KiB) into devices with byte sizes.
+pub fn parse_swaps(text: &str) -> Vec<SwapDevice> {
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            Some(SwapDevice {
+                name: fields.first()?.to_string(),
+                size: fields.get(2)?.parse::<u64>().ok()? * 1024,
+                used: fields.get(3)?.parse::<u64>().ok()? * 1024,
+            })
+        })
+        .collect()
+}
+
+/// Overall compression ratio of all zram devices: original data / memory actually used.
+///
+/// Returns `None` while the devices hold too little data (< 1 MiB) for a meaningful ratio.
+pub fn read_zram_ratio() -> Option<f64> {
+    let mut orig = 0u64;
+    let mut used = 0u64;
+    for entry in fs::read_dir("/sys/block").ok()?.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with("zram") {
+            continue;
+        }
+        let Ok(text) = fs::read_to_string(entry.path().join("mm_stat")) else {
+            continue;
+        };
+        if let Some((o, u)) = parse_mm_stat(&text) {
+            orig += o;
+            used += u;
+        }
+    }
+    (orig >= 1024 * 1024 && used > 0).then(|| orig as f64 / used as f64)
+}
+
+/// Extracts `orig_data_size` and `mem_used_total` (fields 1 and 3) from a zram `mm_stat`.
+pub fn parse_mm_stat(text: &str) -> Option<(u64, u64)> {
+    let mut fields = text.split_whitespace();
+    let orig = fields.next()?.parse().ok()?;
+    let _compr: u64 = fields.next()?.parse().ok()?;
+    let used = fields.next()?.parse().ok()?;
+    Some((orig, used))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    fn zram(size: u64, used: u64) -> SwapDevice {
+        SwapDevice {
+            name: "/dev/zram0".to_string(),
+            size,
+            used,
+        }
+    }
+
+    #[test]
+    fn test_meminfo_field() {
+        let text = "MemTotal:       16000000 kB\nMemAvailable:    4000000 kB\n";
+        assert_eq!(meminfo_field(text, "MemTotal"), Some(16_000_000 * 1024));
+        assert_eq!(meminfo_field(text, "MemAvailable"), Some(4_000_000 * 1024));
+        assert_eq!(meminfo_field(text, "Mem"), None);
+        assert_eq!(
+            meminfo_field("VmSwap:\t  512 kB\n", "VmSwap"),
+            Some(512 * 1024)
+        );
+    }
+
+    #[test]
+    fn test_parse_swaps() {
+        let text = "Filename\tType\tSize\tUsed\tPriority\n\
+                    /dev/zram0  partition  8388604  1048576  100\n\
+                    /swapfile   file       2097148  0        -2\n";
+        let devices = parse_swaps(text);
+        assert_eq!(devices.len(), 2);
+        assert!(devices[0].is_zram());
+        assert_eq!(devices[0].used, 1048576 * 1024);
+        assert!(!devices[1].is_zram());
+        assert_eq!(devices[1].free(), 2097148 * 1024);
+    }
+
+    #[test]
+    fn test_parse_mm_stat() {
+        let text = "  4194304  1048576  1310720        0  1310720     0     0     0\n";
+        assert_eq!(parse_mm_stat(text), Some((4194304, 1310720)));
+        assert_eq!(parse_mm_stat("garbage"), None);
+    }
+
+    #[test]
+    fn test_combine_weights_zram_by_ratio() {
+        // 16 GiB RAM, 1 GiB available, 8 GiB zram with 6 GiB free at 3:1.
+        let mem = combine(16 * GIB, GIB, &[zram(8 * GIB, 2 * GIB)], Some(3.0));
+        assert_eq!(mem.total / (GIB / 3), (16 * 3 + 16) as u64);
+        assert_eq!(mem.available / (GIB / 3), (3 + 12) as u64);
+        // RAM alone is 94% used, but zram headroom keeps the effective figure lower.
+        assert!(mem.used_percent() < 80.0, "{}", mem.used_percent());
+    }
+
+    #[test]
+    fn test_combine_full_swap_counts_as_used() {
+        // RAM looks fine, but swap is exhausted.
+        let disk = SwapDevice {
+            name: "/swapfile".to_string(),
+            size: 16 * GIB,
+            used: 16 * GIB,
+        };
+        let mem = combine(16 * GIB, 4 * GIB, &[disk], None);
+        assert_eq!(mem.total, 32 * GIB);
+        assert_eq!(mem.available, 4 * GIB);
+        assert!(mem.used_percent() > 85.0);
+    }
+
+    #[test]
+    fn test_combine_without_swap_is_ram() {
+        let mem = combine(8 * GIB, 2 * GIB, &[], None);
+        assert_eq!(mem.used(), 6 * GIB);
+        assert_eq!(mem.used_percent(), 75.0);
+    }
+
+    #[test]
+    fn test_read_effective_memory_on_this_host() {
+        let mem = read_effective_memory(&MemoryConfig::default()).unwrap();
+        assert!(mem.total > 0 && mem.available <= mem.total);
+    }
+
+    #[test]
+    fn test_memory_config_validation() {
+        assert!(MemoryConfig::default().validate().is_ok());
+        let bad = MemoryConfig {
+            zram_ratio: 0.5,
+            ..MemoryConfig::default()
+        };
+        assert!(bad.validate().is_err());
+    }
+}
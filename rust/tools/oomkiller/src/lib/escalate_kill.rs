@@ -0,0 +1,204 @@
+use crate::{
+    config::EscalationConfig,
+    pidfd::PidFd,
+    types::{Bytes, KillOutcome, ProcessInfo},
+};
+use std::time::Instant;
+
+/// Stops `process` with SIGTERM, escalating to SIGKILL after the grace period.
+///
+/// Both signals go through one start-time-verified pidfd, so the escalation can
+/// never hit a recycled PID. SIGTERM is skipped when escalation is disabled or
+/// `critical` is set (e.g. PSI full above `critical_full_avg10`). During the grace
+/// period the pidfd is polled for exit and `memory_recovered` is asked every
+/// `poll_ms`; if it returns true, SIGKILL is not sent.
+///
+/// Apart from building an error, nothing here allocates: with memory exhausted a
+/// failed allocation must not be what stops the kill.
+///
+/// # Arguments
+/// * `process` - The victim, including the start time recorded when it was scanned
+/// * `config` - Grace period and polling settings
+/// * `critical` - Skip SIGTERM and SIGKILL immediately
+/// * `memory_recovered` - Re-checks whether memory is still critical
+///
+/// # Returns
+/// * `Ok(KillOutcome)` describing how the process was stopped
+/// * `Err(String)` if the process was gone or replaced, or a signal could not be sent
+pub fn escalate_kill(
+    process: &ProcessInfo,
+    config: &EscalationConfig,
+    critical: bool,
+    mut memory_recovered: impl FnMut() -> bool,
+) -> Result<KillOutcome, String> {
+    let fail = |e: &dyn std::fmt::Display| {
+        format!(
+            "Failed to kill name={} pid={}: {e}",
+            process.name, process.pid
+        )
+    };
+    let pidfd = PidFd::open_verified(process.pid, process.start_time).map_err(|e| fail(&e))?;
+    let started = Instant::now();
+
+    if !config.enabled || critical {
+        pidfd.send_signal(libc::SIGKILL).map_err(|e| fail(&e))?;
+        println!(
+            "Sent SIGKILL to name={} pid={} rss={} ({})",
+            process.name,
+            process.pid,
+            Bytes(process.memory),
+            if critical {
+                "pressure critical, no grace period"
+            } else {
+                "escalation disabled"
+            },
+        );
+        return Ok(KillOutcome::Killed {
+            after: started.elapsed(),
+            term_sent: false,
+        });
+    }
+
+    pidfd.send_signal(libc::SIGTERM).map_err(|e| fail(&e))?;
+    println!(
+        "Sent SIGTERM to name={} pid={} rss={}, grace period {}ms",
+        process.name,
+        process.pid,
+        Bytes(process.memory),
+        config.grace_ms,
+    );
+
+    let deadline = started + config.grace();
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        let exited = pidfd
+            .wait_exit(config.poll().min(deadline - now))
+            .map_err(|e| fail(&e))?;
+        let after = started.elapsed();
+        if exited {
+            println!(
+                "name={} pid={} exited {:.2}s after SIGTERM",
+                process.name,
+                process.pid,
+                after.as_secs_f64()
+            );
+            return Ok(KillOutcome::Terminated { after });
+        }
+        if memory_recovered() {
+            println!(
+                "Memory recovered {:.2}s after SIGTERM; not sending SIGKILL to name={} pid={}",
+                after.as_secs_f64(),
+                process.name,
+                process.pid
+            );
+            return Ok(KillOutcome::Recovered { after });
+        }
+    }
+
+    pidfd.send_signal(libc::SIGKILL).map_err(|e| fail(&e))?;
+    let after = started.elapsed();
+    println!(
+        "Grace period expired; sent SIGKILL to name={} pid={} {:.2}s after SIGTERM",
+        process.name,
+        process.pid,
+        after.as_secs_f64()
+    );
+    Ok(KillOutcome::Killed {
+        after,
+        term_sent: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::procfs::read_start_time;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::{Child, Command};
+    use std::time::Duration;
+
+    fn spawn(script: &str) -> (Child, ProcessInfo) {
+        let child = Command::new("sh").arg("-c").arg(script).spawn().unwrap();
+        let pid = child.id();
+        // Give the shell a moment to install its traps.
+        std::thread::sleep(Duration::from_millis(50));
+        let process = ProcessInfo {
+            pid,
+            memory: 0,
+            name: "sh".to_string(),
+            cmdline: script.to_string(),
+            start_time: read_start_time(pid).unwrap(),
+            ..ProcessInfo::default()
+        };
+        (child, process)
+    }
+
+    fn fast() -> EscalationConfig {
+        EscalationConfig {
+            grace_ms: 300,
+            poll_ms: 10,
+            ..EscalationConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_escalate_kill_cooperative_process_terminates() {
+        let (mut child, process) = spawn("sleep 30");
+        let outcome = escalate_kill(&process, &fast(), false, || false).unwrap();
+        assert!(
+            matches!(outcome, KillOutcome::Terminated { .. }),
+            "{outcome:?}"
+        );
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGTERM));
+    }
+
+    #[test]
+    fn test_escalate_kill_stubborn_process_gets_sigkill() {
+        let (mut child, process) = spawn("trap '' TERM; while :; do sleep 0.01; done");
+        let outcome = escalate_kill(&process, &fast(), false, || false).unwrap();
+        assert!(
+            matches!(
+                outcome,
+                KillOutcome::Killed {
+                    term_sent: true,
+                    ..
+                }
+            ),
+            "{outcome:?}"
+        );
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGKILL));
+    }
+
+    #[test]
+    fn test_escalate_kill_stops_when_memory_recovers() {
+        let (mut child, process) = spawn("trap '' TERM; while :; do sleep 0.01; done");
+        let outcome = escalate_kill(&process, &fast(), false, || true).unwrap();
+        assert!(
+            matches!(outcome, KillOutcome::Recovered { .. }),
+            "{outcome:?}"
+        );
+        // Still alive: clean up ourselves.
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_escalate_kill_critical_skips_sigterm() {
+        let (mut child, process) = spawn("trap '' TERM; while :; do sleep 0.01; done");
+        let outcome = escalate_kill(&process, &fast(), true, || false).unwrap();
+        assert!(
+            matches!(
+                outcome,
+                KillOutcome::Killed {
+                    term_sent: false,
+                    ..
+                }
+            ),
+            "{outcome:?}"
+        );
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGKILL));
+    }
+}
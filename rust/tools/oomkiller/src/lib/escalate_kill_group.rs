@@ -0,0 +1,106 @@
+use crate::{
+    cgroup::{AppGroup, is_populated, kill_cgroup, read_cgroup_pids},
+    config::EscalationConfig,
+    format_bytes,
+    pidfd::PidFd,
+    types::KillOutcome,
+};
+use std::time::Instant;
+
+/// Stops a whole app unit: SIGTERM to each member, then `cgroup.kill` after the grace period.
+///
+/// Works like [`escalate_kill`](crate::escalate_kill), but for every process in the
+/// cgroup. SIGTERM goes to the PIDs listed in `cgroup.procs` through pidfds; the
+/// group counts as terminated once `cgroup.events` reports it unpopulated. The final
+/// SIGKILL is a single `cgroup.kill` write, which also catches processes forked
+/// during the grace period.
+///
+/// # Arguments
+/// * `group` - The app unit to stop
+/// * `config` - Grace period and polling settings
+/// * `critical` - Skip SIGTERM and kill the cgroup immediately
+/// * `memory_recovered` - Re-checks whether memory is still critical
+///
+/// # Returns
+/// * `Ok(KillOutcome)` describing how the unit was stopped
+/// * `Err(String)` if the cgroup could not be read or killed
+pub fn escalate_kill_group(
+    group: &AppGroup,
+    config: &EscalationConfig,
+    critical: bool,
+    mut memory_recovered: impl FnMut() -> bool,
+) -> Result<KillOutcome, String> {
+    let dir = group.dir();
+    let unit = group.unit();
+    let started = Instant::now();
+
+    if !config.enabled || critical {
+        kill_cgroup(&dir)?;
+        println!(
+            "Killed cgroup unit={unit} memory={} ({})",
+            format_bytes(group.memory),
+            if critical {
+                "pressure critical, no grace period"
+            } else {
+                "escalation disabled"
+            },
+        );
+        return Ok(KillOutcome::Killed {
+            after: started.elapsed(),
+            term_sent: false,
+        });
+    }
+
+    let pids = read_cgroup_pids(&dir)?;
+    let mut signalled = 0;
+    for pid in &pids {
+        // Members may exit between listing and signalling; that is fine.
+        if let Ok(pidfd) = PidFd::open(*pid)
+            && pidfd.send_signal(libc::SIGTERM).is_ok()
+        {
+            signalled += 1;
+        }
+    }
+    println!(
+        "Sent SIGTERM to {signalled}/{} processes of unit={unit} memory={}, grace period {}ms",
+        pids.len(),
+        format_bytes(group.memory),
+        config.grace_ms,
+    );
+
+    let deadline = started + config.grace();
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        std::thread::sleep(config.poll().min(deadline - now));
+        let after = started.elapsed();
+        // systemd removes an empty scope, so an unreadable cgroup means it is gone.
+        if !is_populated(&dir).unwrap_or(false) {
+            println!(
+                "unit={unit} exited {:.2}s after SIGTERM",
+                after.as_secs_f64()
+            );
+            return Ok(KillOutcome::Terminated { after });
+        }
+        if memory_recovered() {
+            println!(
+                "Memory recovered {:.2}s after SIGTERM; not killing unit={unit}",
+                after.as_secs_f64()
+            );
+            return Ok(KillOutcome::Recovered { after });
+        }
+    }
+
+    kill_cgroup(&dir)?;
+    let after = started.elapsed();
+    println!(
+        "Grace period expired; killed cgroup unit={unit} {:.2}s after SIGTERM",
+        after.as_secs_f64()
+    );
+    Ok(KillOutcome::Killed {
+        after,
+        term_sent: true,
+    })
+}
@@ -0,0 +1,212 @@
+use crate::{
+    config::EscalationConfig,
+    pidfd::PidFd,
+    types::{Bytes, KillOutcome, ProcessInfo},
+};
+use std::time::{Duration, Instant};
+
+/// Stops `process` and its `descendants`: SIGTERM to each, leaves first, then SIGKILL
+/// to whatever is left after the grace period, again leaves first.
+///
+/// Works like [`escalate_kill`](crate::escalate_kill): every member is signalled
+/// through a start-time-verified pidfd, SIGTERM is skipped when escalation is disabled
+/// or `critical` is set, and `memory_recovered` can call off the SIGKILL. Signalling
+/// the leaves first keeps a parent such as `make` from starting new jobs for children
+/// that have just died. Descendants that already exited are skipped; the tree counts
+/// as terminated once every member has exited.
+///
+/// # Arguments
+/// * `process` - The victim
+/// * `descendants` - Its descendants, deepest first (see [`descendants`](crate::process_tree::descendants))
+/// * `config` - Grace period and polling settings
+/// * `critical` - Skip SIGTERM and SIGKILL immediately
+/// * `memory_recovered` - Re-checks whether memory is still critical
+///
+/// # Returns
+/// * `Ok(KillOutcome)` describing how the victim was stopped
+/// * `Err(String)` if the victim was gone or replaced, or could not be signalled
+pub fn escalate_kill_tree(
+    process: &ProcessInfo,
+    descendants: &[ProcessInfo],
+    config: &EscalationConfig,
+    critical: bool,
+    mut memory_recovered: impl FnMut() -> bool,
+) -> Result<KillOutcome, String> {
+    let fail = |e: &dyn std::fmt::Display| {
+        format!(
+            "Failed to kill name={} pid={}: {e}",
+            process.name, process.pid
+        )
+    };
+    let root = PidFd::open_verified(process.pid, process.start_time).map_err(|e| fail(&e))?;
+    let members: Vec<PidFd> = descendants
+        .iter()
+        .filter_map(|p| PidFd::open_verified(p.pid, p.start_time).ok())
+        .collect();
+    let started = Instant::now();
+    let memory: u64 = descendants.iter().map(|p| p.memory).sum();
+
+    // Descendants may exit (or already be reaped) at any moment; only the victim must be signalled.
+    let signal_all = |signal| {
+        for member in &members {
+            let _ = member.send_signal(signal);
+        }
+        root.send_signal(signal).map_err(|e| fail(&e))
+    };
+
+    if !config.enabled || critical {
+        signal_all(libc::SIGKILL)?;
+        println!(
+            "Sent SIGKILL to name={} pid={} rss={} and {} descendants rss={} ({})",
+            process.name,
+            process.pid,
+            Bytes(process.memory),
+            members.len(),
+            Bytes(memory),
+            if critical {
+                "pressure critical, no grace period"
+            } else {
+                "escalation disabled"
+            },
+        );
+        return Ok(KillOutcome::Killed {
+            after: started.elapsed(),
+            term_sent: false,
+        });
+    }
+
+    signal_all(libc::SIGTERM)?;
+    println!(
+        "Sent SIGTERM to name={} pid={} rss={} and {} descendants rss={}, grace period {}ms",
+        process.name,
+        process.pid,
+        Bytes(process.memory),
+        members.len(),
+        Bytes(memory),
+        config.grace_ms,
+    );
+
+    let deadline = started + config.grace();
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        let root_exited = root
+            .wait_exit(config.poll().min(deadline - now))
+            .map_err(|e| fail(&e))?;
+        let after = started.elapsed();
+        if root_exited
+            && members
+                .iter()
+                .all(|member| member.wait_exit(Duration::ZERO).unwrap_or(true))
+        {
+            println!(
+                "name={} pid={} and its descendants exited {:.2}s after SIGTERM",
+                process.name,
+                process.pid,
+                after.as_secs_f64()
+            );
+            return Ok(KillOutcome::Terminated { after });
+        }
+        if memory_recovered() {
+            println!(
+                "Memory recovered {:.2}s after SIGTERM; not sending SIGKILL to the tree of name={} pid={}",
+                after.as_secs_f64(),
+                process.name,
+                process.pid
+            );
+            return Ok(KillOutcome::Recovered { after });
+        }
+        if root_exited {
+            // Waiting on the victim's pidfd would return at once from now on.
+            std::thread::sleep(
+                config
+                    .poll()
+                    .min(deadline.saturating_duration_since(Instant::now())),
+            );
+        }
+    }
+
+    for member in &members {
+        let _ = member.send_signal(libc::SIGKILL);
+    }
+    // The victim may have exited on SIGTERM while descendants held on.
+    if !root.wait_exit(Duration::ZERO).unwrap_or(false) {
+        root.send_signal(libc::SIGKILL).map_err(|e| fail(&e))?;
+    }
+    let after = started.elapsed();
+    println!(
+        "Grace period expired; sent SIGKILL to the tree of name={} pid={} {:.2}s after SIGTERM",
+        process.name,
+        process.pid,
+        after.as_secs_f64()
+    );
+    Ok(KillOutcome::Killed {
+        after,
+        term_sent: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::procfs::{read_children, read_process};
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::Command;
+
+    fn fast() -> EscalationConfig {
+        EscalationConfig {
+            grace_ms: 300,
+            poll_ms: 10,
+            ..EscalationConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_escalate_kill_tree_kills_stubborn_children() {
+        // The shell exits on SIGTERM, its child ignores it.
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg("sh -c 'trap \"\" TERM; sleep 30' & wait")
+            .spawn()
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        let root = read_process(child.id()).unwrap();
+        // The inner shell and its sleep, which inherits the ignored SIGTERM; deepest first.
+        let mut tree = Vec::new();
+        let mut next = vec![root.pid];
+        while let Some(pid) = next.pop() {
+            for process in read_children(pid).into_iter().filter_map(read_process) {
+                next.push(process.pid);
+                tree.insert(0, process);
+            }
+        }
+        assert_eq!(tree.len(), 2);
+
+        let outcome = escalate_kill_tree(&root, &tree, &fast(), false, || false).unwrap();
+        assert!(matches!(
+            outcome,
+            KillOutcome::Killed {
+                term_sent: true,
+                ..
+            }
+        ));
+        let status = child.wait().unwrap();
+        assert!(status.signal() == Some(libc::SIGTERM) || status.code().is_some());
+        std::thread::sleep(Duration::from_millis(50));
+        for process in &tree {
+            assert!(read_process(process.pid).is_none_or(|p| p.start_time != process.start_time));
+        }
+    }
+
+    #[test]
+    fn test_escalate_kill_tree_missing_victim_is_error() {
+        let process = ProcessInfo {
+            pid: 999_999_999,
+            name: "gone".to_string(),
+            ..ProcessInfo::default()
+        };
+        assert!(escalate_kill_tree(&process, &[], &fast(), true, || false).is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{
+    config::{Config, TriggerMode},
+    host::MemorySnapshot,
+    psi::PsiSnapshot,
+};
+
+/// Decides whether memory is critical according to `config.trigger`.
+///
+/// Combines the used-percent check on `memory` (RAM, or RAM plus zram-weighted swap
+/// with `accounting = "effective"`, see [`MemorySource`](crate::host::MemorySource))
+/// with the PSI averages in `psi`. When PSI is needed but `psi` is `None` (kernel
+/// without PSI or unreadable file), the percent check is used alone.
+///
+/// # Arguments
+/// * `memory` - Current usage under the configured accounting
+/// * `config` - Threshold, trigger mode and PSI limits
+/// * `psi` - Current `/proc/pressure/memory` reading, if available
+pub fn evaluate_trigger(
+    memory: &MemorySnapshot,
+    config: &Config,
+    psi: Option<&PsiSnapshot>,
+) -> bool {
+    let percent = memory.used_percent() >= config.threshold_percent;
+    let pressure = psi.map(|p| p.exceeds(&config.psi));
+    combine_trigger(config.trigger, percent, pressure)
+}
+
+/// Pure combination rule behind `evaluate_trigger`.
+pub fn combine_trigger(mode: TriggerMode, percent: bool, pressure: Option<bool>) -> bool {
+    let Some(pressure) = pressure else {
+        return percent;
+    };
+    match mode {
+        TriggerMode::Percent => percent,
+        TriggerMode::Psi => pressure,
+        TriggerMode::Either => percent || pressure,
+        TriggerMode::Both => percent && pressure,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combine_trigger_modes() {
+        assert!(combine_trigger(TriggerMode::Percent, true, Some(false)));
+        assert!(!combine_trigger(TriggerMode::Percent, false, Some(true)));
+        assert!(combine_trigger(TriggerMode::Psi, false, Some(true)));
+        assert!(!combine_trigger(TriggerMode::Psi, true, Some(false)));
+        assert!(combine_trigger(TriggerMode::Either, false, Some(true)));
+        assert!(combine_trigger(TriggerMode::Either, true, Some(false)));
+        assert!(!combine_trigger(TriggerMode::Both, true, Some(false)));
+        assert!(combine_trigger(TriggerMode::Both, true, Some(true)));
+    }
+
+    #[test]
+    fn test_combine_trigger_without_psi_falls_back_to_percent() {
+        for mode in [TriggerMode::Psi, TriggerMode::Either, TriggerMode::Both] {
+            assert!(combine_trigger(mode, true, None));
+            assert!(!combine_trigger(mode, false, None));
+        }
+    }
+
+    #[test]
+    fn test_evaluate_trigger_percent() {
+        let config = Config {
+            threshold_percent: 90.0,
+            ..Config::default()
+        };
+        let total = 100;
+        assert!(!evaluate_trigger(
+            &MemorySnapshot { used: 89, total },
+            &config,
+            None
+        ));
+        assert!(evaluate_trigger(
+            &MemorySnapshot { used: 90, total },
+            &config,
+            None
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_trigger_psi_mode_uses_snapshot() {
+        let config = Config {
+            trigger: TriggerMode::Psi,
+            threshold_percent: 100.0,
+            ..Config::default()
+        };
+        let memory = MemorySnapshot {
+            used: 99,
+            total: 100,
+        };
+        let mut calm = PsiSnapshot::default();
+        assert!(!evaluate_trigger(&memory, &config, Some(&calm)));
+        calm.full.avg10 = 50.0;
+        assert!(evaluate_trigger(&memory, &config, Some(&calm)));
+    }
+}
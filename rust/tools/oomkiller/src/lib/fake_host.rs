@@ -0,0 +1,305 @@
+//! In-memory [`Host`] parts for scenario tests, here and in `tests/kill_path.rs`.
+
+use crate::{
+    cgroup::AppGroup,
+    config::{EscalationConfig, Target},
+    effective_memory::MemoryConfig,
+    freeze::FrozenTarget,
+    host::{Host, MemorySnapshot, MemorySource, ProcessTable, SignalSink},
+    kill_history::SystemdUnit,
+    notify::{Notice, NotificationSink},
+    psi::PsiSnapshot,
+    smaps::SmapsRollup,
+    types::{KillOutcome, ProcessInfo},
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub const GIB: u64 = 1024 * 1024 * 1024;
+
+/// Total RAM of the fake machine.
+pub const TOTAL: u64 = 16 * GIB;
+
+/// Replays used-percent readings of a 16 GiB machine; the last one repeats.
+#[derive(Default)]
+pub struct FakeMemory {
+    readings: Vec<f64>,
+    next: usize,
+    pub psi: Option<PsiSnapshot>,
+    /// Every snapshot fails, as if `/proc/meminfo` were unreadable.
+    pub broken: bool,
+}
+
+impl FakeMemory {
+    pub fn new(readings: &[f64]) -> Self {
+        Self {
+            readings: readings.to_vec(),
+            ..Self::default()
+        }
+    }
+}
+
+impl MemorySource for FakeMemory {
+    fn snapshot(&mut self, _config: &MemoryConfig) -> Result<MemorySnapshot, String> {
+        if self.broken {
+            return Err("memory unreadable".to_string());
+        }
+        let percent = self.readings[self.next.min(self.readings.len() - 1)];
+        self.next += 1;
+        Ok(MemorySnapshot {
+            used: (TOTAL as f64 * percent / 100.0) as u64,
+            total: TOTAL,
+        })
+    }
+
+    fn pressure(&mut self) -> Option<PsiSnapshot> {
+        self.psi
+    }
+}
+
+/// A fixed process list; every candidate is returned regardless of target.
+///
+/// Executables are unknown, so kill loops outside services are keyed by name.
+#[derive(Default)]
+pub struct FakeProcesses {
+    /// Processes with their cgroup path, if any.
+    pub processes: Vec<(ProcessInfo, Option<String>)>,
+    /// Whether `cgroup.kill` of every unit is writable.
+    pub delegated: bool,
+    /// `smaps_rollup` by PID; processes without one are scored by RSS.
+    pub smaps: HashMap<u32, SmapsRollup>,
+    /// Parent PID by child PID.
+    pub parents: HashMap<u32, u32>,
+}
+
+impl FakeProcesses {
+    pub fn add(&mut self, pid: u32, name: &str, memory: u64) -> &mut Self {
+        self.add_in(pid, name, memory, None)
+    }
+
+    pub fn add_child(&mut self, parent: u32, pid: u32, name: &str, memory: u64) -> &mut Self {
+        self.parents.insert(pid, parent);
+        self.add_in(pid, name, memory, None)
+    }
+
+    pub fn add_in(&mut self, pid: u32, name: &str, memory: u64, cgroup: Option<&str>) -> &mut Self {
+        self.processes.push((
+            ProcessInfo {
+                pid,
+                memory,
+                name: name.to_string(),
+                cmdline: name.to_string(),
+                start_time: u64::from(pid),
+                ..ProcessInfo::default()
+            },
+            cgroup.map(str::to_string),
+        ));
+        self
+    }
+}
+
+impl ProcessTable for FakeProcesses {
+    fn refresh(&mut self, _target: Target) {}
+
+    fn total_memory(&self) -> u64 {
+        TOTAL
+    }
+
+    fn candidates(&self, _target: Target) -> Result<Vec<ProcessInfo>, String> {
+        Ok(self.processes.iter().map(|(p, _)| p.clone()).collect())
+    }
+
+    fn cgroup_path(&self, pid: u32) -> Option<String> {
+        self.processes
+            .iter()
+            .find(|(p, _)| p.pid == pid)
+            .and_then(|(_, cgroup)| cgroup.clone())
+    }
+
+    fn executable(&self, _pid: u32) -> Option<PathBuf> {
+        None
+    }
+
+    fn smaps_rollup(&self, pid: u32) -> Option<SmapsRollup> {
+        self.smaps.get(&pid).copied()
+    }
+
+    fn unit_memory(&self, _group: &AppGroup) -> Option<u64> {
+        None
+    }
+
+    fn is_delegated(&self, _group: &AppGroup) -> bool {
+        self.delegated
+    }
+
+    fn is_alive(&self, target: &FrozenTarget) -> bool {
+        self.processes.iter().any(|(p, cgroup)| match target {
+            FrozenTarget::Process { pid, start_time } => {
+                p.pid == *pid && p.start_time == *start_time
+            }
+            FrozenTarget::Cgroup { path } => cgroup.as_ref() == Some(path),
+        })
+    }
+
+    fn children(&self, pid: u32) -> Vec<ProcessInfo> {
+        self.processes
+            .iter()
+            .filter(|(p, _)| self.parents.get(&p.pid) == Some(&pid))
+            .map(|(p, _)| p.clone())
+            .collect()
+    }
+}
+
+/// What [`FakeSignals`] was asked to stop, and whether without a grace period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Stopped {
+    Process {
+        pid: u32,
+        critical: bool,
+    },
+    App {
+        unit: String,
+        critical: bool,
+    },
+    Unit {
+        name: String,
+        user: bool,
+    },
+    /// A victim with its descendants, in signalling order.
+    Tree {
+        pid: u32,
+        descendants: Vec<u32>,
+        critical: bool,
+    },
+    /// Frozen (`true`) or thawed.
+    Frozen {
+        target: FrozenTarget,
+        frozen: bool,
+    },
+}
+
+/// Records stops instead of signalling. With escalation enabled and not critical it
+/// asks `memory_recovered` once, as if the grace period had been waited out.
+#[derive(Default)]
+pub struct FakeSignals {
+    pub stopped: Arc<Mutex<Vec<Stopped>>>,
+}
+
+impl FakeSignals {
+    fn outcome(
+        config: &EscalationConfig,
+        critical: bool,
+        memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> KillOutcome {
+        if !config.enabled || critical {
+            KillOutcome::Killed {
+                after: Duration::ZERO,
+                term_sent: false,
+            }
+        } else if memory_recovered() {
+            KillOutcome::Recovered {
+                after: config.poll(),
+            }
+        } else {
+            KillOutcome::Killed {
+                after: config.grace(),
+                term_sent: true,
+            }
+        }
+    }
+}
+
+impl SignalSink for FakeSignals {
+    fn stop_process(
+        &mut self,
+        process: &ProcessInfo,
+        config: &EscalationConfig,
+        critical: bool,
+        memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String> {
+        self.stopped.lock().unwrap().push(Stopped::Process {
+            pid: process.pid,
+            critical,
+        });
+        Ok(Self::outcome(config, critical, memory_recovered))
+    }
+
+    fn stop_app(
+        &mut self,
+        group: &AppGroup,
+        config: &EscalationConfig,
+        critical: bool,
+        memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String> {
+        self.stopped.lock().unwrap().push(Stopped::App {
+            unit: group.unit().to_string(),
+            critical,
+        });
+        Ok(Self::outcome(config, critical, memory_recovered))
+    }
+
+    fn stop_tree(
+        &mut self,
+        process: &ProcessInfo,
+        descendants: &[ProcessInfo],
+        config: &EscalationConfig,
+        critical: bool,
+        memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String> {
+        self.stopped.lock().unwrap().push(Stopped::Tree {
+            pid: process.pid,
+            descendants: descendants.iter().map(|p| p.pid).collect(),
+            critical,
+        });
+        Ok(Self::outcome(config, critical, memory_recovered))
+    }
+
+    fn stop_unit(&mut self, unit: &SystemdUnit) -> Result<(), String> {
+        self.stopped.lock().unwrap().push(Stopped::Unit {
+            name: unit.name.clone(),
+            user: unit.user,
+        });
+        Ok(())
+    }
+
+    fn freeze(&mut self, target: &FrozenTarget, frozen: bool) -> Result<(), String> {
+        self.stopped.lock().unwrap().push(Stopped::Frozen {
+            target: target.clone(),
+            frozen,
+        });
+        Ok(())
+    }
+}
+
+/// Collects notices instead of sending them to the session bus.
+pub struct RecordingSink(pub Arc<Mutex<Vec<Notice>>>);
+
+/// Questions are recorded too; tests answer them through `FreezeState::answers`.
+impl NotificationSink for RecordingSink {
+    fn send(&mut self, notice: &Notice) -> Result<(), String> {
+        self.0.lock().unwrap().push(notice.clone());
+        Ok(())
+    }
+
+    fn ask(&mut self, notice: &Notice) -> Result<Receiver<String>, String> {
+        self.send(notice)?;
+        Ok(mpsc::channel().1)
+    }
+
+    fn withdraw(&mut self) {}
+}
+
+/// A host built from fakes, plus the log of what its signal sink stopped.
+pub fn fake_host(memory: FakeMemory, processes: FakeProcesses) -> (Host, Arc<Mutex<Vec<Stopped>>>) {
+    let signals = FakeSignals::default();
+    let stopped = Arc::clone(&signals.stopped);
+    let host = Host {
+        memory: Box::new(memory),
+        processes: Box::new(processes),
+        signals: Box::new(signals),
+    };
+    (host, stopped)
+}
@@ -0,0 +1,396 @@
+use crate::{
+    badness::Patterns,
+    cgroup::{AppGroup, CGROUP_ROOT, is_populated},
+    config::EscalationConfig,
+    host::SignalSink,
+    journal::VictimRecord,
+    kill_history::{LoopKey, state_dir},
+    procfs::read_start_time,
+    types::{KillOutcome, ProcessInfo},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::Duration;
+
+/// `[freeze]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FreezeConfig {
+    /// Freeze matching victims and ask the user instead of killing them straight away.
+    pub enabled: bool,
+    /// Regexes matched against the process name (or the app unit with `group_by = "cgroup"`);
+    /// empty means every victim.
+    pub apps: Patterns,
+    /// Kill the frozen victim when nobody answers within this many seconds.
+    pub timeout_secs: f64,
+    /// Kill it when memory is still critical this many seconds after freezing.
+    pub critical_secs: f64,
+    /// Kill it at once, without waiting, when used memory reaches this percentage
+    /// (or PSI full passes `escalation.critical_full_avg10`).
+    pub ceiling_percent: f64,
+    /// After the user resumes a victim, kill it instead of freezing it again for this many seconds.
+    pub rearm_secs: f64,
+    /// State file; default `$XDG_STATE_HOME/oomkiller/frozen.json`. Read at startup.
+    pub state: Option<PathBuf>,
+}
+
+impl Default for FreezeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            apps: Patterns::default(),
+            timeout_secs: 120.0,
+            critical_secs: 30.0,
+            ceiling_percent: 97.0,
+            rearm_secs: 600.0,
+            state: None,
+        }
+    }
+}
+
+impl FreezeConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        for (key, value) in [
+            ("freeze.timeout_secs", self.timeout_secs),
+            ("freeze.critical_secs", self.critical_secs),
+            ("freeze.rearm_secs", self.rearm_secs),
+        ] {
+            if !(value.is_finite() && value > 0.0 && value <= 86_400.0) {
+                return Err(format!("{key} must be between 0 and 86400, got {value}"));
+            }
+        }
+        if !(self.ceiling_percent > 0.0 && self.ceiling_percent <= 100.0) {
+            return Err(format!(
+                "freeze.ceiling_percent must be between 0 and 100, got {}",
+                self.ceiling_percent
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs_f64(self.timeout_secs)
+    }
+
+    /// Whether a victim called `name` is frozen rather than killed.
+    pub fn applies_to(&self, name: &str) -> bool {
+        self.enabled && (self.apps.is_empty() || self.apps.is_match(name))
+    }
+
+    /// `state`, or `$XDG_STATE_HOME/oomkiller/frozen.json`.
+    pub fn state_path(&self) -> Option<PathBuf> {
+        match &self.state {
+            Some(path) => Some(path.clone()),
+            None => Some(state_dir()?.join("frozen.json")),
+        }
+    }
+}
+
+/// What was frozen, and how to find it again after a restart.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum FrozenTarget {
+    /// Stopped with `SIGSTOP`; `start_time` guards against PID reuse.
+    Process { pid: u32, start_time: u64 },
+    /// Frozen through `cgroup.freeze`; `path` is relative to the cgroup root.
+    Cgroup { path: String },
+}
+
+impl FrozenTarget {
+    /// False once the process has exited (or its PID was reused) or the unit is empty.
+    pub fn is_alive(&self) -> bool {
+        match self {
+            Self::Process { pid, start_time } => read_start_time(*pid) == Ok(*start_time),
+            Self::Cgroup { path } => {
+                is_populated(&Path::new(CGROUP_ROOT).join(path.trim_start_matches('/')))
+                    .unwrap_or(false)
+            }
+        }
+    }
+
+    /// Kills the frozen target straight away: a stopped process cannot act on
+    /// SIGTERM, and `cgroup.kill` works on a frozen cgroup.
+    ///
+    /// # Arguments
+    /// * `name` - Process name, for the signal sink's log lines
+    pub fn kill(
+        &self,
+        name: &str,
+        signals: &mut dyn SignalSink,
+        config: &EscalationConfig,
+    ) -> Result<KillOutcome, String> {
+        match self {
+            Self::Process { pid, start_time } => {
+                let process = ProcessInfo {
+                    pid: *pid,
+                    start_time: *start_time,
+                    name: name.to_string(),
+                    ..ProcessInfo::default()
+                };
+                signals.stop_process(&process, config, true, &mut || false)
+            }
+            Self::Cgroup { path } => {
+                let group = AppGroup {
+                    path: path.clone(),
+                    ..AppGroup::default()
+                };
+                signals.stop_app(&group, config, true, &mut || false)
+            }
+        }
+    }
+}
+
+impl fmt::Display for FrozenTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Process { pid, .. } => write!(f, "pid {pid}"),
+            Self::Cgroup { path } => write!(f, "cgroup {path}"),
+        }
+    }
+}
+
+/// A victim that is frozen and waiting for an answer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Frozen {
+    pub target: FrozenTarget,
+    /// The victim as journaled when it was frozen.
+    pub victim: VictimRecord,
+    /// What the final kill counts against in the kill history.
+    pub loop_key: LoopKey,
+    /// Identity line for the notification, e.g. `PID 1234, RSS 3.2 GiB`.
+    pub detail: String,
+    /// Unix times: when it was frozen, and when it is killed without an answer.
+    pub since: f64,
+    pub deadline: f64,
+    /// Since when memory has been critical again while frozen; reset once it recovers.
+    pub critical_since: Option<f64>,
+}
+
+impl Frozen {
+    /// A victim frozen at `now`, to be killed after `freeze.timeout_secs` without an answer.
+    pub fn new(
+        target: FrozenTarget,
+        victim: VictimRecord,
+        loop_key: LoopKey,
+        detail: String,
+        now: f64,
+        config: &FreezeConfig,
+    ) -> Self {
+        Self {
+            target,
+            victim,
+            loop_key,
+            detail,
+            since: now,
+            deadline: now + config.timeout_secs,
+            critical_since: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.victim.name()
+    }
+}
+
+/// The buttons of the freeze notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Kill,
+    Resume,
+}
+
+impl Answer {
+    /// Action identifiers and labels, as sent with the notification.
+    pub const ACTIONS: [(&'static str, &'static str); 2] = [("kill", "Kill"), ("resume", "Resume")];
+
+    /// The button behind an action identifier; clicking the body (`default`) or
+    /// dismissing the notification (`__closed`) is no answer.
+    pub fn parse(action: &str) -> Option<Self> {
+        match action {
+            "kill" => Some(Self::Kill),
+            "resume" => Some(Self::Resume),
+            _ => None,
+        }
+    }
+}
+
+/// The frozen victim, if any, persisted as JSON so a restart never leaves it frozen and forgotten.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FreezeState {
+    pub frozen: Option<Frozen>,
+    /// Name -> Unix time until which a victim the user resumed is killed rather than frozen again.
+    resumed: BTreeMap<String, f64>,
+    /// Button presses from the notification thread.
+    #[serde(skip)]
+    pub answers: Option<Receiver<String>>,
+    /// Whether this daemon instance has asked about `frozen` (a restarted one asks again).
+    #[serde(skip)]
+    pub asked: bool,
+}
+
+impl FreezeState {
+    /// Reads the state; a missing file means nothing is frozen.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|e| format!("Invalid freeze state {}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!(
+                "Failed to read freeze state {}: {e}",
+                path.display()
+            )),
+        }
+    }
+
+    /// Writes the state atomically (temp file, then rename), creating the directory.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let fail =
+            |e: &dyn fmt::Display| format!("Failed to write freeze state {}: {e}", path.display());
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| fail(&e))?;
+        }
+        let text = serde_json::to_string(self).map_err(|e| fail(&e))?;
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, text).map_err(|e| fail(&e))?;
+        fs::rename(&tmp, path).map_err(|e| fail(&e))
+    }
+
+    /// The first button pressed since the last call, if any.
+    pub fn take_answer(&mut self) -> Option<Answer> {
+        let answers = self.answers.as_ref()?;
+        loop {
+            match answers.try_recv() {
+                Ok(action) => {
+                    if let Some(answer) = Answer::parse(&action) {
+                        return Some(answer);
+                    }
+                }
+                Err(TryRecvError::Empty) => return None,
+                // Dismissed, or no notification daemon: only the timeout is left.
+                Err(TryRecvError::Disconnected) => {
+                    self.answers = None;
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Forgets the frozen victim; a resumed one is not frozen again until `resumed_until`.
+    pub fn release(&mut self, resumed_until: Option<f64>) {
+        if let (Some(frozen), Some(until)) = (self.frozen.take(), resumed_until) {
+            self.resumed.insert(frozen.name().to_string(), until);
+        }
+        self.answers = None;
+    }
+
+    /// Whether the user resumed a victim called `name` recently.
+    pub fn recently_resumed(&mut self, name: &str, now: f64) -> bool {
+        self.resumed.retain(|_, &mut until| until > now);
+        self.resumed.contains_key(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    fn frozen() -> Frozen {
+        Frozen::new(
+            FrozenTarget::Process {
+                pid: 4242,
+                start_time: 7,
+            },
+            VictimRecord::Process {
+                pid: 4242,
+                name: "gimp".to_string(),
+                score: 500,
+            },
+            LoopKey {
+                key: "/usr/bin/gimp".to_string(),
+                unit: None,
+            },
+            "PID 4242, RSS 3.0 GiB".to_string(),
+            100.0,
+            &FreezeConfig::default(),
+        )
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "oomkiller-freeze-{}/frozen.json",
+            std::process::id()
+        ));
+        let mut state = FreezeState {
+            frozen: Some(frozen()),
+            ..FreezeState::default()
+        };
+        state.release(Some(500.0));
+        state.frozen = Some(frozen());
+        state.save(&path).unwrap();
+        let mut loaded = FreezeState::load(&path).unwrap();
+        assert_eq!(loaded.frozen, Some(frozen()));
+        assert!(loaded.recently_resumed("gimp", 400.0));
+        assert!(!loaded.recently_resumed("gimp", 500.0));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert!(FreezeState::load(&path).unwrap().frozen.is_none());
+    }
+
+    #[test]
+    fn test_take_answer_skips_non_answers() {
+        let (tx, rx) = mpsc::channel();
+        let mut state = FreezeState {
+            answers: Some(rx),
+            ..FreezeState::default()
+        };
+        assert_eq!(state.take_answer(), None);
+        tx.send("default".to_string()).unwrap();
+        tx.send("resume".to_string()).unwrap();
+        assert_eq!(state.take_answer(), Some(Answer::Resume));
+        drop(tx);
+        assert_eq!(state.take_answer(), None);
+        assert!(state.answers.is_none());
+    }
+
+    #[test]
+    fn test_applies_to() {
+        let mut config = FreezeConfig::default();
+        assert!(!config.applies_to("gimp"));
+        config.enabled = true;
+        assert!(config.applies_to("gimp"));
+        config.apps = Patterns::new(&["^gimp$"]).unwrap();
+        assert!(config.applies_to("gimp") && !config.applies_to("rustc"));
+    }
+
+    #[test]
+    fn test_process_target_liveness() {
+        let pid = std::process::id();
+        let start_time = read_start_time(pid).unwrap();
+        assert!(FrozenTarget::Process { pid, start_time }.is_alive());
+        let reused = FrozenTarget::Process {
+            pid,
+            start_time: start_time + 1,
+        };
+        assert!(!reused.is_alive());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(FreezeConfig::default().validate().is_ok());
+        let config = FreezeConfig {
+            timeout_secs: 0.0,
+            ..FreezeConfig::default()
+        };
+        assert!(config.validate().is_err());
+        let config = FreezeConfig {
+            ceiling_percent: 101.0,
+            ..FreezeConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
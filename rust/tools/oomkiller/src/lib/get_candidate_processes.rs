@@ -0,0 +1,71 @@
+use crate::{
+    config::Target, get_user_processes, get_user_processes::process_info, types::ProcessInfo,
+};
+use sysinfo::System;
+
+/// Lists the processes that may be killed for the configured target.
+///
+/// Uses a System object that should already have been refreshed with refresh_all().
+///
+/// # Arguments
+/// * `system` - A System object that has already been refreshed with refresh_all()
+/// * `target` - `User` for the daemon's own UID, `All` for every visible process
+///
+/// With `Target::All`, PID 1, the daemon itself and kernel threads (no cmdline) are skipped.
+/// Threads of a process are never listed on their own; the process they belong to is.
+pub fn get_candidate_processes(
+    system: &System,
+    target: Target,
+) -> Result<Vec<ProcessInfo>, String> {
+    match target {
+        Target::User => get_user_processes(system),
+        Target::All => {
+            let own_pid = std::process::id();
+            Ok(system
+                .processes()
+                .iter()
+                .filter(|(pid, process)| {
+                    let pid = pid.as_u32();
+                    pid != 1
+                        && pid != own_pid
+                        && !process.cmd().is_empty()
+                        && process.thread_kind().is_none()
+                })
+                .map(|(pid, process)| process_info(*pid, process))
+                .collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_candidate_processes_all_skips_init_and_self() {
+        let mut system = System::new_all();
+        system.refresh_all();
+        let processes = get_candidate_processes(&system, Target::All).unwrap();
+        let own_pid = std::process::id();
+        assert!(processes.iter().all(|p| p.pid != 1 && p.pid != own_pid));
+    }
+
+    #[test]
+    fn test_get_candidate_processes_user_matches_get_user_processes() {
+        let mut system = System::new_all();
+        system.refresh_all();
+        let mut via_target: Vec<u32> = get_candidate_processes(&system, Target::User)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.pid)
+            .collect();
+        let mut direct: Vec<u32> = get_user_processes(&system)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.pid)
+            .collect();
+        via_target.sort_unstable();
+        direct.sort_unstable();
+        assert_eq!(via_target, direct);
+    }
+}
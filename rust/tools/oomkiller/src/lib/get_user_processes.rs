@@ -1,5 +1,9 @@
-use crate::{check_process_owned_by_user, get_current_uid, types::ProcessInfo};
-use sysinfo::System;
+use crate::{
+    check_process_owned_by_user, get_current_uid,
+    procfs::{read_oom_score, read_oom_score_adj, read_start_time, read_vm_swap},
+    types::ProcessInfo,
+};
+use sysinfo::{Pid, Process, System};
 
 /// Lists all processes owned by the current user.
 ///
@@ -17,65 +21,99 @@ pub fn get_user_processes(system: &System) -> Result<Vec<ProcessInfo>, String> {
     let mut processes = Vec::new();
 
     for (pid, process) in system.processes() {
+        // sysinfo lists the threads of each process too; they share its memory.
+        if process.thread_kind().is_some() {
+            continue;
+        }
         let pid_u32 = (*pid).as_u32();
         if !check_process_owned_by_user(pid_u32, current_uid).unwrap_or(false) {
             continue;
         }
 
-        let name = process.name().to_string_lossy().into_owned();
-        let cmdline = process
-            .cmd()
-            .iter()
-            .map(|s| s.to_string_lossy())
-            .collect::<Vec<_>>()
-            .join(" ");
-
-        processes.push(ProcessInfo {
-            pid: pid_u32,
-            memory: process.memory(),
-            name,
-            cmdline,
-        });
+        processes.push(process_info(*pid, process));
     }
 
     Ok(processes)
 }
 
+/// Converts a sysinfo process entry into the `ProcessInfo` used for selection and logging.
+pub(crate) fn process_info(pid: Pid, process: &Process) -> ProcessInfo {
+    let name = process.name().to_string_lossy().into_owned();
+    let cmdline = process
+        .cmd()
+        .iter()
+        .map(|s| s.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    ProcessInfo {
+        pid: pid.as_u32(),
+        memory: process.memory(),
+        swap: read_vm_swap(pid.as_u32()).unwrap_or(0),
+        name,
+        cmdline,
+        // 0 never matches a live process, so an unreadable stat makes the kill refuse.
+        start_time: read_start_time(pid.as_u32()).unwrap_or(0),
+        oom_score: read_oom_score(pid.as_u32()).unwrap_or(0),
+        oom_score_adj: read_oom_score_adj(pid.as_u32()).unwrap_or(0),
+        // Read per candidate by `ranked_candidates` when scoring needs it.
+        smaps: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_get_user_processes_returns_result() {
+    fn test_get_user_processes_lists_own_child() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
         let mut system = System::new_all();
         system.refresh_all();
-        let result = get_user_processes(&system);
-        assert!(result.is_ok() || result.is_err());
+        let processes = get_user_processes(&system).unwrap();
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        let found = processes
+            .iter()
+            .find(|p| p.pid == child.id())
+            .expect("child listed");
+        assert_eq!(found.name, "sleep");
+        assert_eq!(found.cmdline, "sleep 30");
+        assert!(found.start_time > 0);
     }
 
     #[test]
-    fn test_get_user_processes_returns_vector_when_ok() {
+    fn test_get_user_processes_only_lists_own_uid() {
         let mut system = System::new_all();
         system.refresh_all();
-        let result = get_user_processes(&system);
-        if let Ok(processes) = result {
-            for process in &processes {
-                assert!(process.pid > 0);
-            }
+        let uid = get_current_uid().unwrap();
+        for process in get_user_processes(&system).unwrap() {
+            assert!(
+                check_process_owned_by_user(process.pid, uid).unwrap_or(true),
+                "{process:?}"
+            );
         }
     }
 
     #[test]
-    fn test_get_user_processes_filters_by_uid() {
+    fn test_get_user_processes_skips_threads() {
         let mut system = System::new_all();
         system.refresh_all();
-        let result = get_user_processes(&system);
-        if let Ok(processes) = result {
-            for process in processes {
-                assert!(process.pid > 0);
-                let _ = &process.name;
-                let _ = &process.cmdline;
-            }
-        }
+        let mut pids: Vec<u32> = get_user_processes(&system)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.pid)
+            .collect();
+        let listed = pids.len();
+        pids.sort_unstable();
+        pids.dedup();
+        assert_eq!(pids.len(), listed);
+        assert!(system.processes().iter().all(|(pid, process)| {
+            process.thread_kind().is_none() || !pids.contains(&pid.as_u32())
+        }));
     }
 }
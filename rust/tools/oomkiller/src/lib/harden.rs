@@ -0,0 +1,239 @@
+use crate::{config::Target, host::Host, procfs::read_oom_score_adj};
+use serde::Deserialize;
+
+/// `[hardening]` section of the config file; applied once at startup.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HardeningConfig {
+    /// Lock the daemon's memory with `mlockall` so it cannot be swapped out.
+    pub mlock: bool,
+    /// `oom_score_adj` to lower the daemon to (-1000..=1000); never raised.
+    pub oom_score_adj: i32,
+}
+
+impl Default for HardeningConfig {
+    fn default() -> Self {
+        Self {
+            mlock: true,
+            oom_score_adj: -900,
+        }
+    }
+}
+
+impl HardeningConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(-1000..=1000).contains(&self.oom_score_adj) {
+            return Err(format!(
+                "hardening.oom_score_adj must be between -1000 and 1000, got {}",
+                self.oom_score_adj
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of one hardening step, for the startup log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HardeningStep {
+    pub name: &'static str,
+    /// What was done, or why it could not be (the daemon runs on either way).
+    pub result: Result<String, String>,
+}
+
+/// Protects the daemon itself before the first iteration.
+///
+/// In order: lowers `oom_score_adj`, warms up the kill path (stdout buffer, one
+/// process scan, a pidfd probe of the daemon itself) so its buffers and code are
+/// resident, then locks memory. Every step is best effort; failures are reported
+/// and the daemon carries on unprotected.
+///
+/// # Arguments
+/// * `config` - Which steps to run
+/// * `host` - Its process table is scanned once to preallocate the scan buffers
+/// * `target` - Target for that scan
+#[cfg(target_os = "linux")]
+pub fn harden(config: &HardeningConfig, host: &mut Host, target: Target) -> Vec<HardeningStep> {
+    vec![
+        HardeningStep {
+            name: "oom_score_adj",
+            result: protect_oom_score(config.oom_score_adj),
+        },
+        HardeningStep {
+            name: "preallocate",
+            result: preallocate(host, target),
+        },
+        // After the warm-up, so a current-only lock still covers the kill path.
+        HardeningStep {
+            name: "mlockall",
+            result: if config.mlock {
+                lock_memory()
+            } else {
+                Ok("disabled (hardening.mlock = false)".to_string())
+            },
+        },
+    ]
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn harden(_config: &HardeningConfig, _host: &mut Host, _target: Target) -> Vec<HardeningStep> {
+    Vec::new()
+}
+
+/// Lowers `/proc/self/oom_score_adj` to `target` unless it is already lower.
+///
+/// Going below the value the daemon started with needs `CAP_SYS_RESOURCE`;
+/// without it the unit's `OOMScoreAdjust=` has to do it.
+pub fn protect_oom_score(target: i32) -> Result<String, String> {
+    let current =
+        read_oom_score_adj(std::process::id()).ok_or("cannot read /proc/self/oom_score_adj")?;
+    if current <= target {
+        return Ok(format!("already {current}"));
+    }
+    std::fs::write("/proc/self/oom_score_adj", target.to_string())
+        .map(|()| format!("{current} -> {target}"))
+        .map_err(|e| {
+            format!(
+                "cannot lower {current} -> {target}: {e} (needs CAP_SYS_RESOURCE or OOMScoreAdjust= in the unit)"
+            )
+        })
+}
+
+/// Touches everything the kill path uses so nothing is allocated or paged in later.
+#[cfg(target_os = "linux")]
+fn preallocate(host: &mut Host, target: Target) -> Result<String, String> {
+    use crate::{pidfd::PidFd, procfs::read_start_time};
+    use std::io::Write;
+
+    // Stdout's line buffer is allocated on first use.
+    std::io::stdout()
+        .flush()
+        .map_err(|e| format!("stdout: {e}"))?;
+
+    host.processes.refresh(target);
+    let scanned = host.processes.candidates(target)?.len();
+
+    let pid = std::process::id();
+    let pidfd = PidFd::open_verified(pid, read_start_time(pid)?)?;
+    pidfd
+        .send_signal(0)
+        .map_err(|e| format!("pidfd probe: {e}"))?;
+    Ok(format!(
+        "stdout buffer, scan buffers ({scanned} processes), pidfd"
+    ))
+}
+
+/// `mlockall`, current and future mappings when the lock limit allows it.
+///
+/// `MCL_ONFAULT` locks pages as they are touched instead of populating every
+/// mapping (thread stacks, allocator arenas) up front. `MCL_FUTURE` is only used
+/// when `RLIMIT_MEMLOCK` is unlimited or the daemon has `CAP_IPC_LOCK`: with a
+/// finite limit it would make allocations fail once the limit is reached, so
+/// only the current (warmed-up) mappings are locked then.
+#[cfg(target_os = "linux")]
+pub fn lock_memory() -> Result<String, String> {
+    let limit = memlock_limit();
+    let unlimited = limit.is_none() || has_cap_ipc_lock();
+    let limit_text = limit.map_or("unlimited".to_string(), crate::format_bytes);
+    let (flags, scope) = if unlimited {
+        (libc::MCL_CURRENT | libc::MCL_FUTURE, "current and future")
+    } else {
+        (libc::MCL_CURRENT, "current")
+    };
+
+    let mut on_fault = true;
+    // SAFETY: mlockall only takes flags.
+    let mut rc = unsafe { libc::mlockall(flags | libc::MCL_ONFAULT) };
+    if rc != 0 && std::io::Error::last_os_error().raw_os_error() == Some(libc::EINVAL) {
+        // Kernels before 4.4 have no MCL_ONFAULT.
+        on_fault = false;
+        // SAFETY: as above.
+        rc = unsafe { libc::mlockall(flags) };
+    }
+    if rc != 0 {
+        let e = std::io::Error::last_os_error();
+        return Err(format!(
+            "{e} (RLIMIT_MEMLOCK {limit_text}); running unlocked, raise LimitMEMLOCK= or grant CAP_IPC_LOCK"
+        ));
+    }
+    Ok(format!(
+        "{scope} mappings{} (RLIMIT_MEMLOCK {limit_text})",
+        if on_fault { ", on fault" } else { "" }
+    ))
+}
+
+/// Soft `RLIMIT_MEMLOCK` in bytes, or `None` if unlimited.
+#[cfg(target_os = "linux")]
+fn memlock_limit() -> Option<u64> {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: `limit` is a valid rlimit for getrlimit to fill in.
+    if unsafe { libc::getrlimit(libc::RLIMIT_MEMLOCK, &mut limit) } != 0 {
+        return Some(0);
+    }
+    (limit.rlim_cur != libc::RLIM_INFINITY).then_some(limit.rlim_cur)
+}
+
+/// Capability number from `linux/capability.h`.
+#[cfg(target_os = "linux")]
+const CAP_IPC_LOCK: u32 = 14;
+
+#[cfg(target_os = "linux")]
+fn has_cap_ipc_lock() -> bool {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| parse_cap_eff(&status))
+        .is_some_and(|caps| caps & (1 << CAP_IPC_LOCK) != 0)
+}
+
+/// The effective capability set from `/proc/<pid>/status`.
+pub fn parse_cap_eff(status: &str) -> Option<u64> {
+    let hex = status
+        .lines()
+        .find_map(|line| line.strip_prefix("CapEff:"))?
+        .trim();
+    u64::from_str_radix(hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_oom_score_adj_range() {
+        assert!(HardeningConfig::default().validate().is_ok());
+        let config = HardeningConfig {
+            oom_score_adj: -1001,
+            ..HardeningConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_cap_eff() {
+        let status = "Name:\toomkiller\nCapInh:\t0000000000000000\nCapEff:\t0000000000004000\n";
+        assert_eq!(parse_cap_eff(status), Some(0x4000));
+        assert_eq!(parse_cap_eff("Name:\tx\n"), None);
+    }
+
+    #[test]
+    fn test_protect_oom_score_never_raises() {
+        let current = read_oom_score_adj(std::process::id()).unwrap();
+        assert_eq!(protect_oom_score(1000), Ok(format!("already {current}")));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_harden_reports_every_step() {
+        let config = HardeningConfig {
+            // Locking the whole test binary is not wanted; oom_score_adj stays put.
+            mlock: false,
+            oom_score_adj: 1000,
+        };
+        let steps = harden(&config, &mut Host::default(), Target::User);
+        let names: Vec<_> = steps.iter().map(|s| s.name).collect();
+        assert_eq!(names, ["oom_score_adj", "preallocate", "mlockall"]);
+        assert!(steps.iter().all(|s| s.result.is_ok()), "{steps:?}");
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{
+    cgroup::AppGroup,
+    config::{EscalationConfig, Target},
+    effective_memory::MemoryConfig,
+    freeze::FrozenTarget,
+    kill_history::SystemdUnit,
+    psi::PsiSnapshot,
+    smaps::SmapsRollup,
+    system_host::{KernelSignals, SysinfoMemory},
+    types::{KillOutcome, ProcessInfo},
+};
+use std::path::PathBuf;
+
+/// Used and total bytes, measured per `memory.accounting`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemorySnapshot {
+    pub used: u64,
+    pub total: u64,
+}
+
+impl MemorySnapshot {
+    pub fn used_percent(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.used as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+/// Where memory usage and pressure come from; polled on every iteration.
+pub trait MemorySource {
+    /// Refreshes and returns memory usage under `config.accounting`.
+    ///
+    /// Returns `Err` if memory information cannot be read at all.
+    fn snapshot(&mut self, config: &MemoryConfig) -> Result<MemorySnapshot, String>;
+
+    /// Current `/proc/pressure/memory` averages, or `None` without PSI.
+    fn pressure(&mut self) -> Option<PsiSnapshot>;
+}
+
+/// The processes (and their cgroups) a victim is chosen from.
+pub trait ProcessTable {
+    /// Rescans the processes eligible for `target`; only done once memory is critical.
+    fn refresh(&mut self, target: Target);
+
+    /// Total RAM in bytes, which RSS is scaled against when scoring.
+    fn total_memory(&self) -> u64;
+
+    /// Processes that may be killed for `target` (see `get_candidate_processes`).
+    fn candidates(&self, target: Target) -> Result<Vec<ProcessInfo>, String>;
+
+    /// cgroup v2 path of `pid`, or `None` if it cannot be read.
+    fn cgroup_path(&self, pid: u32) -> Option<String>;
+
+    /// Executable `pid` runs, which kill-loop detection counts kills against.
+    fn executable(&self, pid: u32) -> Option<PathBuf>;
+
+    /// `/proc/<pid>/smaps_rollup`, for scoring by PSS or USS; `None` if unreadable.
+    fn smaps_rollup(&self, pid: u32) -> Option<SmapsRollup>;
+
+    /// `memory.current` of the unit, or `None` if it cannot be read.
+    fn unit_memory(&self, group: &AppGroup) -> Option<u64>;
+
+    /// Whether the daemon may write the unit's `cgroup.kill`.
+    fn is_delegated(&self, group: &AppGroup) -> bool;
+
+    /// Whether a frozen victim still exists; read directly, without a rescan.
+    fn is_alive(&self, target: &FrozenTarget) -> bool;
+
+    /// Direct children of `pid`, read directly, for killing process trees.
+    fn children(&self, pid: u32) -> Vec<ProcessInfo>;
+}
+
+/// Stops the chosen victim.
+///
+/// `stop_process` and `stop_app` run the SIGTERM→SIGKILL escalation and ask
+/// `memory_recovered` during the grace period whether SIGKILL is still needed.
+pub trait SignalSink {
+    fn stop_process(
+        &mut self,
+        process: &ProcessInfo,
+        config: &EscalationConfig,
+        critical: bool,
+        memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String>;
+
+    fn stop_app(
+        &mut self,
+        group: &AppGroup,
+        config: &EscalationConfig,
+        critical: bool,
+        memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String>;
+
+    /// Like `stop_process`, but signals `descendants` (deepest first) before the victim.
+    fn stop_tree(
+        &mut self,
+        process: &ProcessInfo,
+        descendants: &[ProcessInfo],
+        config: &EscalationConfig,
+        critical: bool,
+        memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String>;
+
+    /// Stops a systemd unit that keeps restarting after kills, so it stays down.
+    fn stop_unit(&mut self, unit: &SystemdUnit) -> Result<(), String>;
+
+    /// Freezes (`true`) or thaws a victim: `SIGSTOP`/`SIGCONT` for a process,
+    /// `cgroup.freeze` for an app unit.
+    fn freeze(&mut self, target: &FrozenTarget, frozen: bool) -> Result<(), String>;
+}
+
+/// Everything the daemon reads from and acts on; the default is the live system,
+/// with processes read by [`ProcScanner`](crate::proc_scanner::ProcScanner) on Linux.
+pub struct Host {
+    pub memory: Box<dyn MemorySource>,
+    pub processes: Box<dyn ProcessTable>,
+    pub signals: Box<dyn SignalSink>,
+}
+
+impl Default for Host {
+    fn default() -> Self {
+        Self {
+            memory: Box::new(SysinfoMemory::default()),
+            #[cfg(target_os = "linux")]
+            processes: Box::new(crate::proc_scanner::ProcScanner::default()),
+            #[cfg(not(target_os = "linux"))]
+            processes: Box::new(crate::system_host::SysinfoProcesses::default()),
+            signals: Box::new(KernelSignals),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_used_percent() {
+        let snapshot = MemorySnapshot { used: 3, total: 4 };
+        assert_eq!(snapshot.used_percent(), 75.0);
+        assert_eq!(MemorySnapshot::default().used_percent(), 0.0);
+    }
+}
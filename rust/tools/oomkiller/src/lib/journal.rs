@@ -0,0 +1,301 @@
+use crate::{
+    badness::{Candidate, Rule},
+    cgroup::AppGroup,
+    config::Config,
+    psi::PsiSnapshot,
+};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// `[journal]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JournalConfig {
+    /// Append-only JSON-lines file for kill decisions; unset disables the journal.
+    pub path: Option<PathBuf>,
+    /// How many of the ranked candidates each record lists.
+    pub top_n: usize,
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            top_n: 10,
+        }
+    }
+}
+
+impl JournalConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.top_n == 0 || self.top_n > 1000 {
+            return Err(format!(
+                "journal.top_n must be between 1 and 1000, got {}",
+                self.top_n
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Memory reading at decision time (bytes, per `memory.accounting`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MemoryRecord {
+    pub accounting: &'static str,
+    pub used: u64,
+    pub total: u64,
+    pub used_percent: f64,
+}
+
+/// One ranked candidate with its score breakdown.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CandidateRecord {
+    pub pid: u32,
+    pub name: String,
+    pub rss: u64,
+    pub swap: u64,
+    /// Expected to be freed by a kill, per the decision's `memory_metric`.
+    pub frees: u64,
+    pub oom_score: i32,
+    pub oom_score_adj: i32,
+    pub score: i64,
+    pub mem_points: i64,
+    pub oom_points: i64,
+    pub adj_points: i64,
+    pub rule_points: i64,
+    pub rule: Rule,
+}
+
+impl From<&Candidate> for CandidateRecord {
+    fn from(candidate: &Candidate) -> Self {
+        let p = &candidate.process;
+        let b = &candidate.badness;
+        Self {
+            pid: p.pid,
+            name: p.name.clone(),
+            rss: p.memory,
+            swap: p.swap,
+            frees: b.memory,
+            oom_score: p.oom_score,
+            oom_score_adj: p.oom_score_adj,
+            score: b.total,
+            mem_points: b.mem_points,
+            oom_points: b.oom_points,
+            adj_points: b.adj_points,
+            rule_points: b.rule_points,
+            rule: b.rule,
+        }
+    }
+}
+
+/// What was (or would have been) killed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum VictimRecord {
+    Process {
+        pid: u32,
+        name: String,
+        score: i64,
+    },
+    Cgroup {
+        unit: String,
+        path: String,
+        memory: u64,
+        processes: usize,
+    },
+}
+
+impl VictimRecord {
+    pub fn process(candidate: &Candidate) -> Self {
+        Self::Process {
+            pid: candidate.process.pid,
+            name: candidate.process.name.clone(),
+            score: candidate.badness.total,
+        }
+    }
+
+    /// Process name or app unit, used as the metrics label.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Process { name, .. } => name,
+            Self::Cgroup { unit, .. } => unit,
+        }
+    }
+
+    pub fn cgroup(group: &AppGroup) -> Self {
+        Self::Cgroup {
+            unit: group.unit().to_string(),
+            path: group.path.clone(),
+            memory: group.memory,
+            processes: group.processes.len(),
+        }
+    }
+}
+
+/// Descendants killed along with a process victim (see `[tree]`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TreeRecord {
+    /// In signalling order, deepest first.
+    pub pids: Vec<u32>,
+    /// Expected to be freed by them, on top of the victim's own `frees`.
+    pub frees: u64,
+}
+
+/// One line of the decision journal: the situation, the ranking and what was done.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Decision {
+    /// Seconds since the Unix epoch.
+    pub timestamp: f64,
+    pub dry_run: bool,
+    /// `trigger` from the config, `manual` for `oomkiller ctl kill-now`, or `leak`
+    /// for a leaking process acted on before memory got critical.
+    pub trigger: &'static str,
+    pub threshold_percent: f64,
+    pub group_by: &'static str,
+    /// `scoring.memory_metric`, which candidates' `frees` and `mem_points` come from.
+    pub memory_metric: &'static str,
+    pub memory: MemoryRecord,
+    pub psi: Option<PsiSnapshot>,
+    /// Top `journal.top_n` candidates, highest badness first.
+    pub candidates: Vec<CandidateRecord>,
+    pub victim: Option<VictimRecord>,
+    /// `dry_run`, `terminated`, `recovered`, `killed`, `failed`, `cooldown` (the top
+    /// candidate is in a kill-loop cooldown), `paused` (`oomkiller ctl pause`),
+    /// `frozen` / `resumed` (see `[freeze]`), `leak_warning` (see `[leak]`) or `none`
+    /// (nothing killable).
+    pub action: &'static str,
+    /// Escalation outcome (`killed after=3.00s`) or the error, if any.
+    pub outcome: Option<String>,
+    /// Set when this kill completed a kill loop: the count and how the daemon backed off.
+    pub kill_loop: Option<String>,
+    /// Report written right before the kill (see `[snapshot]`).
+    pub snapshot: Option<PathBuf>,
+    /// Descendants of a process victim that were signalled with it.
+    pub tree: Option<TreeRecord>,
+    /// Set when a Nix build was stopped instead of an app: `cgroup`, `client` or
+    /// `builder` (see `[nix]`).
+    pub nix: Option<&'static str>,
+}
+
+impl Decision {
+    /// Starts a record for the current iteration; candidates, victim and action are filled in later.
+    pub fn new(config: &Config, used: u64, total: u64, psi: Option<PsiSnapshot>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or(0.0);
+        Self {
+            timestamp: (timestamp * 1000.0).round() / 1000.0,
+            dry_run: config.dry_run,
+            trigger: config.trigger.as_str(),
+            threshold_percent: config.threshold_percent,
+            group_by: config.group_by.as_str(),
+            memory_metric: config.scoring.memory_metric.as_str(),
+            memory: MemoryRecord {
+                accounting: config.memory.accounting.as_str(),
+                used,
+                total,
+                used_percent: if total == 0 {
+                    0.0
+                } else {
+                    used as f64 / total as f64 * 100.0
+                },
+            },
+            psi,
+            candidates: Vec::new(),
+            victim: None,
+            action: "none",
+            outcome: None,
+            kill_loop: None,
+            snapshot: None,
+            tree: None,
+            nix: None,
+        }
+    }
+}
+
+/// Appends `decision` as one JSON line to `path`, creating the file if needed.
+///
+/// The file is opened per record in append mode, so it can be rotated or
+/// truncated while the daemon runs.
+pub fn append_decision(path: &Path, decision: &Decision) -> Result<(), String> {
+    let mut line =
+        serde_json::to_string(decision).map_err(|e| format!("Failed to encode decision: {e}"))?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| format!("Failed to append to {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::badness::{ScoringConfig, rank_candidates};
+    use crate::types::ProcessInfo;
+
+    fn ranked() -> Vec<Candidate> {
+        let process = |pid, name: &str, memory| ProcessInfo {
+            pid,
+            memory,
+            name: name.to_string(),
+            ..ProcessInfo::default()
+        };
+        rank_candidates(
+            vec![process(1, "small", 100), process(2, "big", 1000)],
+            10_000,
+            &ScoringConfig::default(),
+        )
+    }
+
+    #[test]
+    fn test_decision_json_shape() {
+        let candidates = ranked();
+        let mut decision = Decision::new(&Config::default(), 900, 1000, None);
+        decision.candidates = candidates.iter().map(CandidateRecord::from).collect();
+        decision.victim = Some(VictimRecord::process(&candidates[0]));
+        decision.action = "dry_run";
+
+        let value: serde_json::Value = serde_json::to_value(&decision).unwrap();
+        assert_eq!(value["memory"]["used_percent"], 90.0);
+        assert_eq!(value["memory"]["accounting"], "ram");
+        assert_eq!(value["psi"], serde_json::Value::Null);
+        assert_eq!(value["candidates"][0]["name"], "big");
+        assert_eq!(value["candidates"][0]["rule"], "none");
+        assert_eq!(value["victim"]["kind"], "process");
+        assert_eq!(value["victim"]["pid"], 2);
+        assert_eq!(value["action"], "dry_run");
+    }
+
+    #[test]
+    fn test_append_decision_writes_one_line_per_record() {
+        let path =
+            std::env::temp_dir().join(format!("oomkiller-journal-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let decision = Decision::new(&Config::default(), 1, 2, Some(PsiSnapshot::default()));
+        append_decision(&path, &decision).unwrap();
+        append_decision(&path, &decision).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let value: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(value["psi"]["some"]["avg10"], 0.0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_journal_config_validation() {
+        assert!(JournalConfig::default().validate().is_ok());
+        let zero = JournalConfig {
+            top_n: 0,
+            ..JournalConfig::default()
+        };
+        assert!(zero.validate().is_err());
+    }
+}
@@ -0,0 +1,289 @@
+use crate::{cgroup::service_unit, types::ProcessInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What to do once the same target has been killed `max_kills` times in `window_secs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoopAction {
+    /// Leave it alone for `cooldown_secs`; while it is the top candidate, kill nothing.
+    Cooldown,
+    /// Leave it alone for `cooldown_secs` and kill the next candidate instead.
+    #[default]
+    Skip,
+    /// Stop its systemd unit so it is not restarted again; `skip` if it has none.
+    StopUnit,
+}
+
+impl LoopAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cooldown => "cooldown",
+            Self::Skip => "skip",
+            Self::StopUnit => "stop_unit",
+        }
+    }
+}
+
+/// `[kill_loop]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KillLoopConfig {
+    /// Kills of the same target within `window_secs` that count as a loop.
+    pub max_kills: usize,
+    pub window_secs: f64,
+    /// How long a looping target is left alone.
+    pub cooldown_secs: f64,
+    pub action: LoopAction,
+    /// History file; default `$XDG_STATE_HOME/oomkiller/kill-history.json`. Read at startup.
+    pub history: Option<PathBuf>,
+}
+
+impl Default for KillLoopConfig {
+    fn default() -> Self {
+        Self {
+            max_kills: 3,
+            window_secs: 600.0,
+            cooldown_secs: 1800.0,
+            action: LoopAction::default(),
+            history: None,
+        }
+    }
+}
+
+impl KillLoopConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_kills < 2 {
+            return Err(format!(
+                "kill_loop.max_kills must be at least 2, got {}",
+                self.max_kills
+            ));
+        }
+        for (key, value) in [
+            ("kill_loop.window_secs", self.window_secs),
+            ("kill_loop.cooldown_secs", self.cooldown_secs),
+        ] {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(format!("{key} must be a positive number, got {value}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// `history`, or `$XDG_STATE_HOME/oomkiller/kill-history.json` (`~/.local/state` by default).
+    pub fn history_path(&self) -> Option<PathBuf> {
+        match &self.history {
+            Some(path) => Some(path.clone()),
+            None => Some(state_dir()?.join("kill-history.json")),
+        }
+    }
+}
+
+/// `$XDG_STATE_HOME/oomkiller` (`~/.local/state/oomkiller` by default), for files that
+/// outlive a daemon restart.
+pub fn state_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local").join("state"))
+        })?;
+    Some(base.join("oomkiller"))
+}
+
+/// A systemd unit a looping target can be stopped through.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemdUnit {
+    pub name: String,
+    /// Runs under the user's manager rather than the system one.
+    pub user: bool,
+}
+
+/// What repeated kills are counted against.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoopKey {
+    /// The `.service` unit if there is one (restarts keep it), else the executable, else the name.
+    pub key: String,
+    pub unit: Option<SystemdUnit>,
+}
+
+impl LoopKey {
+    /// Key for `process` (for an app unit, its largest member), given its cgroup
+    /// path and `/proc/<pid>/exe` where known.
+    ///
+    /// App scopes get a fresh name on every launch, so only services are keyed by unit.
+    pub fn new(process: &ProcessInfo, cgroup: Option<&str>, exe: Option<&Path>) -> Self {
+        if let Some(cgroup) = cgroup
+            && let Some(unit) = service_unit(cgroup)
+        {
+            return Self {
+                key: unit.to_string(),
+                unit: Some(SystemdUnit {
+                    name: unit.to_string(),
+                    user: cgroup.contains("/user@"),
+                }),
+            };
+        }
+        let key = match exe {
+            Some(exe) => exe.display().to_string(),
+            None => process.name.clone(),
+        };
+        Self { key, unit: None }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct KillRecord {
+    key: String,
+    /// Seconds since the Unix epoch.
+    at: f64,
+}
+
+/// Recent kills and active cooldowns, persisted across restarts as JSON.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KillHistory {
+    kills: Vec<KillRecord>,
+    /// Key -> Unix time the cooldown ends.
+    cooldowns: BTreeMap<String, f64>,
+}
+
+impl KillHistory {
+    /// Reads the history; a missing file is an empty history.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|e| format!("Invalid kill history {}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!(
+                "Failed to read kill history {}: {e}",
+                path.display()
+            )),
+        }
+    }
+
+    /// Writes the history atomically (temp file, then rename), creating the directory.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let fail = |e: &dyn std::fmt::Display| {
+            format!("Failed to write kill history {}: {e}", path.display())
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| fail(&e))?;
+        }
+        let text = serde_json::to_string(self).map_err(|e| fail(&e))?;
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, text).map_err(|e| fail(&e))?;
+        fs::rename(&tmp, path).map_err(|e| fail(&e))
+    }
+
+    /// When the cooldown for `key` ends, if one is active at `now`.
+    pub fn cooldown_until(&self, key: &str, now: f64) -> Option<f64> {
+        self.cooldowns
+            .get(key)
+            .copied()
+            .filter(|&until| until > now)
+    }
+
+    /// Records a kill of `key` at `now` and forgets whatever fell out of the window.
+    ///
+    /// Returns the number of kills in the window once it reaches `max_kills`.
+    pub fn record(&mut self, key: &str, now: f64, config: &KillLoopConfig) -> Option<usize> {
+        let since = now - config.window_secs;
+        self.kills.retain(|k| k.at > since);
+        self.cooldowns.retain(|_, &mut until| until > now);
+        self.kills.push(KillRecord {
+            key: key.to_string(),
+            at: now,
+        });
+        let count = self.kills.iter().filter(|k| k.key == key).count();
+        (count >= config.max_kills).then_some(count)
+    }
+
+    /// Leaves `key` alone until `until`; its kill count starts over afterwards.
+    pub fn start_cooldown(&mut self, key: &str, until: f64) {
+        self.kills.retain(|k| k.key != key);
+        self.cooldowns.insert(key.to_string(), until);
+    }
+
+    /// Drops every kill of `key`, e.g. once its unit has been stopped.
+    pub fn forget(&mut self, key: &str) {
+        self.kills.retain(|k| k.key != key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_detects_loop_within_window() {
+        let config = KillLoopConfig::default();
+        let mut history = KillHistory::default();
+        assert_eq!(history.record("a.service", 0.0, &config), None);
+        assert_eq!(history.record("b", 10.0, &config), None);
+        assert_eq!(history.record("a.service", 20.0, &config), None);
+        assert_eq!(history.record("a.service", 30.0, &config), Some(3));
+        // Kills older than the window no longer count.
+        assert_eq!(history.record("b", 700.0, &config), None);
+    }
+
+    #[test]
+    fn test_cooldown_expires_and_resets_count() {
+        let config = KillLoopConfig::default();
+        let mut history = KillHistory::default();
+        history.record("a", 0.0, &config);
+        history.start_cooldown("a", 100.0);
+        assert_eq!(history.cooldown_until("a", 50.0), Some(100.0));
+        assert_eq!(history.cooldown_until("a", 100.0), None);
+        assert_eq!(history.record("a", 110.0, &config), None);
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "oomkiller-history-{}/kill-history.json",
+            std::process::id()
+        ));
+        let mut history = KillHistory::default();
+        history.record("a.service", 1.0, &KillLoopConfig::default());
+        history.start_cooldown("b", 99.0);
+        history.save(&path).unwrap();
+        assert_eq!(KillHistory::load(&path).unwrap(), history);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert_eq!(KillHistory::load(&path).unwrap(), KillHistory::default());
+    }
+
+    #[test]
+    fn test_loop_key_prefers_service_then_exe() {
+        let process = ProcessInfo {
+            name: "worker".to_string(),
+            ..ProcessInfo::default()
+        };
+        let service = "/user.slice/user-1000.slice/user@1000.service/app.slice/sync.service";
+        let key = LoopKey::new(&process, Some(service), Some(Path::new("/usr/bin/worker")));
+        assert_eq!(key.key, "sync.service");
+        assert_eq!(
+            key.unit,
+            Some(SystemdUnit {
+                name: "sync.service".to_string(),
+                user: true
+            })
+        );
+        let scope = "/user.slice/user-1000.slice/session-2.scope";
+        let key = LoopKey::new(&process, Some(scope), Some(Path::new("/usr/bin/worker")));
+        assert_eq!(key.key, "/usr/bin/worker");
+        assert_eq!(key.unit, None);
+        assert_eq!(LoopKey::new(&process, None, None).key, "worker");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(KillLoopConfig::default().validate().is_ok());
+        let config = KillLoopConfig {
+            max_kills: 1,
+            ..KillLoopConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -1,36 +1,34 @@
 use crate::types::ProcessInfo;
 
-/// Kills a process by sending SIGKILL signal.
+/// Kills a process by sending SIGKILL through a pidfd.
+///
+/// The pidfd is opened and checked against `process.start_time` first, so a PID
+/// that was recycled after the process was chosen is never signalled. No helper
+/// process is spawned, which matters when memory is already exhausted.
 ///
 /// # Arguments
-/// * `process` - The ProcessInfo containing the PID of the process to kill
+/// * `process` - The ProcessInfo containing the PID and start time of the process to kill
 ///
 /// # Returns
 /// * `Ok(())` if the process was successfully killed
-/// * `Err(String)` if the kill operation failed
+/// * `Err(String)` if the process is gone, was replaced, or the kill operation failed
 ///
 /// # Platform
 /// Linux-only. Returns an error on non-Linux platforms.
 pub fn kill_process(process: &ProcessInfo) -> Result<(), String> {
     #[cfg(target_os = "linux")]
     {
-        use std::process::Command;
-
-        let output = Command::new("kill")
-            .arg("-9") // SIGKILL
-            .arg(process.pid.to_string())
-            .output()
-            .map_err(|e| format!("Failed to execute kill command: {e}"))?;
+        use crate::pidfd::PidFd;
 
-        if output.status.success() {
-            Ok(())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(format!(
-                "Failed to kill name={} pid={}: {stderr}",
+        let fail = |e: &dyn std::fmt::Display| {
+            format!(
+                "Failed to kill name={} pid={}: {e}",
                 process.name, process.pid
-            ))
-        }
+            )
+        };
+
+        let pidfd = PidFd::open_verified(process.pid, process.start_time).map_err(|e| fail(&e))?;
+        pidfd.send_signal(libc::SIGKILL).map_err(|e| fail(&e))
     }
 
     #[cfg(not(target_os = "linux"))]
@@ -50,15 +48,10 @@ mod tests {
             memory: 0,
             name: "test".to_string(),
             cmdline: String::new(),
+            ..ProcessInfo::default()
         }
     }
 
-    #[test]
-    fn test_kill_process_returns_result() {
-        let result = kill_process(&sample(999_999_999));
-        assert!(result.is_ok() || result.is_err());
-    }
-
     #[test]
     #[cfg(target_os = "linux")]
     fn test_kill_process_with_invalid_pid() {
@@ -73,7 +66,30 @@ mod tests {
     }
 
     #[test]
-    fn test_kill_process_accepts_process_info() {
-        let _result = kill_process(&sample(1234));
+    #[cfg(target_os = "linux")]
+    fn test_kill_process_refuses_reused_pid() {
+        // Our own PID with a wrong start time looks exactly like a recycled PID.
+        let mut me = sample(std::process::id());
+        me.start_time = crate::procfs::read_start_time(me.pid).unwrap() + 1;
+        let err = kill_process(&me).unwrap_err();
+        assert!(err.contains("reused"), "unexpected error: {err}");
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_kill_process_kills_child() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        let process = ProcessInfo {
+            start_time: crate::procfs::read_start_time(pid).unwrap(),
+            ..sample(pid)
+        };
+        kill_process(&process).unwrap();
+        let status = child.wait().unwrap();
+        use std::os::unix::process::ExitStatusExt;
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
     }
 }
@@ -0,0 +1,414 @@
+use crate::{
+    badness::{Candidate, Rule, sort_candidates},
+    types::ProcessInfo,
+};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+const MIB: f64 = 1024.0 * 1024.0;
+
+/// Dips smaller than this fraction of the previous sample still count as growing
+/// (allocator noise, a page or two swapped in and out).
+const DIP_TOLERANCE: f64 = 0.02;
+
+/// What to do with a process that keeps growing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeakAction {
+    /// Log, journal and notify once; at a breach it is still preferred as the victim.
+    #[default]
+    Warn,
+    /// Warn, then kill it `kill_after_secs` later if it is still growing.
+    Kill,
+}
+
+impl LeakAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Warn => "warn",
+            Self::Kill => "kill",
+        }
+    }
+}
+
+/// `[leak]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LeakConfig {
+    /// Sample every candidate's memory and watch for steady growth.
+    pub enabled: bool,
+    pub action: LeakAction,
+    /// Seconds between samples; each one rescans all processes.
+    pub sample_secs: f64,
+    /// Seconds a process must have grown over, without dipping, to count as leaking.
+    pub window_secs: f64,
+    /// Least growth rate over the window, in MiB per minute.
+    pub min_rate_mib_per_min: f64,
+    /// Least size (RSS + swap) in MiB; smaller processes are never flagged.
+    pub min_size_mib: u64,
+    /// With `action = "kill"`, seconds between the warning and the kill.
+    pub kill_after_secs: f64,
+    /// Added to a leaking candidate's score when memory is critical.
+    pub bonus: i64,
+}
+
+impl Default for LeakConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            action: LeakAction::default(),
+            sample_secs: 30.0,
+            window_secs: 600.0,
+            min_rate_mib_per_min: 20.0,
+            min_size_mib: 1024,
+            kill_after_secs: 300.0,
+            bonus: 300,
+        }
+    }
+}
+
+impl LeakConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.sample_secs >= 1.0 && self.sample_secs <= 3600.0) {
+            return Err(format!(
+                "leak.sample_secs must be between 1 and 3600, got {}",
+                self.sample_secs
+            ));
+        }
+        if !(self.window_secs >= 3.0 * self.sample_secs && self.window_secs <= 86_400.0) {
+            return Err(format!(
+                "leak.window_secs must be between 3 * leak.sample_secs and 86400, got {}",
+                self.window_secs
+            ));
+        }
+        if !(self.min_rate_mib_per_min.is_finite() && self.min_rate_mib_per_min > 0.0) {
+            return Err(format!(
+                "leak.min_rate_mib_per_min must be positive, got {}",
+                self.min_rate_mib_per_min
+            ));
+        }
+        if !(self.kill_after_secs.is_finite()
+            && self.kill_after_secs >= 0.0
+            && self.kill_after_secs <= 86_400.0)
+        {
+            return Err(format!(
+                "leak.kill_after_secs must be between 0 and 86400, got {}",
+                self.kill_after_secs
+            ));
+        }
+        if !(0..=10_000).contains(&self.bonus) {
+            return Err(format!(
+                "leak.bonus must be between 0 and 10000, got {}",
+                self.bonus
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// How fast a process has been growing over `leak.window_secs`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Growth {
+    /// Least-squares slope, bytes per second.
+    pub rate: f64,
+    /// RSS + swap at the latest sample.
+    pub size: u64,
+}
+
+impl Growth {
+    pub fn per_minute(&self) -> u64 {
+        (self.rate * 60.0) as u64
+    }
+}
+
+/// A process flagged as leaking, with when it was warned about (Unix seconds).
+#[derive(Debug, Clone)]
+pub struct Leak {
+    pub process: ProcessInfo,
+    pub growth: Growth,
+    pub warned: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    at: f64,
+    bytes: u64,
+}
+
+#[derive(Debug)]
+struct History {
+    process: ProcessInfo,
+    samples: VecDeque<Sample>,
+    warned: Option<f64>,
+}
+
+impl History {
+    /// `Some` if the samples span the window, never dip and grow fast enough.
+    fn growth(&self, config: &LeakConfig) -> Option<Growth> {
+        let (first, last) = (self.samples.front()?, self.samples.back()?);
+        if self.samples.len() < 3
+            || last.at - first.at + config.sample_secs / 2.0 < config.window_secs
+        {
+            return None;
+        }
+        let steady = self
+            .samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .all(|(a, b)| b.bytes as f64 >= a.bytes as f64 * (1.0 - DIP_TOLERANCE));
+        let rate = slope(&self.samples);
+        (steady
+            && last.bytes >= config.min_size_mib * MIB as u64
+            && rate * 60.0 >= config.min_rate_mib_per_min * MIB)
+            .then_some(Growth {
+                rate,
+                size: last.bytes,
+            })
+    }
+}
+
+/// Least-squares slope of memory over time, bytes per second.
+fn slope(samples: &VecDeque<Sample>) -> f64 {
+    let n = samples.len() as f64;
+    let mean_t = samples.iter().map(|s| s.at).sum::<f64>() / n;
+    let mean_b = samples.iter().map(|s| s.bytes as f64).sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for s in samples {
+        cov += (s.at - mean_t) * (s.bytes as f64 - mean_b);
+        var += (s.at - mean_t) * (s.at - mean_t);
+    }
+    if var == 0.0 { 0.0 } else { cov / var }
+}
+
+/// Memory history of every candidate, sampled every `leak.sample_secs`.
+///
+/// Processes are keyed by PID and start time, so a reused PID starts a new history.
+#[derive(Debug, Default)]
+pub struct LeakTracker {
+    histories: HashMap<(u32, u64), History>,
+    last_sample: Option<f64>,
+}
+
+impl LeakTracker {
+    /// Whether leak detection is on and the next sample is due at `now` (Unix seconds).
+    pub fn due(&self, now: f64, config: &LeakConfig) -> bool {
+        config.enabled
+            && self
+                .last_sample
+                .is_none_or(|last| now - last >= config.sample_secs)
+    }
+
+    /// Records RSS + swap of `processes` at `now`. Histories of processes that are
+    /// gone are dropped, and a process that stopped growing loses its warning, so
+    /// the next leak warns again before any kill.
+    pub fn sample(&mut self, now: f64, processes: &[ProcessInfo], config: &LeakConfig) {
+        self.last_sample = Some(now);
+        let mut seen = HashSet::new();
+        for process in processes {
+            let key = (process.pid, process.start_time);
+            seen.insert(key);
+            let history = self.histories.entry(key).or_insert_with(|| History {
+                process: process.clone(),
+                samples: VecDeque::new(),
+                warned: None,
+            });
+            history.process = process.clone();
+            history.samples.push_back(Sample {
+                at: now,
+                bytes: process.memory + process.swap,
+            });
+            // Keep one sample at or before the window start as the baseline.
+            while history
+                .samples
+                .get(1)
+                .is_some_and(|s| s.at <= now - config.window_secs)
+            {
+                history.samples.pop_front();
+            }
+            if history.growth(config).is_none() {
+                history.warned = None;
+            }
+        }
+        self.histories.retain(|key, _| seen.contains(key));
+    }
+
+    /// Processes currently growing per `config`, fastest first.
+    pub fn leaking(&self, config: &LeakConfig) -> Vec<Leak> {
+        let mut leaks: Vec<Leak> = self
+            .histories
+            .values()
+            .filter_map(|h| {
+                Some(Leak {
+                    growth: h.growth(config)?,
+                    process: h.process.clone(),
+                    warned: h.warned,
+                })
+            })
+            .collect();
+        leaks.sort_by(|a, b| b.growth.rate.total_cmp(&a.growth.rate));
+        leaks
+    }
+
+    /// Notes that `process` was warned about at `now`.
+    pub fn warn(&mut self, process: &ProcessInfo, now: f64) {
+        if let Some(history) = self.histories.get_mut(&(process.pid, process.start_time)) {
+            history.warned = Some(now);
+        }
+    }
+
+    /// Adds `leak.bonus` to leaking candidates without an `avoid` or `never` rule,
+    /// marks them [`Rule::Leaking`] and sorts the ranking again.
+    pub fn mark_leaking(&self, candidates: &mut [Candidate], config: &LeakConfig) {
+        if !config.enabled {
+            return;
+        }
+        let mut marked = false;
+        for candidate in candidates.iter_mut() {
+            let p = &candidate.process;
+            let leaking = self
+                .histories
+                .get(&(p.pid, p.start_time))
+                .is_some_and(|h| h.growth(config).is_some());
+            if leaking && matches!(candidate.badness.rule, Rule::None | Rule::Prefer) {
+                candidate.badness.rule = Rule::Leaking;
+                candidate.badness.rule_points += config.bonus;
+                candidate.badness.total += config.bonus;
+                marked = true;
+            }
+        }
+        if marked {
+            sort_candidates(candidates);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::badness::{ScoringConfig, rank_candidates};
+    use crate::fake_host::GIB;
+
+    const MIB: u64 = 1024 * 1024;
+
+    fn process(pid: u32, memory: u64) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            memory,
+            name: format!("p{pid}"),
+            start_time: u64::from(pid),
+            ..ProcessInfo::default()
+        }
+    }
+
+    /// Samples every 30s for 10 minutes; `size(i)` gives each process's memory at sample `i`.
+    fn watch(tracker: &mut LeakTracker, size: impl Fn(u32, u64) -> u64, pids: &[u32]) {
+        let config = LeakConfig {
+            enabled: true,
+            ..LeakConfig::default()
+        };
+        for i in 0..=20 {
+            let processes: Vec<_> = pids.iter().map(|&pid| process(pid, size(pid, i))).collect();
+            tracker.sample(i as f64 * 30.0, &processes, &config);
+        }
+    }
+
+    #[test]
+    fn test_steady_growth_is_a_leak() {
+        let config = LeakConfig::default();
+        let mut tracker = LeakTracker::default();
+        // 1: +50 MiB per sample (100 MiB/min). 2: same, but freed every 5 samples.
+        // 3: slow. 4: fast but small.
+        watch(
+            &mut tracker,
+            |pid, i| match pid {
+                1 => GIB + i * 50 * MIB,
+                2 => GIB + (i % 5) * 50 * MIB,
+                3 => GIB + i * MIB,
+                _ => 100 * MIB + i * 10 * MIB,
+            },
+            &[1, 2, 3, 4],
+        );
+        let leaks = tracker.leaking(&config);
+        assert_eq!(leaks.len(), 1);
+        assert_eq!(leaks[0].process.pid, 1);
+        assert_eq!(leaks[0].growth.per_minute(), 100 * MIB);
+        assert_eq!(leaks[0].growth.size, GIB + 1000 * MIB);
+    }
+
+    #[test]
+    fn test_needs_full_window_and_forgets_exited() {
+        let config = LeakConfig::default();
+        let mut tracker = LeakTracker::default();
+        for i in 0..10 {
+            tracker.sample(i as f64 * 30.0, &[process(1, GIB + i * 100 * MIB)], &config);
+        }
+        assert!(tracker.leaking(&config).is_empty());
+        assert!(!tracker.due(300.0, &config));
+
+        let config = LeakConfig {
+            enabled: true,
+            ..config
+        };
+        assert!(tracker.due(300.0, &config));
+        assert!(!tracker.due(290.0, &config));
+        tracker.sample(300.0, &[], &config);
+        assert!(tracker.histories.is_empty());
+    }
+
+    #[test]
+    fn test_warning_resets_when_growth_stops() {
+        let config = LeakConfig::default();
+        let mut tracker = LeakTracker::default();
+        watch(&mut tracker, |_, i| GIB + i * 50 * MIB, &[1]);
+        tracker.warn(&process(1, 0), 600.0);
+        assert_eq!(tracker.leaking(&config)[0].warned, Some(600.0));
+        tracker.sample(630.0, &[process(1, GIB)], &config);
+        assert!(tracker.leaking(&config).is_empty());
+        assert_eq!(tracker.histories[&(1, 1)].warned, None);
+    }
+
+    #[test]
+    fn test_mark_leaking_reorders_candidates() {
+        let config = LeakConfig {
+            enabled: true,
+            ..LeakConfig::default()
+        };
+        let mut tracker = LeakTracker::default();
+        watch(
+            &mut tracker,
+            |pid, i| {
+                if pid == 1 {
+                    GIB + i * 50 * MIB
+                } else {
+                    6 * GIB
+                }
+            },
+            &[1, 2],
+        );
+        let mut ranked = rank_candidates(
+            vec![process(1, 2 * GIB), process(2, 6 * GIB)],
+            16 * GIB,
+            &ScoringConfig::default(),
+        );
+        assert_eq!(ranked[0].process.pid, 2);
+        tracker.mark_leaking(&mut ranked, &config);
+        assert_eq!(ranked[0].process.pid, 1);
+        assert_eq!(ranked[0].badness.rule, Rule::Leaking);
+        assert_eq!(ranked[0].badness.rule_points, 300);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(LeakConfig::default().validate().is_ok());
+        let config = LeakConfig {
+            window_secs: 60.0,
+            ..LeakConfig::default()
+        };
+        assert!(config.validate().is_err());
+        let config = LeakConfig {
+            min_rate_mib_per_min: 0.0,
+            ..LeakConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,340 @@
+use crate::psi::PsiSnapshot;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// `[metrics]` section of the config file.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+    /// node_exporter textfile (`*.prom`), rewritten atomically every iteration.
+    pub textfile: Option<PathBuf>,
+    /// Loopback address for an HTTP `/metrics` listener, e.g. `127.0.0.1:9567`.
+    pub listen: Option<SocketAddr>,
+}
+
+impl MetricsConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(addr) = self.listen
+            && !addr.ip().is_loopback()
+        {
+            return Err(format!(
+                "metrics.listen must be a loopback address, got {addr}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Counters and gauges exported in the Prometheus text format.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    pub iterations: u64,
+    pub breaches: u64,
+    /// Kills by (process or unit name, outcome).
+    pub kills: BTreeMap<(String, String), u64>,
+    /// Unix time of the last kill (dry runs excluded).
+    pub last_kill_timestamp: Option<f64>,
+    /// Process or unit name of that kill, for the systemd status line.
+    pub last_kill_name: Option<String>,
+    /// Kill loops detected (see `[kill_loop]`).
+    pub kill_loops: u64,
+    /// Processes currently growing per `[leak]`.
+    pub leaking: usize,
+    pub used_percent: f64,
+    /// Sleep before the next check (see `[poll]`).
+    pub poll_interval_secs: f64,
+    pub psi: Option<PsiSnapshot>,
+}
+
+impl Metrics {
+    /// Records a kill decision; `outcome` is `killed`, `terminated`, `recovered`,
+    /// `failed`, `dry_run`, `frozen`, `resumed` or `leak_warning`.
+    pub fn record_kill(&mut self, name: &str, outcome: &str) {
+        *self
+            .kills
+            .entry((name.to_string(), outcome.to_string()))
+            .or_default() += 1;
+        if !matches!(
+            outcome,
+            "dry_run" | "failed" | "frozen" | "resumed" | "leak_warning"
+        ) {
+            self.last_kill_name = Some(name.to_string());
+            self.last_kill_timestamp = Some(
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs_f64())
+                    .unwrap_or(0.0),
+            );
+        }
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, String)]| {
+            out.push_str(&format!("# HELP {name} {help}\n# TYPE {name} {kind}\n"));
+            for (labels, value) in samples {
+                out.push_str(&format!("{name}{labels} {value}\n"));
+            }
+        };
+        let plain = |value: String| vec![(String::new(), value)];
+
+        metric(
+            "oomkiller_iterations_total",
+            "counter",
+            "Memory checks performed.",
+            &plain(self.iterations.to_string()),
+        );
+        metric(
+            "oomkiller_threshold_breaches_total",
+            "counter",
+            "Checks on which memory was critical.",
+            &plain(self.breaches.to_string()),
+        );
+        let kills: Vec<_> = self
+            .kills
+            .iter()
+            .map(|((name, outcome), count)| {
+                (
+                    format!(
+                        "{{name=\"{}\",outcome=\"{}\"}}",
+                        escape_label(name),
+                        escape_label(outcome)
+                    ),
+                    count.to_string(),
+                )
+            })
+            .collect();
+        metric(
+            "oomkiller_kills_total",
+            "counter",
+            "Kill decisions by process (or app unit) name and outcome.",
+            &kills,
+        );
+        metric(
+            "oomkiller_kill_loops_total",
+            "counter",
+            "Targets killed kill_loop.max_kills times within kill_loop.window_secs.",
+            &plain(self.kill_loops.to_string()),
+        );
+        metric(
+            "oomkiller_leaking_processes",
+            "gauge",
+            "Processes growing faster than leak.min_rate_mib_per_min, at the last sample.",
+            &plain(self.leaking.to_string()),
+        );
+        metric(
+            "oomkiller_last_kill_timestamp_seconds",
+            "gauge",
+            "Unix time of the last kill, 0 if none yet.",
+            &plain(self.last_kill_timestamp.unwrap_or(0.0).to_string()),
+        );
+        metric(
+            "oomkiller_memory_used_percent",
+            "gauge",
+            "Used memory percentage as measured for the threshold.",
+            &plain(self.used_percent.to_string()),
+        );
+        metric(
+            "oomkiller_poll_interval_seconds",
+            "gauge",
+            "Sleep before the next memory check.",
+            &plain(self.poll_interval_secs.to_string()),
+        );
+        if let Some(psi) = self.psi {
+            let samples: Vec<_> = [("some", psi.some), ("full", psi.full)]
+                .into_iter()
+                .flat_map(|(kind, line)| {
+                    [("10", line.avg10), ("60", line.avg60), ("300", line.avg300)]
+                        .into_iter()
+                        .map(move |(window, value)| {
+                            (
+                                format!("{{kind=\"{kind}\",window=\"{window}\"}}"),
+                                value.to_string(),
+                            )
+                        })
+                })
+                .collect();
+            metric(
+                "oomkiller_psi_memory_avg_percent",
+                "gauge",
+                "PSI memory stall averages from /proc/pressure/memory.",
+                &samples,
+            );
+        }
+        out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Writes `text` to `path` atomically: a temporary file in the same directory, then rename,
+/// so node_exporter never reads a half-written file.
+pub fn write_textfile(path: &Path, text: &str) -> Result<(), String> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("metrics.textfile {} has no file name", path.display()))?;
+    let tmp = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    std::fs::write(&tmp, text)
+        .and_then(|()| std::fs::rename(&tmp, path))
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Serves the latest rendered metrics on `GET /metrics` from a background thread.
+///
+/// `shared` is replaced by the daemon after every iteration; the listener only reads it.
+///
+/// Returns the address actually bound, which differs from `addr` for port 0.
+pub fn serve_metrics(addr: SocketAddr, shared: Arc<Mutex<String>>) -> Result<SocketAddr, String> {
+    let listener =
+        TcpListener::bind(addr).map_err(|e| format!("Failed to listen on {addr}: {e}"))?;
+    let bound = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to listen on {addr}: {e}"))?;
+    std::thread::Builder::new()
+        .name("metrics".to_string())
+        .spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Err(e) = respond(stream, &shared) {
+                    eprintln!("Metrics request failed: {e}");
+                }
+            }
+        })
+        .map(|_| bound)
+        .map_err(|e| format!("Failed to spawn metrics thread: {e}"))
+}
+
+/// Longest request (line and headers) read before answering.
+const MAX_REQUEST_BYTES: u64 = 8 * 1024;
+
+fn respond(mut stream: TcpStream, shared: &Mutex<String>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    // Clients may send the request in pieces; read it up to the blank line ending the
+    // headers, or closing early resets the connection before they are done writing.
+    let mut reader = BufReader::new((&stream).take(MAX_REQUEST_BYTES));
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if reader.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+            break;
+        }
+    }
+    let path = request.split_whitespace().nth(1).unwrap_or("");
+    let (status, body) = if request.starts_with("GET ") && path == "/metrics" {
+        let body = shared.lock().map(|s| s.clone()).unwrap_or_default();
+        ("200 OK", body)
+    } else {
+        ("404 Not Found", "not found\n".to_string())
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Metrics {
+        let mut metrics = Metrics {
+            iterations: 12,
+            breaches: 2,
+            used_percent: 93.5,
+            psi: Some(PsiSnapshot::default()),
+            ..Metrics::default()
+        };
+        metrics.record_kill("firefox", "killed");
+        metrics.record_kill("firefox", "killed");
+        metrics.record_kill("we\"ird", "dry_run");
+        metrics
+    }
+
+    #[test]
+    fn test_render_text_format() {
+        let text = sample().render();
+        assert!(text.contains(
+            "# TYPE oomkiller_iterations_total counter\noomkiller_iterations_total 12\n"
+        ));
+        assert!(text.contains("oomkiller_threshold_breaches_total 2\n"));
+        assert!(text.contains("oomkiller_kills_total{name=\"firefox\",outcome=\"killed\"} 2\n"));
+        assert!(text.contains("oomkiller_kills_total{name=\"we\\\"ird\",outcome=\"dry_run\"} 1\n"));
+        assert!(text.contains("oomkiller_memory_used_percent 93.5\n"));
+        assert!(
+            text.contains("oomkiller_psi_memory_avg_percent{kind=\"full\",window=\"300\"} 0\n")
+        );
+    }
+
+    #[test]
+    fn test_last_kill_ignores_dry_runs() {
+        let mut metrics = Metrics::default();
+        metrics.record_kill("x", "dry_run");
+        assert_eq!(metrics.last_kill_timestamp, None);
+        metrics.record_kill("x", "terminated");
+        assert!(metrics.last_kill_timestamp.unwrap() > 0.0);
+        assert_eq!(metrics.last_kill_name.as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn test_write_textfile_replaces_atomically() {
+        let path =
+            std::env::temp_dir().join(format!("oomkiller-metrics-{}.prom", std::process::id()));
+        write_textfile(&path, "a 1\n").unwrap();
+        write_textfile(&path, "a 2\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a 2\n");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_serve_metrics_answers_get() {
+        let shared = Arc::new(Mutex::new("oomkiller_iterations_total 1\n".to_string()));
+        let addr = serve_metrics("127.0.0.1:0".parse().unwrap(), Arc::clone(&shared)).unwrap();
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            // In two writes, as clients may send it: answering after the request line
+            // alone would reset the connection.
+            write!(stream, "GET {path} HTTP/1.1\r\n").unwrap();
+            std::thread::sleep(Duration::from_millis(20));
+            write!(stream, "Host: localhost\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let ok = get("/metrics");
+        assert!(ok.starts_with("HTTP/1.1 200 OK"), "{ok}");
+        assert!(ok.ends_with("oomkiller_iterations_total 1\n"));
+        assert!(get("/").starts_with("HTTP/1.1 404"));
+    }
+
+    #[test]
+    fn test_metrics_config_requires_loopback() {
+        let local = MetricsConfig {
+            listen: Some("127.0.0.1:9567".parse().unwrap()),
+            ..MetricsConfig::default()
+        };
+        assert!(local.validate().is_ok());
+        let public = MetricsConfig {
+            listen: Some("0.0.0.0:9567".parse().unwrap()),
+            ..MetricsConfig::default()
+        };
+        assert!(public.validate().is_err());
+    }
+}
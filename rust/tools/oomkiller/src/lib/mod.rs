@@ -1,17 +1,93 @@
+pub mod badness;
+pub mod cgroup;
 pub mod check_process_owned_by_user;
+pub mod config;
+pub mod control;
 pub mod daemon_iteration;
-pub mod find_highest_memory_process;
+pub mod daemon_state;
+pub mod effective_memory;
+#[cfg(target_os = "linux")]
+pub mod escalate_kill;
+#[cfg(target_os = "linux")]
+pub mod escalate_kill_group;
+#[cfg(target_os = "linux")]
+pub mod escalate_kill_tree;
+pub mod evaluate_trigger;
+pub mod fake_host;
+pub mod freeze;
+pub mod get_candidate_processes;
 pub mod get_current_uid;
 pub mod get_user_processes;
-pub mod is_memory_threshold_exceeded;
+pub mod harden;
+pub mod host;
+pub mod journal;
+pub mod kill_history;
 pub mod kill_process;
+pub mod leak;
+pub mod metrics;
+pub mod nix_build;
+pub mod notify;
+#[cfg(target_os = "linux")]
+pub mod pidfd;
+pub mod poll_interval;
+#[cfg(target_os = "linux")]
+pub mod proc_scanner;
+pub mod process_tree;
+pub mod procfs;
+pub mod psi;
+pub mod sd_notify;
+pub mod select_app;
+pub mod select_victim;
+pub mod smaps;
+pub mod snapshot;
+pub mod system_host;
 pub mod types;
 
+pub use badness::{Candidate, ScoringConfig, explain_candidate, rank_candidates};
+pub use cgroup::AppGroup;
 pub use check_process_owned_by_user::check_process_owned_by_user;
+pub use config::{
+    Config, ConfigOverrides, EscalationConfig, GroupBy, MEMORY_THRESHOLD_PERCENT, Target,
+    TriggerMode, default_config_path,
+};
+pub use control::{
+    Control, ControlConfig, ProtectTarget, Request, default_socket_path, parse_duration,
+    send_request, serve_control,
+};
 pub use daemon_iteration::daemon_iteration;
-pub use find_highest_memory_process::find_highest_memory_process;
+pub use daemon_state::DaemonState;
+pub use effective_memory::{Accounting, EffectiveMemory, MemoryConfig, read_effective_memory};
+#[cfg(target_os = "linux")]
+pub use escalate_kill::escalate_kill;
+#[cfg(target_os = "linux")]
+pub use escalate_kill_group::escalate_kill_group;
+#[cfg(target_os = "linux")]
+pub use escalate_kill_tree::escalate_kill_tree;
+pub use evaluate_trigger::evaluate_trigger;
+pub use freeze::{FreezeConfig, FreezeState, FrozenTarget};
+pub use get_candidate_processes::get_candidate_processes;
 pub use get_current_uid::get_current_uid;
 pub use get_user_processes::get_user_processes;
-pub use is_memory_threshold_exceeded::{MEMORY_THRESHOLD_PERCENT, is_memory_threshold_exceeded};
+pub use harden::{HardeningConfig, harden};
+pub use host::{Host, MemorySnapshot, MemorySource, ProcessTable, SignalSink};
+pub use journal::{Decision, JournalConfig, append_decision};
+pub use kill_history::{KillHistory, KillLoopConfig, LoopAction, LoopKey, SystemdUnit};
 pub use kill_process::kill_process;
-pub use types::{ProcessInfo, format_bytes};
+pub use leak::{LeakAction, LeakConfig, LeakTracker};
+pub use metrics::{Metrics, MetricsConfig, serve_metrics};
+pub use nix_build::{NixConfig, NixTarget, select_nix_build};
+pub use notify::{KillNotifier, NotifyConfig};
+#[cfg(target_os = "linux")]
+pub use pidfd::PidFd;
+pub use poll_interval::{AdaptiveInterval, PollConfig};
+#[cfg(target_os = "linux")]
+pub use proc_scanner::ProcScanner;
+pub use process_tree::{TreeConfig, descendants};
+pub use psi::{PsiConfig, PsiSnapshot, PsiTrigger, read_memory_pressure};
+pub use sd_notify::{SystemdNotifier, status_line};
+pub use select_app::select_app;
+pub use select_victim::{ranked_candidates, select_victim};
+pub use smaps::{MemoryMetric, SmapsRollup, read_smaps_rollup};
+pub use snapshot::{Capture, SnapshotConfig, write_snapshot};
+pub use system_host::{KernelSignals, SysinfoMemory, SysinfoProcesses};
+pub use types::{Bytes, KillOutcome, ProcessInfo, format_bytes};
@@ -0,0 +1,265 @@
+use crate::{
+    badness::{Candidate, Patterns},
+    cgroup::AppGroup,
+    host::ProcessTable,
+};
+use serde::Deserialize;
+
+/// `[nix]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NixConfig {
+    /// Stop a running Nix build before any app when memory is critical.
+    pub enabled: bool,
+    /// systemd unit of the Nix daemon; processes in its cgroup, other than the
+    /// daemon itself, are builders.
+    pub daemon_unit: String,
+    /// Regexes for Nix client processes; the daemon cancels a client's builds once it exits.
+    pub clients: Patterns,
+}
+
+impl Default for NixConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            daemon_unit: "nix-daemon.service".to_string(),
+            // `nixos-update-no` is nixos-update-notifier, cut to the 15-byte comm.
+            clients: Patterns::new(&[
+                "^(nix|nix-build|nix-shell|nix-store|nix-env|nixos-rebuild|nixos-update-no)$",
+            ])
+            .expect("default nix.clients patterns are valid"),
+        }
+    }
+}
+
+impl NixConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.daemon_unit.ends_with(".service") || self.daemon_unit.contains('/') {
+            return Err(format!(
+                "nix.daemon_unit must be a service name such as nix-daemon.service, got {:?}",
+                self.daemon_unit
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The Nix build to stop, in the order they are tried.
+#[derive(Debug, Clone)]
+pub enum NixTarget {
+    /// A build's own cgroup below the daemon unit (Nix `use-cgroups`), killed as a whole.
+    Cgroup(AppGroup),
+    /// A client such as `nix build` or `nixos-rebuild`.
+    Client(Candidate),
+    /// A builder process in the daemon's cgroup, when there is neither.
+    Builder(Candidate),
+}
+
+impl NixTarget {
+    /// `cgroup`, `client` or `builder`, for the journal.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Cgroup(_) => "cgroup",
+            Self::Client(_) => "client",
+            Self::Builder(_) => "builder",
+        }
+    }
+}
+
+/// Where a process sits relative to the Nix daemon's unit: outside it (`None`), in
+/// the unit itself (`Some(None)`), or in a cgroup below it (`Some(Some(path))`, the
+/// path of that cgroup).
+fn build_cgroup<'a>(path: &'a str, unit: &str) -> Option<Option<&'a str>> {
+    let start = path.match_indices('/').find_map(|(i, _)| {
+        let rest = &path[i + 1..];
+        (rest.starts_with(unit) && matches!(rest.as_bytes().get(unit.len()), None | Some(b'/')))
+            .then_some(i + 1 + unit.len())
+    })?;
+    let below = &path[start..];
+    let Some(child) = below.strip_prefix('/') else {
+        return Some(None);
+    };
+    let end = start + 1 + child.find('/').unwrap_or(child.len());
+    Some(Some(&path[..end]))
+}
+
+/// Picks the Nix build to stop instead of an app.
+///
+/// Builders are the candidates in `nix.daemon_unit`'s cgroup other than `nix-daemon`
+/// itself; they run as `nixbld*` users or root, so they are only listed with
+/// `target = "all"`. A build with its own cgroup whose `cgroup.kill` is writable is
+/// preferred (heaviest first), then the newest client matching `nix.clients`, then
+/// the highest-scoring builder. Cgroups holding a process protected by `never` or
+/// `oomkiller ctl protect` are skipped, like those processes.
+///
+/// # Arguments
+/// * `processes` - A process table that has already been refreshed
+/// * `ranked` - The scored candidates, with protections applied
+///
+/// Returns `None` when `nix.enabled` is off or no build is running.
+pub fn select_nix_build(
+    processes: &dyn ProcessTable,
+    ranked: &[Candidate],
+    config: &NixConfig,
+) -> Option<NixTarget> {
+    if !config.enabled {
+        return None;
+    }
+    let mut groups: Vec<AppGroup> = Vec::new();
+    let mut protected: Vec<String> = Vec::new();
+    let mut clients = Vec::new();
+    let mut builders = Vec::new();
+    for candidate in ranked {
+        let process = &candidate.process;
+        let cgroup = processes.cgroup_path(process.pid);
+        let Some(build) = cgroup
+            .as_deref()
+            .and_then(|path| build_cgroup(path, &config.daemon_unit))
+        else {
+            if candidate.is_killable() && config.clients.is_match(&process.name) {
+                clients.push(candidate.clone());
+            }
+            continue;
+        };
+        if !candidate.is_killable() {
+            protected.extend(build.map(str::to_string));
+            continue;
+        }
+        if process.name == "nix-daemon" {
+            continue;
+        }
+        builders.push(candidate.clone());
+        if let Some(path) = build {
+            match groups.iter_mut().find(|g| g.path == path) {
+                Some(group) => group.processes.push(process.clone()),
+                None => groups.push(AppGroup {
+                    path: path.to_string(),
+                    memory: 0,
+                    processes: vec![process.clone()],
+                }),
+            }
+        }
+    }
+
+    groups.retain(|g| !protected.contains(&g.path) && processes.is_delegated(g));
+    for group in &mut groups {
+        group.memory = processes
+            .unit_memory(group)
+            .unwrap_or_else(|| group.processes.iter().map(|p| p.memory).sum());
+    }
+    if let Some(group) = groups.into_iter().max_by_key(|g| g.memory) {
+        return Some(NixTarget::Cgroup(group));
+    }
+    if let Some(client) = clients.into_iter().max_by_key(|c| c.process.start_time) {
+        return Some(NixTarget::Client(client));
+    }
+    builders.into_iter().next().map(NixTarget::Builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::badness::{ScoringConfig, rank_candidates};
+    use crate::fake_host::{FakeProcesses, GIB};
+
+    const DAEMON: &str = "/system.slice/nix-daemon.service";
+    const BUILD: &str = "/system.slice/nix-daemon.service/nix-build-uid-30001";
+    const SHELL: &str = "/user.slice/user-1000.slice/session-2.scope";
+
+    fn enabled() -> NixConfig {
+        NixConfig {
+            enabled: true,
+            ..NixConfig::default()
+        }
+    }
+
+    fn select(processes: &FakeProcesses) -> Option<NixTarget> {
+        let ranked = rank_candidates(
+            processes.candidates(crate::Target::All).unwrap(),
+            processes.total_memory(),
+            &ScoringConfig::default(),
+        );
+        select_nix_build(processes, &ranked, &enabled())
+    }
+
+    fn build() -> FakeProcesses {
+        let mut processes = FakeProcesses::default();
+        processes
+            .add_in(10, "nix-daemon", GIB / 10, Some(DAEMON))
+            .add_in(11, "nix-daemon", GIB / 10, Some(DAEMON))
+            .add_in(20, "nix", GIB / 10, Some(SHELL))
+            .add_in(21, "nixos-rebuild", GIB / 100, Some(SHELL))
+            .add_in(30, "firefox", 4 * GIB, Some(SHELL));
+        processes
+    }
+
+    #[test]
+    fn test_build_cgroup() {
+        assert_eq!(build_cgroup(DAEMON, "nix-daemon.service"), Some(None));
+        assert_eq!(
+            build_cgroup(&format!("{BUILD}/x"), "nix-daemon.service"),
+            Some(Some(BUILD))
+        );
+        assert_eq!(build_cgroup(SHELL, "nix-daemon.service"), None);
+        assert_eq!(
+            build_cgroup("/system.slice/nix-daemon.service.d", "nix-daemon.service"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_prefers_build_cgroup_then_client_then_builder() {
+        let mut processes = build();
+        processes
+            .add_in(40, "cc1plus", 2 * GIB, Some(BUILD))
+            .add_in(41, "ld", 3 * GIB, Some(DAEMON));
+
+        processes.delegated = true;
+        let Some(NixTarget::Cgroup(group)) = select(&processes) else {
+            panic!("expected the build cgroup");
+        };
+        assert_eq!(group.path, BUILD);
+        assert_eq!(group.memory, 2 * GIB);
+
+        processes.delegated = false;
+        let Some(NixTarget::Client(client)) = select(&processes) else {
+            panic!("expected a client");
+        };
+        assert_eq!(client.process.name, "nixos-rebuild");
+
+        processes
+            .processes
+            .retain(|(p, _)| p.pid != 20 && p.pid != 21);
+        let Some(NixTarget::Builder(builder)) = select(&processes) else {
+            panic!("expected a builder");
+        };
+        assert_eq!(builder.process.name, "ld");
+    }
+
+    #[test]
+    fn test_nothing_without_build_or_when_disabled() {
+        let mut processes = build();
+        processes
+            .processes
+            .retain(|(p, _)| p.pid != 20 && p.pid != 21);
+        assert!(select(&processes).is_none());
+
+        let processes = build();
+        let ranked = rank_candidates(
+            processes.candidates(crate::Target::All).unwrap(),
+            processes.total_memory(),
+            &ScoringConfig::default(),
+        );
+        assert!(select_nix_build(&processes, &ranked, &NixConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(NixConfig::default().validate().is_ok());
+        let config = NixConfig {
+            daemon_unit: "nix-daemon".to_string(),
+            ..NixConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,551 @@
+use serde::Deserialize;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// `[notify]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifyConfig {
+    /// Send a desktop notification after each kill.
+    pub enabled: bool,
+    /// Also warn once SIGTERM has been sent, while the grace period runs.
+    pub on_sigterm: bool,
+    /// Minimum seconds between notifications; kills in between are counted and
+    /// mentioned in the next one.
+    pub min_interval_secs: f64,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            on_sigterm: false,
+            min_interval_secs: 30.0,
+        }
+    }
+}
+
+impl NotifyConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.min_interval_secs >= 0.0 && self.min_interval_secs <= 86_400.0) {
+            return Err(format!(
+                "notify.min_interval_secs must be between 0 and 86400, got {}",
+                self.min_interval_secs
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn min_interval(&self) -> Duration {
+        Duration::from_secs_f64(self.min_interval_secs)
+    }
+}
+
+/// One desktop notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notice {
+    pub summary: String,
+    pub body: String,
+    /// Kills are critical (stay until dismissed); SIGTERM warnings are normal.
+    pub critical: bool,
+    /// Buttons as (action id, label); only used by [`NotificationSink::ask`].
+    pub actions: Vec<(&'static str, &'static str)>,
+}
+
+/// Where notices go; the daemon uses [`DesktopSink`], tests record them.
+pub trait NotificationSink {
+    fn send(&mut self, notice: &Notice) -> Result<(), String>;
+
+    /// Sends a notice with buttons; the id of each button pressed (or `__closed`
+    /// when it is dismissed) arrives on the returned channel, which disconnects
+    /// once no answer can come any more.
+    fn ask(&mut self, notice: &Notice) -> Result<Receiver<String>, String>;
+
+    /// Closes the notification of the last [`ask`](Self::ask) if it is still shown,
+    /// which disconnects its channel.
+    fn withdraw(&mut self);
+}
+
+/// Desktop notifications via org.freedesktop.Notifications on the session bus.
+///
+/// Each notice is sent from its own thread, so a stalled bus (or notification
+/// daemon swapped out under memory pressure) never delays the next memory check.
+#[derive(Default)]
+pub struct DesktopSink {
+    /// The question of the last [`ask`](NotificationSink::ask), shared with its thread.
+    asked: Option<Arc<Mutex<Asked>>>,
+}
+
+/// A question shown by [`DesktopSink`] whose thread waits for the answer.
+#[derive(Default)]
+struct Asked {
+    /// Its notification id, once the notification daemon has shown it.
+    id: Option<u32>,
+    /// Withdrawn before it was shown (the thread closes it then) or already answered.
+    done: bool,
+}
+
+impl DesktopSink {
+    /// Shows `notice` from a new thread and hands the shown notification to `then`.
+    fn show(
+        notice: &Notice,
+        then: impl FnOnce(notify_rust::NotificationHandle) + Send + 'static,
+    ) -> Result<(), String> {
+        let notice = notice.clone();
+        std::thread::Builder::new()
+            .name("notify".to_string())
+            .spawn(move || {
+                let mut notification = notify_rust::Notification::new();
+                notification
+                    .summary(&notice.summary)
+                    .body(&notice.body)
+                    .appname("oomkiller")
+                    .icon("dialog-warning");
+                #[cfg(all(unix, not(target_os = "macos")))]
+                notification.urgency(if notice.critical {
+                    notify_rust::Urgency::Critical
+                } else {
+                    notify_rust::Urgency::Normal
+                });
+                for (id, label) in &notice.actions {
+                    notification.action(id, label);
+                }
+                match notification.show() {
+                    Ok(handle) => then(handle),
+                    Err(e) => eprintln!("Failed to send desktop notification: {e}"),
+                }
+            })
+            .map(drop)
+            .map_err(|e| format!("Failed to spawn notification thread: {e}"))
+    }
+
+    /// Closes notification `id` from a new thread; failures are only logged.
+    fn close(id: u32) {
+        let spawned = std::thread::Builder::new()
+            .name("notify".to_string())
+            .spawn(move || {
+                let closed = zbus::blocking::Connection::session().and_then(|bus| {
+                    bus.call_method(
+                        Some("org.freedesktop.Notifications"),
+                        "/org/freedesktop/Notifications",
+                        Some("org.freedesktop.Notifications"),
+                        "CloseNotification",
+                        &(id,),
+                    )
+                });
+                if let Err(e) = closed {
+                    eprintln!("Failed to close desktop notification {id}: {e}");
+                }
+            });
+        if let Err(e) = spawned {
+            eprintln!("Failed to spawn notification thread: {e}");
+        }
+    }
+}
+
+impl NotificationSink for DesktopSink {
+    fn send(&mut self, notice: &Notice) -> Result<(), String> {
+        Self::show(notice, drop)
+    }
+
+    /// The notification thread blocks on the bus until a button is pressed or the
+    /// notification is closed, by the user or by [`withdraw`](Self::withdraw); the
+    /// daemon polls the channel on every check.
+    fn ask(&mut self, notice: &Notice) -> Result<Receiver<String>, String> {
+        self.withdraw();
+        let (answer, answers) = mpsc::channel();
+        let asked = Arc::new(Mutex::new(Asked::default()));
+        let shared = Arc::clone(&asked);
+        Self::show(notice, move |handle| {
+            {
+                let mut asked = shared.lock().unwrap_or_else(PoisonError::into_inner);
+                if asked.done {
+                    drop(asked);
+                    handle.close();
+                    return;
+                }
+                asked.id = Some(handle.id());
+            }
+            handle.wait_for_action(|action| {
+                let _ = answer.send(action.to_string());
+            });
+            shared.lock().unwrap_or_else(PoisonError::into_inner).done = true;
+        })?;
+        self.asked = Some(asked);
+        Ok(answers)
+    }
+
+    fn withdraw(&mut self) {
+        let Some(asked) = self.asked.take() else {
+            return;
+        };
+        let mut asked = asked.lock().unwrap_or_else(PoisonError::into_inner);
+        match asked.id {
+            Some(id) if !asked.done => Self::close(id),
+            _ => asked.done = true,
+        }
+    }
+}
+
+/// Sends kill notifications with rate limiting, so a kill storm becomes one
+/// notification per `min_interval_secs` plus a count of what was skipped.
+pub struct KillNotifier {
+    sink: Box<dyn NotificationSink>,
+    last_sent: Option<Instant>,
+    suppressed: u32,
+    /// A SIGTERM warning went out; its kill notification skips the rate limit.
+    follow_up: bool,
+    /// The freeze notification is out and may still be shown.
+    asking: bool,
+}
+
+impl Default for KillNotifier {
+    fn default() -> Self {
+        Self::new(Box::new(DesktopSink::default()))
+    }
+}
+
+impl KillNotifier {
+    pub fn new(sink: Box<dyn NotificationSink>) -> Self {
+        Self {
+            sink,
+            last_sent: None,
+            suppressed: 0,
+            follow_up: false,
+            asking: false,
+        }
+    }
+
+    /// Warns, once SIGTERM has been sent, that `victim` will be killed after `grace`
+    /// (if enabled).
+    ///
+    /// # Arguments
+    /// * `victim` - Process or app unit name
+    /// * `detail` - Identity line, e.g. `PID 1234, RSS 3.2 GiB`
+    /// * `memory_state` - The reading that triggered the kill
+    pub fn on_sigterm(
+        &mut self,
+        config: &NotifyConfig,
+        victim: &str,
+        detail: &str,
+        grace: Duration,
+        memory_state: &str,
+    ) {
+        if !config.on_sigterm {
+            return;
+        }
+        let notice = Notice {
+            summary: format!("Memory low: stopping {victim}"),
+            body: format!(
+                "{detail}\nIt will be killed in {:.1}s unless it exits.\n{memory_state}",
+                grace.as_secs_f64()
+            ),
+            critical: false,
+            actions: Vec::new(),
+        };
+        self.follow_up = self.send(config, notice, false);
+    }
+
+    /// Reports that `victim` was stopped.
+    ///
+    /// # Arguments
+    /// * `victim` - Process or app unit name
+    /// * `detail` - Identity line, e.g. `PID 1234, RSS 3.2 GiB`
+    /// * `outcome` - How it ended (`killed`, `terminated`, ...)
+    /// * `memory_state` - The reading that triggered the kill
+    pub fn killed(
+        &mut self,
+        config: &NotifyConfig,
+        victim: &str,
+        detail: &str,
+        outcome: &str,
+        memory_state: &str,
+    ) {
+        let notice = Notice {
+            summary: format!("oomkiller stopped {victim}"),
+            body: format!("{detail} ({outcome})\n{memory_state}"),
+            critical: true,
+            actions: Vec::new(),
+        };
+        let follow_up = std::mem::take(&mut self.follow_up);
+        self.send(config, notice, follow_up);
+    }
+
+    /// Reports that `target` keeps coming back after kills and how the daemon backs off.
+    ///
+    /// Always critical and never rate-limited, since it follows a kill notification.
+    ///
+    /// # Arguments
+    /// * `target` - Unit, executable or process name the kills were counted against
+    /// * `kills` - Kills within `kill_loop.window_secs`
+    /// * `response` - What was done, e.g. `stopped unit sync.service`
+    pub fn kill_loop(&mut self, config: &NotifyConfig, target: &str, kills: usize, response: &str) {
+        let notice = Notice {
+            summary: format!("oomkiller: {target} keeps restarting"),
+            body: format!("Killed {kills} times in a row under memory pressure; {response}."),
+            critical: true,
+            actions: Vec::new(),
+        };
+        self.send(config, notice, true);
+    }
+
+    /// Warns that `victim` keeps growing (see `[leak]`), and when it will be killed
+    /// if `kill_in` is set.
+    ///
+    /// # Arguments
+    /// * `victim` - Process name
+    /// * `detail` - Identity and growth, e.g. `PID 1234, growing 80 MiB/min to 3.1 GiB`
+    /// * `kill_in` - Time until it is killed unless it stops growing
+    pub fn leaking(
+        &mut self,
+        config: &NotifyConfig,
+        victim: &str,
+        detail: &str,
+        kill_in: Option<Duration>,
+    ) {
+        let notice = Notice {
+            summary: format!("{victim} keeps using more memory"),
+            body: match kill_in {
+                Some(left) => format!(
+                    "{detail}\nIt will be killed in {:.0}s unless it stops growing.",
+                    left.as_secs_f64()
+                ),
+                None => format!("{detail}\nIt is preferred as the victim if memory runs low."),
+            },
+            critical: false,
+            actions: Vec::new(),
+        };
+        self.send(config, notice, false);
+    }
+
+    /// Asks whether the frozen `victim` should be killed or resumed.
+    ///
+    /// Never rate-limited, since the answer decides what happens to it.
+    ///
+    /// # Arguments
+    /// * `victim` - Process or app unit name
+    /// * `detail` - Identity line, e.g. `PID 1234, RSS 3.2 GiB`
+    /// * `left` - Time until it is killed without an answer
+    /// * `memory_state` - The reading that triggered the freeze
+    ///
+    /// # Returns
+    /// The channel the pressed button's action id arrives on (see [`Answer`](crate::freeze::Answer)),
+    /// or `None` when notifications are disabled or could not be sent.
+    pub fn frozen(
+        &mut self,
+        config: &NotifyConfig,
+        victim: &str,
+        detail: &str,
+        left: Duration,
+        memory_state: &str,
+    ) -> Option<Receiver<String>> {
+        if !config.enabled {
+            return None;
+        }
+        let notice = Notice {
+            summary: format!("Memory low: froze {victim}"),
+            body: format!(
+                "{detail}\nIt is paused, not killed. Without an answer it will be killed in {:.0}s.\n{memory_state}",
+                left.as_secs_f64()
+            ),
+            critical: true,
+            actions: crate::freeze::Answer::ACTIONS.to_vec(),
+        };
+        match self.sink.ask(&notice) {
+            Ok(answers) => {
+                self.last_sent = Some(Instant::now());
+                self.asking = true;
+                Some(answers)
+            }
+            Err(e) => {
+                eprintln!("Failed to send desktop notification: {e}");
+                None
+            }
+        }
+    }
+
+    /// Closes the notification sent by [`frozen`](Self::frozen) once the victim is
+    /// killed, resumed or gone, so its buttons (and the thread waiting for them) do
+    /// not outlive the freeze.
+    pub fn close_frozen(&mut self) {
+        if std::mem::take(&mut self.asking) {
+            self.sink.withdraw();
+        }
+    }
+
+    /// Sends `notice` unless disabled or rate-limited; returns whether it went out.
+    fn send(&mut self, config: &NotifyConfig, mut notice: Notice, bypass_limit: bool) -> bool {
+        if !config.enabled {
+            return false;
+        }
+        let now = Instant::now();
+        let limited = self
+            .last_sent
+            .is_some_and(|last| now.duration_since(last) < config.min_interval());
+        if limited && !bypass_limit {
+            self.suppressed += 1;
+            return false;
+        }
+        if self.suppressed > 0 {
+            notice.body.push_str(&format!(
+                "\n({} earlier notification(s) suppressed; see the journal)",
+                self.suppressed
+            ));
+        }
+        match self.sink.send(&notice) {
+            Ok(()) => {
+                self.last_sent = Some(now);
+                self.suppressed = 0;
+                true
+            }
+            Err(e) => {
+                eprintln!("Failed to send desktop notification: {e}");
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sent notices, and how often a question was withdrawn.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<Notice>>>, Arc<Mutex<usize>>);
+
+    impl NotificationSink for Recorder {
+        fn send(&mut self, notice: &Notice) -> Result<(), String> {
+            self.0.lock().unwrap().push(notice.clone());
+            Ok(())
+        }
+
+        fn ask(&mut self, notice: &Notice) -> Result<Receiver<String>, String> {
+            self.send(notice)?;
+            Ok(mpsc::channel().1)
+        }
+
+        fn withdraw(&mut self) {
+            *self.1.lock().unwrap() += 1;
+        }
+    }
+
+    fn notifier() -> (KillNotifier, Recorder) {
+        let recorder = Recorder::default();
+        (KillNotifier::new(Box::new(recorder.clone())), recorder)
+    }
+
+    #[test]
+    fn test_killed_notice_contents() {
+        let (mut notifier, recorder) = notifier();
+        notifier.killed(
+            &NotifyConfig::default(),
+            "firefox",
+            "PID 42, RSS 3.0 GiB",
+            "killed after=3.00s",
+            "memory 15.0 GiB / 16.0 GiB (93.8%)",
+        );
+        let sent = recorder.0.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].summary, "oomkiller stopped firefox");
+        assert!(
+            sent[0]
+                .body
+                .contains("PID 42, RSS 3.0 GiB (killed after=3.00s)")
+        );
+        assert!(sent[0].body.contains("93.8%"));
+        assert!(sent[0].critical);
+    }
+
+    #[test]
+    fn test_kill_storm_is_rate_limited() {
+        let (mut notifier, recorder) = notifier();
+        let config = NotifyConfig::default();
+        for i in 0..5 {
+            notifier.killed(&config, &format!("p{i}"), "", "killed", "");
+        }
+        assert_eq!(recorder.0.lock().unwrap().len(), 1);
+        assert_eq!(notifier.suppressed, 4);
+
+        let config = NotifyConfig {
+            min_interval_secs: 0.0,
+            ..config
+        };
+        notifier.killed(&config, "p5", "", "killed", "");
+        let sent = recorder.0.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert!(
+            sent[1]
+                .body
+                .contains("4 earlier notification(s) suppressed")
+        );
+    }
+
+    #[test]
+    fn test_warning_and_its_kill_count_as_one_event() {
+        let (mut notifier, recorder) = notifier();
+        let config = NotifyConfig {
+            on_sigterm: true,
+            ..NotifyConfig::default()
+        };
+        notifier.on_sigterm(&config, "rustc", "PID 7", Duration::from_secs(3), "");
+        notifier.killed(&config, "rustc", "PID 7", "killed", "");
+        // The next, unrelated kill is rate-limited again.
+        notifier.killed(&config, "node", "PID 8", "killed", "");
+        let sent = recorder.0.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert!(!sent[0].critical);
+        assert!(sent[0].body.contains("killed in 3.0s"));
+        assert_eq!(sent[1].summary, "oomkiller stopped rustc");
+    }
+
+    #[test]
+    fn test_frozen_notice_has_buttons_and_skips_rate_limit() {
+        let (mut notifier, recorder) = notifier();
+        let config = NotifyConfig::default();
+        notifier.killed(&config, "a", "", "killed", "");
+        let answers = notifier.frozen(&config, "gimp", "PID 9", Duration::from_secs(120), "");
+        assert!(answers.is_some());
+        let sent = recorder.0.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[1].summary, "Memory low: froze gimp");
+        assert!(sent[1].body.contains("killed in 120s"));
+        assert_eq!(sent[1].actions, [("kill", "Kill"), ("resume", "Resume")]);
+    }
+
+    #[test]
+    fn test_frozen_notice_is_closed_once() {
+        let (mut notifier, recorder) = notifier();
+        let config = NotifyConfig::default();
+        notifier.close_frozen();
+        assert_eq!(*recorder.1.lock().unwrap(), 0);
+        notifier.frozen(&config, "gimp", "PID 9", Duration::from_secs(120), "");
+        notifier.close_frozen();
+        notifier.close_frozen();
+        assert_eq!(*recorder.1.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_disabled_sends_nothing() {
+        let (mut notifier, recorder) = notifier();
+        let config = NotifyConfig {
+            enabled: false,
+            on_sigterm: true,
+            ..NotifyConfig::default()
+        };
+        notifier.on_sigterm(&config, "x", "", Duration::ZERO, "");
+        notifier.killed(&config, "x", "", "killed", "");
+        assert!(recorder.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_notify_config_validation() {
+        assert!(NotifyConfig::default().validate().is_ok());
+        let negative = NotifyConfig {
+            min_interval_secs: -1.0,
+            ..NotifyConfig::default()
+        };
+        assert!(negative.validate().is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use crate::procfs::read_start_time;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+/// A process file descriptor: a stable handle to one process instance.
+///
+/// Signals sent through it can never reach a different process that later
+/// reuses the same PID, and sending them needs no fork/exec.
+#[derive(Debug)]
+pub struct PidFd {
+    pid: u32,
+    fd: OwnedFd,
+}
+
+impl PidFd {
+    /// Opens a pidfd for `pid` (`pidfd_open(2)`, Linux 5.3+).
+    pub fn open(pid: u32) -> io::Result<Self> {
+        let raw_pid =
+            libc::pid_t::try_from(pid).map_err(|_| io::Error::from_raw_os_error(libc::ESRCH))?;
+        // SAFETY: pidfd_open takes (pid_t, unsigned int) and returns a new fd or -1.
+        let rc = unsafe { libc::syscall(libc::SYS_pidfd_open, raw_pid, 0) };
+        if rc < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: on success the kernel returned a fresh fd that nothing else owns.
+        let fd = unsafe { OwnedFd::from_raw_fd(rc as i32) };
+        Ok(Self { pid, fd })
+    }
+
+    /// Opens a pidfd and checks that it still refers to the process that was
+    /// observed with `start_time` (clock ticks since boot, `/proc/<pid>/stat` field 22).
+    ///
+    /// The start time is read after the pidfd is open, so a match proves the
+    /// handle pins the original process rather than a PID-reusing newcomer.
+    pub fn open_verified(pid: u32, start_time: u64) -> Result<Self, String> {
+        let pidfd = Self::open(pid).map_err(|e| e.to_string())?;
+        let now = read_start_time(pid)?;
+        if now != start_time {
+            return Err(format!(
+                "PID {pid} was reused (start time {start_time} -> {now}); not signalling"
+            ));
+        }
+        Ok(pidfd)
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Sends `signal` through the pidfd (`pidfd_send_signal(2)`).
+    pub fn send_signal(&self, signal: i32) -> io::Result<()> {
+        // SAFETY: fd is a valid pidfd; a null siginfo and zero flags are allowed.
+        let rc = unsafe {
+            libc::syscall(
+                libc::SYS_pidfd_send_signal,
+                self.fd.as_raw_fd(),
+                signal,
+                std::ptr::null::<libc::siginfo_t>(),
+                0,
+            )
+        };
+        if rc < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Waits up to `timeout` for the process to exit (the pidfd turns readable).
+    ///
+    /// # Returns
+    /// * `Ok(true)` once the process has exited
+    /// * `Ok(false)` on timeout or signal interruption
+    pub fn wait_exit(&self, timeout: std::time::Duration) -> io::Result<bool> {
+        let mut pfd = libc::pollfd {
+            fd: self.fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout_ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+        // SAFETY: `pfd` is a valid pollfd for the duration of the call and nfds is 1.
+        let rc = unsafe { libc::poll(&mut pfd, 1, timeout_ms) };
+        if rc < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                return Ok(false);
+            }
+            return Err(err);
+        }
+        Ok(rc > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_verified_accepts_own_process() {
+        let pid = std::process::id();
+        let start = read_start_time(pid).unwrap();
+        let pidfd = PidFd::open_verified(pid, start).unwrap();
+        assert_eq!(pidfd.pid(), pid);
+        // Signal 0 only checks that the process can be signalled.
+        assert!(pidfd.send_signal(0).is_ok());
+    }
+
+    #[test]
+    fn test_open_verified_rejects_changed_start_time() {
+        let pid = std::process::id();
+        let start = read_start_time(pid).unwrap();
+        let err = PidFd::open_verified(pid, start + 1).unwrap_err();
+        assert!(err.contains("reused"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn test_wait_exit_times_out_for_live_process() {
+        let pidfd = PidFd::open(std::process::id()).unwrap();
+        assert!(
+            !pidfd
+                .wait_exit(std::time::Duration::from_millis(1))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_open_missing_pid_fails() {
+        assert!(PidFd::open(999_999_999).is_err());
+    }
+}
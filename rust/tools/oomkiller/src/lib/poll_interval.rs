@@ -0,0 +1,181 @@
+use crate::config::Config;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+/// Headroom below the threshold, in percentage points, at which the interval reaches `max_secs`.
+const FAR_POINTS: f64 = 40.0;
+
+/// Checks wanted before memory reaches the threshold at the current rate.
+const CHECKS_BEFORE_THRESHOLD: f64 = 4.0;
+
+/// `[poll]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PollConfig {
+    /// Derive the sleep between checks from headroom and trend; off means `interval_secs`.
+    pub adaptive: bool,
+    /// Shortest sleep, used at or right below the threshold.
+    pub min_secs: f64,
+    /// Longest sleep, used when memory is far below the threshold and not rising.
+    pub max_secs: f64,
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        Self {
+            adaptive: true,
+            min_secs: 0.1,
+            max_secs: 10.0,
+        }
+    }
+}
+
+impl PollConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.min_secs >= 0.01 && self.min_secs <= self.max_secs && self.max_secs <= 3600.0) {
+            return Err(format!(
+                "poll.min_secs and poll.max_secs must satisfy 0.01 <= min_secs <= max_secs <= 3600, got {} and {}",
+                self.min_secs, self.max_secs
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The sleep for `headroom` percentage points below the threshold, with memory
+/// rising by `rate` points per second.
+///
+/// The shorter of two intervals, clamped to `[min_secs, max_secs]`: one scaled
+/// linearly with headroom (`max_secs` from 40 points down), and a quarter of the
+/// time the threshold would be reached at `rate`.
+pub fn next_interval(headroom: f64, rate: f64, config: &PollConfig) -> Duration {
+    let by_headroom = config.max_secs * (headroom / FAR_POINTS).clamp(0.0, 1.0);
+    let by_trend = if rate > 0.0 {
+        headroom.max(0.0) / rate / CHECKS_BEFORE_THRESHOLD
+    } else {
+        f64::INFINITY
+    };
+    Duration::from_secs_f64(
+        by_headroom
+            .min(by_trend)
+            .clamp(config.min_secs, config.max_secs),
+    )
+}
+
+/// The daemon's sleep between checks, updated from every memory reading.
+#[derive(Debug, Default)]
+pub struct AdaptiveInterval {
+    last: Option<(Instant, f64)>,
+    /// Memory growth in percentage points per second; follows increases at once and
+    /// decreases gradually, so a burst keeps the interval short for a while.
+    rate: f64,
+    interval: Duration,
+    logged: Option<Duration>,
+}
+
+impl AdaptiveInterval {
+    /// Takes the reading `used_percent` at `at` and returns the sleep until the next
+    /// check: `interval_secs`, or with `poll.adaptive`, [`next_interval`].
+    pub fn observe(&mut self, at: Instant, used_percent: f64, config: &Config) -> Duration {
+        if let Some((then, before)) = self.last {
+            let secs = at.duration_since(then).as_secs_f64();
+            if secs > 0.0 {
+                let rate = (used_percent - before) / secs;
+                self.rate = if rate > self.rate {
+                    rate
+                } else {
+                    (self.rate + rate) / 2.0
+                };
+            }
+        }
+        self.last = Some((at, used_percent));
+        self.interval = if config.poll.adaptive {
+            next_interval(
+                config.threshold_percent - used_percent,
+                self.rate,
+                &config.poll,
+            )
+        } else {
+            config.interval()
+        };
+        self.interval
+    }
+
+    /// The sleep chosen by the last [`observe`](Self::observe).
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Memory growth in percentage points per second.
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// True on the first call and whenever the interval more than halved or doubled since
+    /// the last time it returned true, so the log is not flooded.
+    pub fn should_log(&mut self) -> bool {
+        let changed = self.logged.is_none_or(|logged| {
+            let ratio = self.interval.as_secs_f64() / logged.as_secs_f64().max(f64::EPSILON);
+            !(0.5..=2.0).contains(&ratio)
+        });
+        if changed {
+            self.logged = Some(self.interval);
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(d: Duration) -> f64 {
+        (d.as_secs_f64() * 1000.0).round() / 1000.0
+    }
+
+    #[test]
+    fn test_next_interval_from_headroom_and_trend() {
+        let config = PollConfig::default();
+        // Idle far below the threshold.
+        assert_eq!(secs(next_interval(70.0, 0.0, &config)), 10.0);
+        // 10 points left, steady.
+        assert_eq!(secs(next_interval(10.0, 0.0, &config)), 2.5);
+        // 40 points left, but rising 5 points/s: 8s to go, checked 4 times.
+        assert_eq!(secs(next_interval(40.0, 5.0, &config)), 2.0);
+        // At or past the threshold.
+        assert_eq!(secs(next_interval(0.1, 0.0, &config)), 0.1);
+        assert_eq!(secs(next_interval(-3.0, 1.0, &config)), 0.1);
+    }
+
+    #[test]
+    fn test_observe_tracks_rate() {
+        let mut config = Config::default();
+        let mut poll = AdaptiveInterval::default();
+        let start = Instant::now();
+        assert_eq!(secs(poll.observe(start, 50.0, &config)), 10.0);
+        assert!(poll.should_log());
+        // +10 points in one second: 30 left at 10/s.
+        let interval = poll.observe(start + Duration::from_secs(1), 60.0, &config);
+        assert_eq!(secs(interval), 0.75);
+        assert_eq!(poll.rate(), 10.0);
+        assert!(poll.should_log());
+        // Flat again: the rate decays instead of dropping to zero.
+        poll.observe(start + Duration::from_secs(2), 60.0, &config);
+        assert_eq!(poll.rate(), 5.0);
+        assert!(!poll.should_log());
+
+        config.poll.adaptive = false;
+        let interval = poll.observe(start + Duration::from_secs(3), 60.0, &config);
+        assert_eq!(interval, config.interval());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(PollConfig::default().validate().is_ok());
+        let config = PollConfig {
+            min_secs: 20.0,
+            ..PollConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,312 @@
+use crate::{
+    cgroup::{AppGroup, is_delegated, read_cgroup_path, read_memory_current},
+    config::Target,
+    effective_memory::meminfo_field,
+    freeze::FrozenTarget,
+    host::ProcessTable,
+    procfs::{read_children, read_exe, read_process},
+    smaps::{SmapsRollup, read_smaps_rollup},
+    types::ProcessInfo,
+};
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::PathBuf;
+
+/// Process table read straight from `/proc`, for use under memory pressure.
+///
+/// Unlike sysinfo's `refresh_all` it touches nothing but `/proc/meminfo` and, per
+/// process, `status` (owner, name, swap), `stat` (start time, RSS) and `cmdline`,
+/// plus the two `oom_score*` files the badness score needs. Other users' processes
+/// are dropped after `status`, ownership comes from its `Uid:` line rather than a
+/// `stat()` of the directory, and all reads go through buffers that are kept
+/// between scans, so a rescan allocates little beyond the names and command lines.
+pub struct ProcScanner {
+    uid: u32,
+    page_size: u64,
+    total_memory: u64,
+    /// Scan results; entries past `len` keep their string buffers for the next scan.
+    processes: Vec<ProcessInfo>,
+    len: usize,
+    buf: Vec<u8>,
+    path: String,
+}
+
+impl Default for ProcScanner {
+    fn default() -> Self {
+        // SAFETY: geteuid and sysconf have no preconditions.
+        let (uid, page_size) = unsafe { (libc::geteuid(), libc::sysconf(libc::_SC_PAGESIZE)) };
+        Self {
+            uid,
+            page_size: u64::try_from(page_size).unwrap_or(4096),
+            total_memory: 0,
+            processes: Vec::with_capacity(1024),
+            len: 0,
+            buf: Vec::with_capacity(4096),
+            path: String::with_capacity(32),
+        }
+    }
+}
+
+impl ProcScanner {
+    /// Fills the next slot with `pid`, or returns false if it is not a candidate.
+    fn scan_process(&mut self, pid: u32, target: Target) -> bool {
+        let Self {
+            uid,
+            page_size,
+            processes,
+            len,
+            buf,
+            path,
+            ..
+        } = self;
+        if !read_proc(path, buf, pid, "status") {
+            return false;
+        }
+        let Some(status) = parse_status(buf) else {
+            return false;
+        };
+        if target == Target::User && status.uid != *uid {
+            return false;
+        }
+        // The slot only counts once `len` is bumped, so bailing out below just reuses it.
+        if *len == processes.len() {
+            processes.push(ProcessInfo::default());
+        }
+        let entry = &mut processes[*len];
+        entry.pid = pid;
+        entry.name.clear();
+        entry.name.push_str(status.name);
+        entry.swap = status.swap;
+
+        if !read_proc(path, buf, pid, "stat") {
+            return false;
+        }
+        let Some((start_time, rss_pages)) = parse_stat(buf) else {
+            return false;
+        };
+        entry.start_time = start_time;
+        entry.memory = rss_pages * *page_size;
+
+        // Kernel threads and zombies have an empty cmdline and nothing to free.
+        if !read_proc(path, buf, pid, "cmdline") || buf.is_empty() {
+            return false;
+        }
+        entry.cmdline.clear();
+        let args = buf.strip_suffix(b"\0").unwrap_or(buf);
+        for (i, arg) in args.split(|&b| b == 0).enumerate() {
+            if i > 0 {
+                entry.cmdline.push(' ');
+            }
+            entry.cmdline.push_str(&String::from_utf8_lossy(arg));
+        }
+
+        entry.oom_score = read_i32(path, buf, pid, "oom_score").unwrap_or(0);
+        entry.oom_score_adj = read_i32(path, buf, pid, "oom_score_adj").unwrap_or(0);
+        *len += 1;
+        true
+    }
+}
+
+/// Reads `/proc/<pid>/<file>` into `buf`, building the path in `path`; both keep their capacity.
+fn read_proc(path: &mut String, buf: &mut Vec<u8>, pid: u32, file: &str) -> bool {
+    path.clear();
+    let _ = write!(path, "/proc/{pid}/{file}");
+    buf.clear();
+    File::open(path.as_str())
+        .and_then(|mut f| f.read_to_end(buf))
+        .is_ok()
+}
+
+fn read_i32(path: &mut String, buf: &mut Vec<u8>, pid: u32, file: &str) -> Option<i32> {
+    if !read_proc(path, buf, pid, file) {
+        return None;
+    }
+    std::str::from_utf8(buf).ok()?.trim().parse().ok()
+}
+
+impl ProcessTable for ProcScanner {
+    /// With `Target::All`, PID 1 and the daemon itself are skipped, as in `get_candidate_processes`.
+    fn refresh(&mut self, target: Target) {
+        self.buf.clear();
+        if File::open("/proc/meminfo")
+            .and_then(|mut f| f.read_to_end(&mut self.buf))
+            .is_ok()
+            && let Ok(meminfo) = std::str::from_utf8(&self.buf)
+            && let Some(total) = meminfo_field(meminfo, "MemTotal")
+        {
+            self.total_memory = total;
+        }
+
+        self.len = 0;
+        let Ok(entries) = fs::read_dir("/proc") else {
+            return;
+        };
+        let own_pid = std::process::id();
+        for entry in entries.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+                continue;
+            };
+            if target == Target::All && (pid == 1 || pid == own_pid) {
+                continue;
+            }
+            self.scan_process(pid, target);
+        }
+    }
+
+    fn total_memory(&self) -> u64 {
+        self.total_memory
+    }
+
+    /// The processes found by the last `refresh`, which should have used the same target.
+    fn candidates(&self, _target: Target) -> Result<Vec<ProcessInfo>, String> {
+        Ok(self.processes[..self.len].to_vec())
+    }
+
+    fn cgroup_path(&self, pid: u32) -> Option<String> {
+        read_cgroup_path(pid).ok()
+    }
+
+    fn executable(&self, pid: u32) -> Option<PathBuf> {
+        read_exe(pid)
+    }
+
+    fn smaps_rollup(&self, pid: u32) -> Option<SmapsRollup> {
+        read_smaps_rollup(pid)
+    }
+
+    fn unit_memory(&self, group: &AppGroup) -> Option<u64> {
+        read_memory_current(&group.dir()).ok()
+    }
+
+    fn is_delegated(&self, group: &AppGroup) -> bool {
+        is_delegated(&group.dir())
+    }
+
+    fn is_alive(&self, target: &FrozenTarget) -> bool {
+        target.is_alive()
+    }
+
+    fn children(&self, pid: u32) -> Vec<ProcessInfo> {
+        read_children(pid)
+            .into_iter()
+            .filter_map(read_process)
+            .collect()
+    }
+}
+
+/// The `/proc/<pid>/status` fields the scanner needs.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Status<'a> {
+    pub name: &'a str,
+    /// Effective UID, the owner `/proc/<pid>` itself reports.
+    pub uid: u32,
+    /// `VmSwap` in bytes; 0 for kernel threads, which have no such line.
+    pub swap: u64,
+}
+
+/// Parses `Name:`, `Uid:` and `VmSwap:` from `/proc/<pid>/status`.
+pub fn parse_status(status: &[u8]) -> Option<Status<'_>> {
+    let text = std::str::from_utf8(status).ok()?;
+    let mut name = None;
+    let mut uid = None;
+    let mut swap = 0;
+    for line in text.lines() {
+        if let Some(value) = line.strip_prefix("Name:") {
+            name = Some(value.trim());
+        } else if let Some(value) = line.strip_prefix("Uid:") {
+            // Real, effective, saved, filesystem.
+            uid = value.split_whitespace().nth(1)?.parse().ok();
+        } else if let Some(value) = line.strip_prefix("VmSwap:") {
+            swap = value.split_whitespace().next()?.parse::<u64>().ok()? * 1024;
+            break;
+        }
+    }
+    Some(Status {
+        name: name?,
+        uid: uid?,
+        swap,
+    })
+}
+
+/// Extracts field 22 (starttime) and field 24 (rss, in pages) from `/proc/<pid>/stat`.
+///
+/// Fields are counted from the last `)`, since the command name may contain spaces
+/// and parentheses.
+pub fn parse_stat(stat: &[u8]) -> Option<(u64, u64)> {
+    let after_comm = &stat[stat.iter().rposition(|&b| b == b')')? + 1..];
+    let mut fields = std::str::from_utf8(after_comm).ok()?.split_whitespace();
+    // Fields after comm start at 3 (state).
+    let start_time = fields.nth(22 - 3)?.parse().ok()?;
+    let rss = fields.nth(24 - 22 - 1)?.parse().ok()?;
+    Some((start_time, rss))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat_with_spaces_in_comm() {
+        let stat = b"1234 (Web Content (x)) S 1 1234 1234 0 -1 4194560 100 0 0 0 5 6 0 0 20 0 30 0 987654 123456 789 18446744073709551615";
+        assert_eq!(parse_stat(stat), Some((987654, 789)));
+        assert_eq!(parse_stat(b"1 (init) S 0"), None);
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let status = b"Name:\tWeb Content\nUmask:\t0022\nState:\tS (sleeping)\nUid:\t1000\t1001\t1000\t1000\nGid:\t100\t100\t100\t100\nVmRSS:\t  204800 kB\nVmSwap:\t    1024 kB\nThreads:\t30\n";
+        assert_eq!(
+            parse_status(status),
+            Some(Status {
+                name: "Web Content",
+                uid: 1001,
+                swap: 1024 * 1024,
+            })
+        );
+        let kthread = b"Name:\tkworker/0:1\nUid:\t0\t0\t0\t0\n";
+        assert_eq!(parse_status(kthread).unwrap().swap, 0);
+        assert_eq!(parse_status(b"Umask:\t0022\n"), None);
+    }
+
+    #[test]
+    fn test_scanner_lists_own_child() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let mut scanner = ProcScanner::default();
+        scanner.refresh(Target::User);
+        let processes = scanner.candidates(Target::User).unwrap();
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        let found = processes
+            .iter()
+            .find(|p| p.pid == child.id())
+            .expect("child listed");
+        assert_eq!(found.name, "sleep");
+        assert_eq!(found.cmdline, "sleep 30");
+        assert!(found.memory > 0);
+        assert!(found.start_time > 0);
+        assert!(scanner.total_memory() > 0);
+    }
+
+    #[test]
+    fn test_scanner_all_skips_init_and_self() {
+        let mut scanner = ProcScanner::default();
+        scanner.refresh(Target::All);
+        let scanned = scanner.candidates(Target::All).unwrap();
+        let own_pid = std::process::id();
+        assert!(scanned.iter().all(|p| p.pid != 1 && p.pid != own_pid));
+
+        // A rescan reuses the buffers and sees the same long-lived processes.
+        scanner.refresh(Target::All);
+        let rescanned = scanner.candidates(Target::All).unwrap();
+        let parent = std::os::unix::process::parent_id();
+        assert_eq!(
+            scanned.iter().any(|p| p.pid == parent),
+            rescanned.iter().any(|p| p.pid == parent)
+        );
+    }
+}
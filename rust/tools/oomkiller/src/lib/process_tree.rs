@@ -0,0 +1,206 @@
+use crate::{badness::Patterns, host::ProcessTable, smaps::MemoryMetric, types::ProcessInfo};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// `[tree]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TreeConfig {
+    /// Kill the victim's descendants (e.g. `make -j` jobs) along with it.
+    pub enabled: bool,
+    /// Regexes for victims whose tree is killed; empty means every victim.
+    pub apps: Patterns,
+    /// Processes never killed as part of a tree, nor anything below them; a
+    /// victim matching this is killed alone.
+    pub spare: Patterns,
+    /// Upper bound on the descendants killed with one victim.
+    pub max_processes: usize,
+}
+
+impl Default for TreeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            apps: Patterns::default(),
+            spare: Patterns::new(&[
+                "^(gnome-terminal-|konsole|kitty|alacritty|foot|wezterm-gui|xterm|tilix|ptyxis)",
+                "^(tmux: server|screen|SCREEN|sshd|sshd-session|systemd|dbus-daemon)$",
+            ])
+            .expect("default tree.spare patterns are valid"),
+            max_processes: 256,
+        }
+    }
+}
+
+impl TreeConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_processes == 0 || self.max_processes > 10_000 {
+            return Err(format!(
+                "tree.max_processes must be between 1 and 10000, got {}",
+                self.max_processes
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether a victim called `name` is killed together with its descendants.
+    pub fn applies_to(&self, name: &str) -> bool {
+        self.enabled
+            && !self.spare.is_match(name)
+            && (self.apps.is_empty() || self.apps.is_match(name))
+    }
+}
+
+/// The victim's descendants, deepest first, so they can be signalled from the leaves up.
+///
+/// A descendant matching `tree.spare`, or for which `spared` returns true (`never`
+/// rules, `oomkiller ctl protect`), is skipped together with everything below it.
+/// Empty if trees do not apply to the victim.
+///
+/// # Arguments
+/// * `victim` - The chosen process
+/// * `metric` - Unless `rss`, descendants' `smaps_rollup` is read for [`tree_footprint`]
+/// * `spared` - Extra processes to leave alone
+pub fn descendants(
+    processes: &dyn ProcessTable,
+    victim: &ProcessInfo,
+    config: &TreeConfig,
+    metric: MemoryMetric,
+    spared: &dyn Fn(&ProcessInfo) -> bool,
+) -> Vec<ProcessInfo> {
+    let mut tree = Vec::new();
+    if config.applies_to(&victim.name) {
+        let mut seen = HashSet::from([victim.pid]);
+        let mut walk = Walk {
+            processes,
+            config,
+            metric,
+            spared,
+            seen: &mut seen,
+            tree: &mut tree,
+        };
+        walk.visit(victim.pid);
+    }
+    tree
+}
+
+/// Memory the descendants are expected to give back, per `metric`.
+pub fn tree_footprint(tree: &[ProcessInfo], metric: MemoryMetric) -> u64 {
+    tree.iter().map(|p| p.footprint(metric)).sum()
+}
+
+struct Walk<'a> {
+    processes: &'a dyn ProcessTable,
+    config: &'a TreeConfig,
+    metric: MemoryMetric,
+    spared: &'a dyn Fn(&ProcessInfo) -> bool,
+    seen: &'a mut HashSet<u32>,
+    tree: &'a mut Vec<ProcessInfo>,
+}
+
+impl Walk<'_> {
+    /// Adds the subtree below `pid`, children after their own descendants.
+    fn visit(&mut self, pid: u32) {
+        for mut child in self.processes.children(pid) {
+            if self.tree.len() >= self.config.max_processes {
+                return;
+            }
+            if !self.seen.insert(child.pid)
+                || self.config.spare.is_match(&child.name)
+                || (self.spared)(&child)
+            {
+                continue;
+            }
+            self.visit(child.pid);
+            if self.metric != MemoryMetric::Rss {
+                child.smaps = self.processes.smaps_rollup(child.pid);
+            }
+            self.tree.push(child);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_host::{FakeProcesses, GIB};
+
+    /// make -> (cc1plus, sh -> cc1plus), and a terminal under make.
+    fn build() -> FakeProcesses {
+        let mut processes = FakeProcesses::default();
+        processes
+            .add(100, "make", GIB / 100)
+            .add_child(100, 101, "cc1plus", GIB)
+            .add_child(100, 102, "sh", GIB / 100)
+            .add_child(102, 103, "cc1plus", 2 * GIB)
+            .add_child(100, 104, "kitty", GIB / 10)
+            .add_child(104, 105, "bash", GIB / 100);
+        processes
+    }
+
+    fn enabled() -> TreeConfig {
+        TreeConfig {
+            enabled: true,
+            ..TreeConfig::default()
+        }
+    }
+
+    fn pids(tree: &[ProcessInfo]) -> Vec<u32> {
+        tree.iter().map(|p| p.pid).collect()
+    }
+
+    #[test]
+    fn test_descendants_leaves_first_sparing_terminals() {
+        let processes = build();
+        let make = &processes.processes[0].0;
+        let tree = descendants(&processes, make, &enabled(), MemoryMetric::Rss, &|_| false);
+        assert_eq!(pids(&tree), [101, 103, 102]);
+        assert_eq!(
+            tree_footprint(&tree, MemoryMetric::Rss),
+            GIB + 2 * GIB + GIB / 100
+        );
+
+        let tree = descendants(&processes, make, &enabled(), MemoryMetric::Rss, &|p| {
+            p.pid == 102
+        });
+        assert_eq!(pids(&tree), [101]);
+    }
+
+    #[test]
+    fn test_descendants_respect_config() {
+        let processes = build();
+        let make = &processes.processes[0].0;
+        let kitty = &processes.processes[4].0;
+        let none = |_: &ProcessInfo| false;
+        assert!(
+            descendants(
+                &processes,
+                make,
+                &TreeConfig::default(),
+                MemoryMetric::Rss,
+                &none
+            )
+            .is_empty()
+        );
+        assert!(descendants(&processes, kitty, &enabled(), MemoryMetric::Rss, &none).is_empty());
+        let config = TreeConfig {
+            max_processes: 1,
+            ..enabled()
+        };
+        assert_eq!(
+            pids(&descendants(
+                &processes,
+                make,
+                &config,
+                MemoryMetric::Rss,
+                &none
+            )),
+            [101]
+        );
+        let config = TreeConfig {
+            apps: Patterns::new(&["^ninja$"]).unwrap(),
+            ..enabled()
+        };
+        assert!(!config.applies_to("make"));
+    }
+}
@@ -0,0 +1,231 @@
+use crate::effective_memory::meminfo_field;
+use crate::types::ProcessInfo;
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::PathBuf;
+
+/// Reads a process's start time in clock ticks since boot from `/proc/<pid>/stat`.
+///
+/// Uses stack buffers only, since it runs on the kill path; only errors allocate.
+pub fn read_start_time(pid: u32) -> Result<u64, String> {
+    let fail = |e: &dyn std::fmt::Display| format!("Failed to read /proc/{pid}/stat: {e}");
+    let mut path = [0u8; 32];
+    let mut cursor = Cursor::new(&mut path[..]);
+    write!(cursor, "/proc/{pid}/stat").map_err(|e| fail(&e))?;
+    let len = cursor.position() as usize;
+    let path = std::str::from_utf8(&path[..len]).map_err(|e| fail(&e))?;
+
+    // A stat line is a few hundred bytes; 52 fields of at most 20 digits fit in 2 KiB.
+    let mut buf = [0u8; 2048];
+    let mut file = fs::File::open(path).map_err(|e| fail(&e))?;
+    let mut filled = 0;
+    loop {
+        match file.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(fail(&e)),
+        }
+        if filled == buf.len() {
+            break;
+        }
+    }
+    std::str::from_utf8(&buf[..filled])
+        .ok()
+        .and_then(parse_start_time)
+        .ok_or_else(|| format!("Malformed /proc/{pid}/stat"))
+}
+
+/// Extracts field 22 (starttime) from a `/proc/<pid>/stat` line.
+///
+/// The command name (field 2) may contain spaces and parentheses, so fields are
+/// counted from the last `)`.
+pub fn parse_start_time(stat: &str) -> Option<u64> {
+    let after_comm = &stat[stat.rfind(')')? + 1..];
+    // Fields after comm start at 3 (state); starttime is field 22.
+    after_comm.split_whitespace().nth(22 - 3)?.parse().ok()
+}
+
+/// Reads the kernel's current OOM badness (`/proc/<pid>/oom_score`, 0..=2000).
+pub fn read_oom_score(pid: u32) -> Option<i32> {
+    read_i32(pid, "oom_score")
+}
+
+/// Reads the user-space OOM bias (`/proc/<pid>/oom_score_adj`, -1000..=1000).
+pub fn read_oom_score_adj(pid: u32) -> Option<i32> {
+    read_i32(pid, "oom_score_adj")
+}
+
+/// Reads how much of the process is swapped out (`VmSwap` in `/proc/<pid>/status`), in bytes.
+pub fn read_vm_swap(pid: u32) -> Option<u64> {
+    let status = fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    meminfo_field(&status, "VmSwap")
+}
+
+/// Resolves `/proc/<pid>/exe`, the executable a process was started from.
+pub fn read_exe(pid: u32) -> Option<PathBuf> {
+    fs::read_link(format!("/proc/{pid}/exe")).ok()
+}
+
+/// Lists the children of `pid` from `/proc/<pid>/task/*/children`.
+///
+/// Those files need `CONFIG_PROC_CHILDREN`; without them, every process's parent
+/// PID (`/proc/<pid>/stat` field 4) is scanned instead.
+pub fn read_children(pid: u32) -> Vec<u32> {
+    let Ok(tasks) = fs::read_dir(format!("/proc/{pid}/task")) else {
+        return Vec::new();
+    };
+    let mut supported = false;
+    let mut children = Vec::new();
+    for task in tasks.flatten() {
+        if let Ok(text) = fs::read_to_string(task.path().join("children")) {
+            supported = true;
+            children.extend(
+                text.split_whitespace()
+                    .filter_map(|c| c.parse::<u32>().ok()),
+            );
+        }
+    }
+    if !supported {
+        return children_by_ppid(pid);
+    }
+    children.sort_unstable();
+    children.dedup();
+    children
+}
+
+/// Children of `pid` found by reading the parent PID of every process.
+fn children_by_ppid(pid: u32) -> Vec<u32> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|&child| {
+            fs::read_to_string(format!("/proc/{child}/stat"))
+                .ok()
+                .and_then(|stat| parse_ppid(&stat))
+                == Some(pid)
+        })
+        .collect()
+}
+
+/// Extracts field 4 (ppid) from a `/proc/<pid>/stat` line.
+pub fn parse_ppid(stat: &str) -> Option<u32> {
+    let after_comm = &stat[stat.rfind(')')? + 1..];
+    after_comm.split_whitespace().nth(4 - 3)?.parse().ok()
+}
+
+/// Reads what killing `pid` as part of a process tree needs: name, start time,
+/// RSS, swap and command line. `None` if it has exited or is a kernel thread.
+pub fn read_process(pid: u32) -> Option<ProcessInfo> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    let name = &stat[stat.find('(')? + 1..stat.rfind(')')?];
+    let rss_pages: u64 = stat[stat.rfind(')')? + 1..]
+        .split_whitespace()
+        .nth(24 - 3)?
+        .parse()
+        .ok()?;
+    let cmdline = fs::read(format!("/proc/{pid}/cmdline")).ok()?;
+    if cmdline.is_empty() {
+        return None;
+    }
+    // SAFETY: sysconf has no preconditions.
+    let page_size = u64::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) }).unwrap_or(4096);
+    Some(ProcessInfo {
+        pid,
+        memory: rss_pages * page_size,
+        swap: read_vm_swap(pid).unwrap_or(0),
+        name: name.to_string(),
+        cmdline: String::from_utf8_lossy(cmdline.strip_suffix(b"\0").unwrap_or(&cmdline))
+            .replace('\0', " "),
+        start_time: parse_start_time(&stat)?,
+        ..ProcessInfo::default()
+    })
+}
+
+fn read_i32(pid: u32, file: &str) -> Option<i32> {
+    fs::read_to_string(format!("/proc/{pid}/{file}"))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_start_time_with_spaces_in_comm() {
+        let stat = "1234 (Web Content (x)) S 1 1234 1234 0 -1 4194560 100 0 0 0 5 6 0 0 20 0 30 0 987654 123456 789";
+        assert_eq!(parse_start_time(stat), Some(987654));
+    }
+
+    #[test]
+    fn test_parse_start_time_rejects_truncated_line() {
+        assert_eq!(parse_start_time("1 (init) S 0"), None);
+        assert_eq!(parse_start_time("garbage"), None);
+    }
+
+    #[test]
+    fn test_read_start_time_own_process() {
+        assert!(read_start_time(std::process::id()).unwrap() > 0);
+    }
+
+    #[test]
+    fn test_read_oom_scores_own_process() {
+        let pid = std::process::id();
+        let score = read_oom_score(pid).unwrap();
+        assert!((0..=2000).contains(&score));
+        let adj = read_oom_score_adj(pid).unwrap();
+        assert!((-1000..=1000).contains(&adj));
+    }
+
+    #[test]
+    fn test_read_vm_swap_own_process() {
+        // Kernel threads have no VmSwap line, user processes always do.
+        assert!(read_vm_swap(std::process::id()).is_some());
+        assert_eq!(read_vm_swap(999_999_999), None);
+    }
+
+    #[test]
+    fn test_read_exe_own_process() {
+        let exe = read_exe(std::process::id()).unwrap();
+        assert_eq!(exe, std::env::current_exe().unwrap());
+    }
+
+    #[test]
+    fn test_read_children_and_process() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        let children = read_children(std::process::id());
+        let listed = children_by_ppid(std::process::id());
+        let process = read_process(pid);
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        assert!(children.contains(&pid) && listed.contains(&pid));
+        let process = process.unwrap();
+        assert_eq!(process.name, "sleep");
+        assert_eq!(process.cmdline, "sleep 30");
+        assert!(process.start_time > 0);
+        assert!(process.memory > 0);
+    }
+
+    #[test]
+    fn test_parse_ppid() {
+        let stat = "1234 (Web Content (x)) S 987 1234 1234 0 -1";
+        assert_eq!(parse_ppid(stat), Some(987));
+        assert_eq!(parse_ppid("1 (init)"), None);
+    }
+
+    #[test]
+    fn test_read_oom_score_missing_pid() {
+        assert_eq!(read_oom_score(999_999_999), None);
+    }
+}
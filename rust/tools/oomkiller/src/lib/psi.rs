@@ -0,0 +1,307 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::OpenOptionsExt;
+use std::time::Duration;
+
+/// Kernel pressure-stall file for memory (needs CONFIG_PSI).
+pub const MEMORY_PRESSURE_PATH: &str = "/proc/pressure/memory";
+
+/// One `some`/`full` line of a PSI file; averages are percentages, total is microseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PsiLine {
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+    pub total_us: u64,
+}
+
+/// Parsed contents of `/proc/pressure/memory`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PsiSnapshot {
+    pub some: PsiLine,
+    pub full: PsiLine,
+}
+
+impl PsiSnapshot {
+    /// True when either 10-second average is at or above its configured limit.
+    pub fn exceeds(&self, config: &PsiConfig) -> bool {
+        self.some.avg10 >= config.some_avg10 || self.full.avg10 >= config.full_avg10
+    }
+}
+
+/// Short form for log lines, e.g. `psi some=12.3% full=4.0%`; does not allocate.
+impl fmt::Display for PsiSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "psi some={:.1}% full={:.1}%",
+            self.some.avg10, self.full.avg10
+        )
+    }
+}
+
+/// Which PSI line the kernel trigger watches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PsiKind {
+    /// At least one task stalled on memory.
+    #[default]
+    Some,
+    /// All non-idle tasks stalled on memory at once (thrashing).
+    Full,
+}
+
+impl PsiKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Some => "some",
+            Self::Full => "full",
+        }
+    }
+}
+
+/// `[psi]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PsiConfig {
+    /// `some avg10` percentage at or above which pressure counts as exceeded.
+    pub some_avg10: f64,
+    /// `full avg10` percentage at or above which pressure counts as exceeded.
+    pub full_avg10: f64,
+    /// Line watched by the wake-up trigger.
+    pub trigger_kind: PsiKind,
+    /// Stall time within `window_ms` that wakes the daemon early.
+    pub stall_ms: u64,
+    /// Trigger window; unprivileged users need a multiple of 2000 ms.
+    pub window_ms: u64,
+}
+
+impl Default for PsiConfig {
+    fn default() -> Self {
+        Self {
+            some_avg10: 20.0,
+            full_avg10: 5.0,
+            trigger_kind: PsiKind::Some,
+            stall_ms: 150,
+            window_ms: 2000,
+        }
+    }
+}
+
+impl PsiConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        for (key, value) in [
+            ("psi.some_avg10", self.some_avg10),
+            ("psi.full_avg10", self.full_avg10),
+        ] {
+            if !(value > 0.0 && value <= 100.0) {
+                return Err(format!("{key} must be in (0, 100], got {value}"));
+            }
+        }
+        // Kernel limits for trigger windows: 500 ms ..= 10 s.
+        if !(500..=10_000).contains(&self.window_ms) {
+            return Err(format!(
+                "psi.window_ms must be between 500 and 10000, got {}",
+                self.window_ms
+            ));
+        }
+        if self.stall_ms == 0 || self.stall_ms > self.window_ms {
+            return Err(format!(
+                "psi.stall_ms must be between 1 and window_ms ({}), got {}",
+                self.window_ms, self.stall_ms
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Reads and parses `/proc/pressure/memory`.
+///
+/// # Returns
+/// * `Ok(PsiSnapshot)` with both lines parsed
+/// * `Err(String)` if the file is missing (kernel without PSI) or malformed
+pub fn read_memory_pressure() -> Result<PsiSnapshot, String> {
+    let text = std::fs::read_to_string(MEMORY_PRESSURE_PATH)
+        .map_err(|e| format!("Failed to read {MEMORY_PRESSURE_PATH}: {e}"))?;
+    parse_pressure(&text)
+}
+
+/// Parses the text of a PSI file (`some avg10=… avg60=… avg300=… total=…` + `full …`).
+pub fn parse_pressure(text: &str) -> Result<PsiSnapshot, String> {
+    let mut some = None;
+    let mut full = None;
+    for line in text.lines() {
+        let mut fields = line.split_whitespace();
+        let slot = match fields.next() {
+            Some("some") => &mut some,
+            Some("full") => &mut full,
+            _ => continue,
+        };
+        let mut parsed = PsiLine::default();
+        for field in fields {
+            let (key, value) = field
+                .split_once('=')
+                .ok_or_else(|| format!("Malformed PSI field '{field}'"))?;
+            let bad = |e: &dyn std::fmt::Display| format!("Malformed PSI value '{field}': {e}");
+            match key {
+                "avg10" => parsed.avg10 = value.parse().map_err(|e| bad(&e))?,
+                "avg60" => parsed.avg60 = value.parse().map_err(|e| bad(&e))?,
+                "avg300" => parsed.avg300 = value.parse().map_err(|e| bad(&e))?,
+                "total" => parsed.total_us = value.parse().map_err(|e| bad(&e))?,
+                _ => {}
+            }
+        }
+        *slot = Some(parsed);
+    }
+    Ok(PsiSnapshot {
+        some: some.ok_or("PSI data has no 'some' line")?,
+        // Older kernels omit `full` for some resources; treat as no full stall.
+        full: full.unwrap_or_default(),
+    })
+}
+
+/// A kernel PSI trigger: the fd becomes readable (POLLPRI) when the configured
+/// stall time is exceeded within the window, so the daemon can sleep in poll()
+/// and still react within milliseconds.
+pub struct PsiTrigger {
+    file: File,
+}
+
+impl PsiTrigger {
+    /// Registers a trigger on `/proc/pressure/memory`.
+    ///
+    /// # Returns
+    /// * `Err(String)` if PSI is unavailable or the kernel rejects the trigger
+    ///   (e.g. an unprivileged window that is not a multiple of 2 s)
+    pub fn new(config: &PsiConfig) -> Result<Self, String> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(MEMORY_PRESSURE_PATH)
+            .map_err(|e| format!("Failed to open {MEMORY_PRESSURE_PATH}: {e}"))?;
+        let spec = format!(
+            "{} {} {}\0",
+            config.trigger_kind.as_str(),
+            config.stall_ms * 1000,
+            config.window_ms * 1000
+        );
+        file.write_all(spec.as_bytes()).map_err(|e| {
+            format!(
+                "Failed to register PSI trigger '{}': {e}",
+                spec.trim_end_matches('\0')
+            )
+        })?;
+        Ok(Self { file })
+    }
+
+    /// Blocks until the trigger fires or `timeout` passes.
+    ///
+    /// # Returns
+    /// * `Ok(true)` if memory pressure crossed the trigger
+    /// * `Ok(false)` on timeout or when interrupted by a signal
+    /// * `Err(String)` if poll failed or the trigger was torn down
+    pub fn wait(&self, timeout: Duration) -> Result<bool, String> {
+        let mut pfd = libc::pollfd {
+            fd: self.file.as_raw_fd(),
+            events: libc::POLLPRI,
+            revents: 0,
+        };
+        let timeout_ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+        // SAFETY: `pfd` is a valid pollfd for the duration of the call and nfds is 1.
+        let rc = unsafe { libc::poll(&mut pfd, 1, timeout_ms) };
+        if rc < 0 {
+            let err = std::io::Error::last_os_error();
+            if err.kind() == std::io::ErrorKind::Interrupted {
+                return Ok(false);
+            }
+            return Err(format!("poll on PSI trigger failed: {err}"));
+        }
+        if pfd.revents & libc::POLLERR != 0 {
+            return Err("PSI trigger was removed by the kernel".to_string());
+        }
+        Ok(rc > 0 && pfd.revents & libc::POLLPRI != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "some avg10=12.50 avg60=3.10 avg300=0.80 total=123456\n\
+                          full avg10=4.00 avg60=1.00 avg300=0.25 total=6543\n";
+
+    #[test]
+    fn test_parse_pressure_reads_both_lines() {
+        let psi = parse_pressure(SAMPLE).unwrap();
+        assert_eq!(psi.some.avg10, 12.5);
+        assert_eq!(psi.some.avg60, 3.1);
+        assert_eq!(psi.some.total_us, 123456);
+        assert_eq!(psi.full.avg10, 4.0);
+        assert_eq!(psi.full.avg300, 0.25);
+        assert_eq!(psi.full.total_us, 6543);
+    }
+
+    #[test]
+    fn test_parse_pressure_without_full_line() {
+        let psi = parse_pressure("some avg10=1.00 avg60=0.00 avg300=0.00 total=1\n").unwrap();
+        assert_eq!(psi.full, PsiLine::default());
+    }
+
+    #[test]
+    fn test_parse_pressure_rejects_garbage() {
+        assert!(parse_pressure("").is_err());
+        assert!(parse_pressure("some avg10=abc\n").is_err());
+    }
+
+    #[test]
+    fn test_exceeds_uses_either_average() {
+        let psi = parse_pressure(SAMPLE).unwrap();
+        let config = PsiConfig::default();
+        assert!(!psi.exceeds(&config));
+        assert!(psi.exceeds(&PsiConfig {
+            some_avg10: 10.0,
+            ..PsiConfig::default()
+        }));
+        assert!(psi.exceeds(&PsiConfig {
+            full_avg10: 4.0,
+            ..PsiConfig::default()
+        }));
+    }
+
+    #[test]
+    fn test_psi_config_validation() {
+        assert!(PsiConfig::default().validate().is_ok());
+        let short_window = PsiConfig {
+            window_ms: 100,
+            ..PsiConfig::default()
+        };
+        assert!(short_window.validate().is_err());
+        let stall_beyond_window = PsiConfig {
+            stall_ms: 3000,
+            ..PsiConfig::default()
+        };
+        assert!(stall_beyond_window.validate().is_err());
+    }
+
+    #[test]
+    fn test_psi_trigger_wait_times_out_when_calm() {
+        // Trigger registration needs PSI and, unprivileged, a 2 s window; skip otherwise.
+        if let Ok(trigger) = PsiTrigger::new(&PsiConfig::default()) {
+            assert!(trigger.wait(Duration::from_millis(10)).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_read_memory_pressure_when_available() {
+        if std::path::Path::new(MEMORY_PRESSURE_PATH).exists() {
+            let psi = read_memory_pressure().unwrap();
+            assert!(psi.some.avg10 >= 0.0);
+        } else {
+            assert!(read_memory_pressure().is_err());
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::metrics::Metrics;
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+use std::time::Duration;
+
+/// Talks to systemd over `$NOTIFY_SOCKET` when running as a `Type=notify` unit.
+///
+/// The daemon sends `READY=1` after its first memory read, then `WATCHDOG=1` and a
+/// `STATUS=` line from the main loop (and `WATCHDOG=1` alone while waiting out a
+/// kill's grace period), so a wedged loop misses its pings and systemd restarts it.
+pub struct SystemdNotifier {
+    socket: UnixDatagram,
+    addr: SocketAddr,
+    watchdog: Option<Duration>,
+}
+
+impl SystemdNotifier {
+    /// Connects to `$NOTIFY_SOCKET`, or returns `None` when systemd did not set one.
+    ///
+    /// The watchdog is armed from `$WATCHDOG_USEC` unless `$WATCHDOG_PID` names
+    /// another process.
+    pub fn from_env() -> Option<Result<Self, String>> {
+        let path = std::env::var("NOTIFY_SOCKET")
+            .ok()
+            .filter(|p| !p.is_empty())?;
+        let for_us = std::env::var("WATCHDOG_PID")
+            .ok()
+            .and_then(|pid| pid.parse::<u32>().ok())
+            .is_none_or(|pid| pid == std::process::id());
+        let watchdog = std::env::var("WATCHDOG_USEC")
+            .ok()
+            .and_then(|usec| usec.parse().ok())
+            .filter(|&usec| usec > 0 && for_us)
+            .map(Duration::from_micros);
+        Some(Self::connect(&path, watchdog))
+    }
+
+    /// Uses the notification socket at `path` (`@name` for the abstract namespace).
+    ///
+    /// # Arguments
+    /// * `path` - Value of `$NOTIFY_SOCKET`
+    /// * `watchdog` - `WatchdogSec=` of the unit, if any
+    pub fn connect(path: &str, watchdog: Option<Duration>) -> Result<Self, String> {
+        let addr = match path.strip_prefix('@') {
+            #[cfg(target_os = "linux")]
+            Some(name) => {
+                use std::os::linux::net::SocketAddrExt;
+                SocketAddr::from_abstract_name(name)
+            }
+            #[cfg(not(target_os = "linux"))]
+            Some(_) => {
+                return Err(format!(
+                    "abstract NOTIFY_SOCKET {path} is only supported on Linux"
+                ));
+            }
+            None => SocketAddr::from_pathname(path),
+        }
+        .map_err(|e| format!("Invalid NOTIFY_SOCKET {path}: {e}"))?;
+        let socket =
+            UnixDatagram::unbound().map_err(|e| format!("Failed to create notify socket: {e}"))?;
+        Ok(Self {
+            socket,
+            addr,
+            watchdog,
+        })
+    }
+
+    /// How often `WATCHDOG=1` has to be sent: half of `WatchdogSec=`, as systemd recommends.
+    pub fn ping_interval(&self) -> Option<Duration> {
+        self.watchdog.map(|timeout| timeout / 2)
+    }
+
+    /// Reports the daemon as started, with its first status line.
+    pub fn ready(&self, status: &str) -> Result<(), String> {
+        self.send(&format!("READY=1\nSTATUS={status}"))
+    }
+
+    /// Pings the watchdog (if armed) and updates the status line.
+    pub fn alive(&self, status: &str) -> Result<(), String> {
+        if self.watchdog.is_some() {
+            self.send(&format!("WATCHDOG=1\nSTATUS={status}"))
+        } else {
+            self.send(&format!("STATUS={status}"))
+        }
+    }
+
+    /// Pings the watchdog, if armed, without touching the status line.
+    pub fn ping(&self) -> Result<(), String> {
+        if self.watchdog.is_some() {
+            self.send("WATCHDOG=1")
+        } else {
+            Ok(())
+        }
+    }
+
+    fn send(&self, message: &str) -> Result<(), String> {
+        self.socket
+            .send_to_addr(message.as_bytes(), &self.addr)
+            .map(drop)
+            .map_err(|e| format!("Failed to notify systemd: {e}"))
+    }
+}
+
+/// The `STATUS=` line, e.g. `used 72%, polling every 2.5s, last kill firefox 12m ago`.
+///
+/// # Arguments
+/// * `metrics` - Memory reading, poll interval and last kill of the latest iteration
+/// * `now` - Current Unix time in seconds
+pub fn status_line(metrics: &Metrics, now: f64) -> String {
+    let used = format!(
+        "used {:.0}%, polling every {}",
+        metrics.used_percent,
+        format_interval(metrics.poll_interval_secs)
+    );
+    match (&metrics.last_kill_name, metrics.last_kill_timestamp) {
+        (Some(name), Some(at)) => {
+            format!("{used}, last kill {name} {} ago", format_age(now - at))
+        }
+        _ => format!("{used}, no kills yet"),
+    }
+}
+
+/// Poll interval for the status line: `250ms` below a second, else `2.5s`.
+fn format_interval(secs: f64) -> String {
+    if secs < 1.0 {
+        format!("{:.0}ms", secs * 1000.0)
+    } else {
+        format!("{secs:.1}s")
+    }
+}
+
+/// Coarse age for the status line: `45s`, `12m`, `3h`, `2d`.
+fn format_age(secs: f64) -> String {
+    let secs = secs.max(0.0) as u64;
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86_400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86_400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_line() {
+        let mut metrics = Metrics {
+            used_percent: 71.6,
+            poll_interval_secs: 0.25,
+            ..Metrics::default()
+        };
+        assert_eq!(
+            status_line(&metrics, 1000.0),
+            "used 72%, polling every 250ms, no kills yet"
+        );
+        metrics.poll_interval_secs = 10.0;
+        metrics.last_kill_name = Some("firefox".to_string());
+        metrics.last_kill_timestamp = Some(1000.0 - 12.0 * 60.0 - 5.0);
+        assert_eq!(
+            status_line(&metrics, 1000.0),
+            "used 72%, polling every 10.0s, last kill firefox 12m ago"
+        );
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(-1.0), "0s");
+        assert_eq!(format_age(59.9), "59s");
+        assert_eq!(format_age(7200.0), "2h");
+        assert_eq!(format_age(200_000.0), "2d");
+    }
+
+    #[test]
+    fn test_notifier_sends_datagrams() {
+        let path = std::env::temp_dir().join(format!("oomkiller-notify-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let systemd = UnixDatagram::bind(&path).unwrap();
+        let notifier =
+            SystemdNotifier::connect(path.to_str().unwrap(), Some(Duration::from_secs(30)))
+                .unwrap();
+        assert_eq!(notifier.ping_interval(), Some(Duration::from_secs(15)));
+
+        let mut buf = [0; 256];
+        notifier.ready("used 50%, no kills yet").unwrap();
+        let n = systemd.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"READY=1\nSTATUS=used 50%, no kills yet");
+        notifier.alive("used 51%, no kills yet").unwrap();
+        let n = systemd.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"WATCHDOG=1\nSTATUS=used 51%, no kills yet");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{
+    badness::rank_candidates,
+    cgroup::{AppGroup, group_by_app},
+    config::Config,
+    host::ProcessTable,
+};
+
+/// Picks the app unit (systemd `app-*.scope` etc.) charged the most memory.
+///
+/// Browsers and Electron apps spread their memory over many processes, so the
+/// unit's `memory.current` is compared rather than any single RSS. Units that
+/// contain a process protected by a `never` rule, or whose `cgroup.kill` the
+/// daemon cannot write (cgroups not delegated), are skipped.
+///
+/// # Arguments
+/// * `processes` - A process table that has already been refreshed
+/// * `config` - Target selection and scoring rules
+///
+/// Returns `Some(AppGroup)` for the heaviest killable unit, or `None` so the caller
+/// can fall back to per-process selection.
+/// Returns `Err` if process listing fails.
+pub fn select_app(
+    processes: &dyn ProcessTable,
+    config: &Config,
+) -> Result<Option<AppGroup>, String> {
+    let ranked = rank_candidates(
+        processes.candidates(config.target)?,
+        processes.total_memory(),
+        &config.scoring,
+    );
+    let protected: Vec<u32> = ranked
+        .iter()
+        .filter(|c| !c.is_killable())
+        .map(|c| c.process.pid)
+        .collect();
+    let with_cgroups = ranked
+        .into_iter()
+        .filter_map(|c| {
+            let cgroup = processes.cgroup_path(c.process.pid)?;
+            Some((c.process, cgroup))
+        })
+        .collect();
+
+    let mut groups: Vec<AppGroup> = group_by_app(with_cgroups)
+        .into_iter()
+        .filter(|g| g.processes.iter().all(|p| !protected.contains(&p.pid)))
+        .filter(|g| processes.is_delegated(g))
+        .collect();
+    for group in &mut groups {
+        if let Some(memory) = processes.unit_memory(group) {
+            group.memory = memory;
+        }
+    }
+    Ok(groups.into_iter().max_by_key(|g| g.memory))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_host::{FakeProcesses, GIB};
+
+    const FIREFOX: &str =
+        "/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox.scope";
+    const CODE: &str = "/user.slice/user-1000.slice/user@1000.service/app.slice/app-code.scope";
+
+    fn desktop() -> FakeProcesses {
+        let mut processes = FakeProcesses {
+            delegated: true,
+            ..FakeProcesses::default()
+        };
+        processes
+            .add_in(10, "firefox", 2 * GIB, Some(FIREFOX))
+            .add_in(11, "Web Content", 2 * GIB, Some(FIREFOX))
+            .add_in(20, "code", 3 * GIB, Some(CODE))
+            .add(30, "rustc", GIB);
+        processes
+    }
+
+    #[test]
+    fn test_select_app_picks_heaviest_unit() {
+        let group = select_app(&desktop(), &Config::default()).unwrap().unwrap();
+        assert_eq!(group.unit(), "app-firefox.scope");
+        assert_eq!(group.memory, 4 * GIB);
+        assert_eq!(group.processes.len(), 2);
+    }
+
+    #[test]
+    fn test_select_app_skips_unit_with_protected_member() {
+        let mut processes = desktop();
+        processes.add_in(12, "dbus-daemon", GIB / 100, Some(FIREFOX));
+        let group = select_app(&processes, &Config::default()).unwrap().unwrap();
+        assert_eq!(group.unit(), "app-code.scope");
+    }
+
+    #[test]
+    fn test_select_app_none_without_delegation() {
+        let processes = FakeProcesses {
+            delegated: false,
+            ..desktop()
+        };
+        assert!(
+            select_app(&processes, &Config::default())
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{
+    badness::{Candidate, rank_candidates},
+    config::Config,
+    host::ProcessTable,
+    smaps::MemoryMetric,
+};
+
+/// Scores every candidate for `config.target`, highest badness first (`never` matches last).
+///
+/// The table should already have been refreshed. Unless `scoring.memory_metric`
+/// is `rss`, each candidate's `smaps_rollup` is read here, after the cheap scan.
+///
+/// Returns `Err` if process listing fails.
+pub fn ranked_candidates(
+    processes: &dyn ProcessTable,
+    config: &Config,
+) -> Result<Vec<Candidate>, String> {
+    let mut candidates = processes.candidates(config.target)?;
+    if config.scoring.memory_metric != MemoryMetric::Rss {
+        for process in &mut candidates {
+            process.smaps = processes.smaps_rollup(process.pid);
+        }
+    }
+    Ok(rank_candidates(
+        candidates,
+        processes.total_memory(),
+        &config.scoring,
+    ))
+}
+
+/// Picks the process to kill: the highest-badness candidate not protected by a `never` rule.
+///
+/// # Arguments
+/// * `processes` - A process table that has already been refreshed
+/// * `config` - Target selection and scoring rules
+///
+/// Returns `Some(Candidate)` with its score breakdown, or `None` if nothing is killable.
+/// Returns `Err` if process listing fails.
+pub fn select_victim(
+    processes: &dyn ProcessTable,
+    config: &Config,
+) -> Result<Option<Candidate>, String> {
+    Ok(ranked_candidates(processes, config)?
+        .into_iter()
+        .find(Candidate::is_killable))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_host::{FakeProcesses, GIB};
+    use crate::smaps::SmapsRollup;
+
+    #[test]
+    fn test_select_victim_skips_protected_process() {
+        let mut processes = FakeProcesses::default();
+        processes
+            .add(10, "systemd", 8 * GIB)
+            .add(20, "firefox", 3 * GIB)
+            .add(30, "bash", GIB / 10);
+        let victim = select_victim(&processes, &Config::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(victim.process.pid, 20);
+    }
+
+    #[test]
+    fn test_select_victim_by_uss_skips_shared_memory() {
+        // "worker" is mostly shared memory that a kill would not free.
+        let mut processes = FakeProcesses::default();
+        processes
+            .add(10, "worker", 4 * GIB)
+            .add(20, "leaker", 3 * GIB);
+        processes.smaps.insert(
+            10,
+            SmapsRollup {
+                rss: 4 * GIB,
+                pss: GIB,
+                private: GIB / 2,
+                ..SmapsRollup::default()
+            },
+        );
+        processes.smaps.insert(
+            20,
+            SmapsRollup {
+                rss: 3 * GIB,
+                pss: 3 * GIB,
+                private: 3 * GIB,
+                ..SmapsRollup::default()
+            },
+        );
+        let victim = select_victim(&processes, &Config::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(victim.process.pid, 20);
+        assert_eq!(victim.badness.memory, 3 * GIB);
+
+        let mut config = Config::default();
+        config.scoring.memory_metric = MemoryMetric::Rss;
+        let victim = select_victim(&processes, &config).unwrap().unwrap();
+        assert_eq!(victim.process.pid, 10);
+    }
+
+    #[test]
+    fn test_select_victim_none_when_only_protected() {
+        let mut processes = FakeProcesses::default();
+        processes.add(10, "systemd", 8 * GIB);
+        assert!(
+            select_victim(&processes, &Config::default())
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            ranked_candidates(&processes, &Config::default())
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::effective_memory::meminfo_field;
+use serde::Deserialize;
+use std::fs;
+
+/// Which per-process memory figure candidates are scored and reported by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryMetric {
+    /// Resident set size: counts every shared page in full, for every process mapping it.
+    Rss,
+    /// Proportional set size: shared pages divided among the processes mapping them.
+    Pss,
+    /// Unique set size: private pages only, which are what a kill actually frees.
+    #[default]
+    Uss,
+}
+
+impl MemoryMetric {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Rss => "rss",
+            Self::Pss => "pss",
+            Self::Uss => "uss",
+        }
+    }
+}
+
+/// The totals of `/proc/<pid>/smaps_rollup`, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SmapsRollup {
+    pub rss: u64,
+    pub pss: u64,
+    /// `Private_Clean` + `Private_Dirty`.
+    pub private: u64,
+    pub swap: u64,
+    /// Swap divided among sharers like `Pss` (kernels before 4.3 have none; `Swap` is used).
+    pub swap_pss: u64,
+}
+
+impl SmapsRollup {
+    /// PSS plus proportional swap.
+    pub fn pss_footprint(&self) -> u64 {
+        self.pss + self.swap_pss
+    }
+
+    /// Private memory plus swap: what killing the process should give back.
+    pub fn uss_footprint(&self) -> u64 {
+        self.private + self.swap
+    }
+}
+
+/// Reads `/proc/<pid>/smaps_rollup` (Linux 4.14+).
+///
+/// The kernel walks every mapping under the process's mmap lock to produce it, so
+/// it is only read for candidates, once memory is already critical. Returns `None`
+/// if the file is missing or unreadable (another user's process without
+/// `CAP_SYS_PTRACE`, or a process that has exited).
+pub fn read_smaps_rollup(pid: u32) -> Option<SmapsRollup> {
+    let text = fs::read_to_string(format!("/proc/{pid}/smaps_rollup")).ok()?;
+    parse_smaps_rollup(&text)
+}
+
+/// Parses the `Key: N kB` lines of `smaps_rollup`; `None` without an `Rss:` line
+/// (kernel threads have no mappings at all).
+pub fn parse_smaps_rollup(text: &str) -> Option<SmapsRollup> {
+    let field = |key| meminfo_field(text, key).unwrap_or(0);
+    let swap = field("Swap");
+    Some(SmapsRollup {
+        rss: meminfo_field(text, "Rss")?,
+        pss: field("Pss"),
+        private: field("Private_Clean") + field("Private_Dirty"),
+        swap,
+        swap_pss: meminfo_field(text, "SwapPss").unwrap_or(swap),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROLLUP: &str = "\
+55d0c0a4b000-7ffd5b3f9000 ---p 00000000 00:00 0                          [rollup]
+Rss:              204800 kB
+Pss:              102400 kB
+Pss_Anon:          81920 kB
+Pss_File:          20480 kB
+Shared_Clean:     122880 kB
+Shared_Dirty:          0 kB
+Private_Clean:     10240 kB
+Private_Dirty:     71680 kB
+Referenced:       204800 kB
+Anonymous:         81920 kB
+Swap:               2048 kB
+SwapPss:            1024 kB
+Locked:                0 kB
+";
+
+    #[test]
+    fn test_parse_smaps_rollup() {
+        let rollup = parse_smaps_rollup(ROLLUP).unwrap();
+        assert_eq!(
+            rollup,
+            SmapsRollup {
+                rss: 200 << 20,
+                pss: 100 << 20,
+                private: 80 << 20,
+                swap: 2 << 20,
+                swap_pss: 1 << 20,
+            }
+        );
+        assert_eq!(rollup.pss_footprint(), 101 << 20);
+        assert_eq!(rollup.uss_footprint(), 82 << 20);
+        // `Pss_Anon:` must not be taken for `Pss:`.
+        assert_eq!(
+            parse_smaps_rollup("Rss: 8 kB\nPss_Anon: 4 kB\n")
+                .unwrap()
+                .pss,
+            0
+        );
+        assert_eq!(parse_smaps_rollup(""), None);
+    }
+
+    #[test]
+    fn test_read_smaps_rollup_own_process() {
+        let rollup = read_smaps_rollup(std::process::id()).unwrap();
+        assert!(rollup.rss > 0);
+        assert!(rollup.private <= rollup.pss && rollup.pss <= rollup.rss);
+        assert_eq!(read_smaps_rollup(999_999_999), None);
+    }
+}
@@ -0,0 +1,565 @@
+use crate::{
+    badness::{Candidate, EXPLAIN_HEADER, explain_candidate},
+    cgroup::CGROUP_ROOT,
+    effective_memory::meminfo_field,
+    journal::Decision,
+    kill_history::state_dir,
+    types::format_bytes,
+};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, Read, Write as _};
+use std::path::{Path, PathBuf};
+
+/// `[snapshot]` section of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SnapshotConfig {
+    /// Write a report of the system's memory state at every kill.
+    pub enabled: bool,
+    /// Directory for the reports; default `$XDG_STATE_HOME/oomkiller/snapshots`.
+    pub dir: Option<PathBuf>,
+    /// How many reports are kept; older ones are deleted.
+    pub keep: usize,
+    /// How many processes the report lists, largest by `scoring.memory_metric` first.
+    pub top_n: usize,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: None,
+            keep: 20,
+            top_n: 20,
+        }
+    }
+}
+
+impl SnapshotConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.keep == 0 || self.keep > 10_000 {
+            return Err(format!(
+                "snapshot.keep must be between 1 and 10000, got {}",
+                self.keep
+            ));
+        }
+        if self.top_n == 0 || self.top_n > 1000 {
+            return Err(format!(
+                "snapshot.top_n must be between 1 and 1000, got {}",
+                self.top_n
+            ));
+        }
+        Ok(())
+    }
+
+    /// `dir`, or `$XDG_STATE_HOME/oomkiller/snapshots`.
+    pub fn dir(&self) -> Option<PathBuf> {
+        match &self.dir {
+            Some(dir) => Some(dir.clone()),
+            None => Some(state_dir()?.join("snapshots")),
+        }
+    }
+}
+
+/// What a snapshot is taken of, besides the files in its [`Capture`].
+pub struct SnapshotInput<'a> {
+    /// The decision: reading, PSI, trigger and victim.
+    pub decision: &'a Decision,
+    /// Scored candidates, in any order.
+    pub candidates: &'a [Candidate],
+}
+
+/// Bytes kept of each captured file; the rest is cut off, which the report notes.
+const CAPTURE_BYTES: usize = 8 * 1024;
+
+/// Longest victim cgroup path whose memory files are captured.
+const CGROUP_BYTES: usize = 512;
+
+/// `/proc` and cgroup files of a report, read right before the victim is signalled
+/// into buffers reserved up front: the kill must not wait on an allocation (or fail
+/// for one). The report is rendered from them once the victim has been signalled.
+pub struct Capture {
+    taken: bool,
+    pid: Option<u32>,
+    cgroup: String,
+    path: String,
+    files: [Captured; CAPTURED_FILES],
+}
+
+/// Order of [`Capture::files`]; `/proc/<pid>/cgroup` is only read to find the cgroup.
+const CAPTURED_FILES: usize = 8;
+const MEMINFO: usize = 0;
+const PRESSURE: usize = 1;
+const PROC_FILES: [(usize, &str); 2] = [(2, "status"), (3, "smaps_rollup")];
+const PROC_CGROUP: usize = 4;
+const CGROUP_FILES: [(usize, &str); 3] = [
+    (5, "memory.current"),
+    (6, "memory.events"),
+    (7, "memory.stat"),
+];
+
+#[derive(Default)]
+struct Captured {
+    data: Vec<u8>,
+    error: Option<io::Error>,
+    truncated: bool,
+}
+
+impl Default for Capture {
+    fn default() -> Self {
+        Self {
+            taken: false,
+            pid: None,
+            cgroup: String::with_capacity(CGROUP_BYTES),
+            path: String::with_capacity(CGROUP_BYTES + 64),
+            // Written once, so the pages exist (and are locked) before the first kill.
+            files: std::array::from_fn(|_| {
+                let mut data = vec![1; CAPTURE_BYTES];
+                data.clear();
+                Captured {
+                    data,
+                    ..Captured::default()
+                }
+            }),
+        }
+    }
+}
+
+impl Capture {
+    /// Reads the files for a report without allocating.
+    ///
+    /// # Arguments
+    /// * `pid` - Process whose `status` and `smaps_rollup` are included (an app unit's largest member)
+    /// * `cgroup` - Cgroup of the victim relative to the cgroup root; `None` reads `pid`'s
+    pub fn take(&mut self, pid: Option<u32>, cgroup: Option<&str>) {
+        self.taken = true;
+        self.pid = pid;
+        self.read(MEMINFO, format_args!("/proc/meminfo"));
+        self.read(PRESSURE, format_args!("/proc/pressure/memory"));
+        self.cgroup.clear();
+        if let Some(pid) = pid {
+            for (slot, file) in PROC_FILES {
+                self.read(slot, format_args!("/proc/{pid}/{file}"));
+            }
+            if cgroup.is_none() {
+                self.read(PROC_CGROUP, format_args!("/proc/{pid}/cgroup"));
+            }
+        }
+        let cgroup = cgroup.or_else(|| {
+            let text = std::str::from_utf8(&self.files[PROC_CGROUP].data).ok()?;
+            text.lines().find_map(|line| line.strip_prefix("0::"))
+        });
+        if let Some(cgroup) = cgroup.filter(|c| c.len() <= CGROUP_BYTES) {
+            self.cgroup.push_str(cgroup.trim_start_matches('/'));
+        }
+        if !self.cgroup.is_empty() {
+            for (slot, file) in CGROUP_FILES {
+                let cgroup = std::mem::take(&mut self.cgroup);
+                self.read(slot, format_args!("{CGROUP_ROOT}/{cgroup}/{file}"));
+                self.cgroup = cgroup;
+            }
+        }
+    }
+
+    /// Whether there is a capture that has not been rendered yet.
+    pub fn is_taken(&self) -> bool {
+        self.taken
+    }
+
+    /// Reads the file at `path` into `files[slot]`, up to its reserved capacity.
+    fn read(&mut self, slot: usize, path: std::fmt::Arguments) {
+        self.path.clear();
+        let _ = self.path.write_fmt(path);
+        let file = &mut self.files[slot];
+        file.error = None;
+        file.truncated = false;
+        file.data.clear();
+        let mut opened = match File::open(&self.path) {
+            Ok(opened) => opened,
+            Err(e) => {
+                file.error = Some(e);
+                return;
+            }
+        };
+        file.data.resize(file.data.capacity(), 0);
+        let mut len = 0;
+        while len < file.data.len() {
+            match opened.read(&mut file.data[len..]) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    file.error = Some(e);
+                    break;
+                }
+            }
+        }
+        file.truncated = len == file.data.len() && opened.read(&mut [0]).is_ok_and(|n| n > 0);
+        file.data.truncate(len);
+    }
+
+    /// Text of `files[slot]`, or why it is missing.
+    fn text(&self, slot: usize) -> Cow<'_, str> {
+        let file = &self.files[slot];
+        match &file.error {
+            Some(e) => format!("(unreadable: {e})\n").into(),
+            None if file.truncated => {
+                format!("{}\n(truncated)\n", String::from_utf8_lossy(&file.data)).into()
+            }
+            None => String::from_utf8_lossy(&file.data),
+        }
+    }
+}
+
+/// Renders the report: the decision, `/proc/meminfo`, PSI, kernel memory (page
+/// cache and slab), the top `top_n` processes and the victim's `status`,
+/// `smaps_rollup` and cgroup memory files, as captured right before the kill.
+/// Unreadable files are noted, not fatal.
+pub fn render_report(input: &SnapshotInput, capture: &Capture, top_n: usize) -> String {
+    let decision = input.decision;
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "oomkiller snapshot at {:.3} (Unix time)",
+        decision.timestamp
+    );
+    let _ = writeln!(
+        out,
+        "trigger={} threshold={}% used={} / {} ({:.1}%, {})",
+        decision.trigger,
+        decision.threshold_percent,
+        format_bytes(decision.memory.used),
+        format_bytes(decision.memory.total),
+        decision.memory.used_percent,
+        decision.memory.accounting,
+    );
+    if let Some(psi) = &decision.psi {
+        let _ = writeln!(out, "psi {psi}");
+    }
+    if let Some(victim) = &decision.victim {
+        let _ = writeln!(
+            out,
+            "victim {}",
+            serde_json::to_string(victim).unwrap_or_default()
+        );
+    }
+
+    let meminfo = capture.text(MEMINFO);
+    section(&mut out, "/proc/meminfo", &meminfo);
+    section(&mut out, "/proc/pressure/memory", &capture.text(PRESSURE));
+    section(&mut out, "kernel memory", &kernel_summary(&meminfo));
+
+    let mut top: Vec<&Candidate> = input.candidates.iter().collect();
+    top.sort_by_key(|c| std::cmp::Reverse(c.badness.memory));
+    let mut table = format!("{EXPLAIN_HEADER}\n");
+    for candidate in top.iter().take(top_n) {
+        let _ = writeln!(
+            table,
+            "{}\n          cmdline: {}",
+            explain_candidate(candidate),
+            candidate.process.cmdline
+        );
+    }
+    section(
+        &mut out,
+        &format!("top {top_n} by {}", decision.memory_metric),
+        &table,
+    );
+
+    if let Some(pid) = capture.pid {
+        for (slot, file) in PROC_FILES {
+            section(
+                &mut out,
+                &format!("/proc/{pid}/{file}"),
+                &capture.text(slot),
+            );
+        }
+    }
+    if !capture.cgroup.is_empty() {
+        for (slot, file) in CGROUP_FILES {
+            section(
+                &mut out,
+                &format!("/{}/{file}", capture.cgroup),
+                &capture.text(slot),
+            );
+        }
+    }
+    out
+}
+
+/// Writes the report for `input` and `capture` to a new file in `config.dir()` and
+/// deletes all but the newest `config.keep` reports; failures to delete are only logged.
+/// The capture is used up either way. Reports of the same millisecond, like a failed
+/// Nix build stop followed by an app kill, get a `-1`, `-2`, ... suffix.
+///
+/// # Returns
+/// The new report's path, e.g. `snapshots/snapshot-1792391989168.txt`.
+pub fn write_snapshot(
+    config: &SnapshotConfig,
+    input: &SnapshotInput,
+    capture: &mut Capture,
+) -> Result<PathBuf, String> {
+    capture.taken = false;
+    let dir = config
+        .dir()
+        .ok_or("No snapshot directory: set snapshot.dir or XDG_STATE_HOME")?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create snapshot dir {}: {e}", dir.display()))?;
+    let millis = (input.decision.timestamp * 1000.0).round() as u64;
+    let mut path = dir.join(format!("snapshot-{millis:013}.txt"));
+    let mut suffix = 0;
+    let mut file = loop {
+        match File::create_new(&path) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                suffix += 1;
+                path = dir.join(format!("snapshot-{millis:013}-{suffix}.txt"));
+            }
+            opened => {
+                break opened
+                    .map_err(|e| format!("Failed to write snapshot {}: {e}", path.display()))?;
+            }
+        }
+    };
+    file.write_all(render_report(input, capture, config.top_n).as_bytes())
+        .map_err(|e| format!("Failed to write snapshot {}: {e}", path.display()))?;
+    if let Err(e) = prune_snapshots(&dir, config.keep) {
+        eprintln!("{e}");
+    }
+    Ok(path)
+}
+
+/// Deletes the oldest `snapshot-*.txt` files in `dir` until `keep` are left.
+///
+/// # Returns
+/// How many were deleted.
+pub fn prune_snapshots(dir: &Path, keep: usize) -> Result<usize, String> {
+    let fail = |e: std::io::Error| format!("Failed to prune snapshots in {}: {e}", dir.display());
+    let mut reports: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(fail)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("snapshot-") && name.ends_with(".txt"))
+        })
+        .collect();
+    // Zero-padded millisecond timestamps sort in time order; by stem, so that
+    // `snapshot-<ms>.txt` comes before its `snapshot-<ms>-1.txt`.
+    reports.sort_by(|a, b| a.file_stem().cmp(&b.file_stem()));
+    let excess = reports.len().saturating_sub(keep);
+    for path in &reports[..excess] {
+        fs::remove_file(path).map_err(fail)?;
+    }
+    Ok(excess)
+}
+
+/// Page cache and kernel allocations from `/proc/meminfo`, plus the largest
+/// slab caches (`/proc/slabinfo` is root-only).
+fn kernel_summary(meminfo: &str) -> String {
+    let mut out = String::new();
+    for key in [
+        "Cached",
+        "Buffers",
+        "Shmem",
+        "Dirty",
+        "Writeback",
+        "Slab",
+        "SReclaimable",
+        "SUnreclaim",
+        "KernelStack",
+        "PageTables",
+        "VmallocUsed",
+    ] {
+        if let Some(bytes) = meminfo_field(meminfo, key) {
+            let _ = writeln!(out, "{key:<14}{:>12}", format_bytes(bytes));
+        }
+    }
+    match fs::read_to_string("/proc/slabinfo") {
+        Ok(text) => {
+            let _ = writeln!(out, "largest slab caches:");
+            for (name, bytes) in parse_slabinfo(&text).into_iter().take(10) {
+                let _ = writeln!(out, "  {name:<24}{:>12}", format_bytes(bytes));
+            }
+        }
+        Err(e) => {
+            let _ = writeln!(out, "/proc/slabinfo unreadable: {e}");
+        }
+    }
+    out
+}
+
+/// Slab caches from `/proc/slabinfo` by size (`num_objs * objsize`), largest first.
+pub fn parse_slabinfo(text: &str) -> Vec<(String, u64)> {
+    let mut caches: Vec<(String, u64)> = text
+        .lines()
+        .filter(|line| !line.starts_with("slabinfo") && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?;
+            let _active: u64 = fields.next()?.parse().ok()?;
+            let objects: u64 = fields.next()?.parse().ok()?;
+            let size: u64 = fields.next()?.parse().ok()?;
+            Some((name.to_string(), objects * size))
+        })
+        .collect();
+    caches.sort_by_key(|(_, bytes)| std::cmp::Reverse(*bytes));
+    caches
+}
+
+fn section(out: &mut String, title: &str, body: &str) {
+    let _ = write!(out, "\n=== {title} ===\n{body}");
+    if !body.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::badness::{ScoringConfig, rank_candidates};
+    use crate::config::Config;
+    use crate::types::ProcessInfo;
+
+    #[test]
+    fn test_parse_slabinfo() {
+        let text = "\
+slabinfo - version: 2.1
+# name            <active_objs> <num_objs> <objsize> <objperslab> <pagesperslab> : tunables <limit> <batchcount> <sharedfactor> : slabdata <active_slabs> <num_slabs> <sharedavail>
+dentry            100000 120000    192   21    1 : tunables    0    0    0 : slabdata   5714   5714      0
+kmalloc-64         50000  64000     64   64    1 : tunables    0    0    0 : slabdata   1000   1000      0
+";
+        assert_eq!(
+            parse_slabinfo(text),
+            [
+                ("dentry".to_string(), 120_000 * 192),
+                ("kmalloc-64".to_string(), 64_000 * 64)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report_of_own_process() {
+        let pid = std::process::id();
+        let candidates = rank_candidates(
+            vec![ProcessInfo {
+                pid,
+                memory: 1 << 20,
+                name: "oomkiller-test".to_string(),
+                cmdline: "oomkiller-test --flag".to_string(),
+                ..ProcessInfo::default()
+            }],
+            1 << 30,
+            &ScoringConfig::default(),
+        );
+        let decision = Decision::new(&Config::default(), 900, 1000, None);
+        let mut capture = Capture::default();
+        capture.take(Some(pid), None);
+        let report = render_report(
+            &SnapshotInput {
+                decision: &decision,
+                candidates: &candidates,
+            },
+            &capture,
+            20,
+        );
+        assert!(report.contains("=== /proc/meminfo ===\nMemTotal:"));
+        assert!(report.contains("=== top 20 by uss ==="));
+        assert!(report.contains("cmdline: oomkiller-test --flag"));
+        assert!(report.contains(&format!("=== /proc/{pid}/status ===\nName:")));
+        assert!(report.contains(&format!("=== /proc/{pid}/smaps_rollup ===")));
+    }
+
+    #[test]
+    fn test_capture_outlives_the_victim() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        let mut capture = Capture::default();
+        capture.take(Some(pid), None);
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        let decision = Decision::new(&Config::default(), 900, 1000, None);
+        let input = SnapshotInput {
+            decision: &decision,
+            candidates: &[],
+        };
+        let report = render_report(&input, &capture, 20);
+        assert!(report.contains(&format!("=== /proc/{pid}/status ===\nName:")));
+        assert!(report.contains(&format!("\nPid:\t{pid}\n")));
+        assert!(capture.is_taken());
+    }
+
+    #[test]
+    fn test_write_snapshot_keeps_newest() {
+        let dir = std::env::temp_dir().join(format!("oomkiller-snapshots-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let config = SnapshotConfig {
+            dir: Some(dir.clone()),
+            keep: 2,
+            ..SnapshotConfig::default()
+        };
+        let mut decision = Decision::new(&Config::default(), 1, 2, None);
+        let mut written = Vec::new();
+        for timestamp in [100.0, 200.0, 300.0] {
+            decision.timestamp = timestamp;
+            let input = SnapshotInput {
+                decision: &decision,
+                candidates: &[],
+            };
+            let mut capture = Capture::default();
+            capture.take(None, None);
+            written.push(write_snapshot(&config, &input, &mut capture).unwrap());
+        }
+        assert_eq!(
+            written[2].file_name().unwrap(),
+            "snapshot-0000000300000.txt"
+        );
+        assert!(!written[0].exists() && written[1].exists() && written[2].exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_snapshot_same_millisecond() {
+        let dir =
+            std::env::temp_dir().join(format!("oomkiller-snapshots-ms-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let config = SnapshotConfig {
+            dir: Some(dir.clone()),
+            keep: 2,
+            ..SnapshotConfig::default()
+        };
+        let decision = Decision::new(&Config::default(), 1, 2, None);
+        let input = SnapshotInput {
+            decision: &decision,
+            candidates: &[],
+        };
+        let mut written = Vec::new();
+        for _ in 0..3 {
+            let mut capture = Capture::default();
+            capture.take(None, None);
+            written.push(write_snapshot(&config, &input, &mut capture).unwrap());
+        }
+        let names: Vec<_> = written.iter().map(|p| p.file_name().unwrap()).collect();
+        assert!(names[1].to_str().unwrap().ends_with("-1.txt"));
+        assert!(names[2].to_str().unwrap().ends_with("-2.txt"));
+        // The unsuffixed report is the oldest of the three.
+        assert!(!written[0].exists() && written[1].exists() && written[2].exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(SnapshotConfig::default().validate().is_ok());
+        let config = SnapshotConfig {
+            keep: 0,
+            ..SnapshotConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,292 @@
+use crate::{
+    cgroup::{AppGroup, freeze_cgroup, is_delegated, read_cgroup_path, read_memory_current},
+    config::{EscalationConfig, Target},
+    effective_memory::{Accounting, MemoryConfig, read_effective_memory},
+    freeze::FrozenTarget,
+    get_candidate_processes,
+    host::{MemorySnapshot, MemorySource, ProcessTable, SignalSink},
+    kill_history::SystemdUnit,
+    procfs::{read_children, read_exe, read_process},
+    psi::{PsiSnapshot, read_memory_pressure},
+    smaps::{SmapsRollup, read_smaps_rollup},
+    types::{KillOutcome, ProcessInfo},
+};
+use std::path::PathBuf;
+use sysinfo::System;
+use zbus::blocking::Connection;
+
+/// Memory from sysinfo (RAM) or `/proc/meminfo` + zram (effective), PSI from `/proc/pressure`.
+pub struct SysinfoMemory {
+    system: System,
+}
+
+impl Default for SysinfoMemory {
+    fn default() -> Self {
+        Self {
+            system: System::new(),
+        }
+    }
+}
+
+impl MemorySource for SysinfoMemory {
+    fn snapshot(&mut self, config: &MemoryConfig) -> Result<MemorySnapshot, String> {
+        if config.accounting == Accounting::Effective {
+            match read_effective_memory(config) {
+                Ok(memory) => {
+                    return Ok(MemorySnapshot {
+                        used: memory.used(),
+                        total: memory.total,
+                    });
+                }
+                Err(e) => eprintln!("Effective memory unavailable, using RAM only: {e}"),
+            }
+        }
+        // Only refresh memory, not all processes (much faster)
+        self.system.refresh_memory();
+        let total = self.system.total_memory();
+        if total == 0 {
+            return Err("Total memory is 0, cannot calculate usage percentage".to_string());
+        }
+        Ok(MemorySnapshot {
+            used: self.system.used_memory(),
+            total,
+        })
+    }
+
+    fn pressure(&mut self) -> Option<PsiSnapshot> {
+        read_memory_pressure().ok()
+    }
+}
+
+/// Processes from a sysinfo `System` (reused across iterations), cgroups from `/sys/fs/cgroup`.
+pub struct SysinfoProcesses {
+    system: System,
+}
+
+impl Default for SysinfoProcesses {
+    fn default() -> Self {
+        Self {
+            system: System::new_all(),
+        }
+    }
+}
+
+impl ProcessTable for SysinfoProcesses {
+    fn refresh(&mut self, _target: Target) {
+        self.system.refresh_all();
+    }
+
+    fn total_memory(&self) -> u64 {
+        self.system.total_memory()
+    }
+
+    fn candidates(&self, target: Target) -> Result<Vec<ProcessInfo>, String> {
+        get_candidate_processes(&self.system, target)
+    }
+
+    fn cgroup_path(&self, pid: u32) -> Option<String> {
+        read_cgroup_path(pid).ok()
+    }
+
+    fn executable(&self, pid: u32) -> Option<PathBuf> {
+        read_exe(pid)
+    }
+
+    fn smaps_rollup(&self, pid: u32) -> Option<SmapsRollup> {
+        read_smaps_rollup(pid)
+    }
+
+    fn unit_memory(&self, group: &AppGroup) -> Option<u64> {
+        read_memory_current(&group.dir()).ok()
+    }
+
+    fn is_delegated(&self, group: &AppGroup) -> bool {
+        is_delegated(&group.dir())
+    }
+
+    fn is_alive(&self, target: &FrozenTarget) -> bool {
+        target.is_alive()
+    }
+
+    fn children(&self, pid: u32) -> Vec<ProcessInfo> {
+        read_children(pid)
+            .into_iter()
+            .filter_map(read_process)
+            .collect()
+    }
+}
+
+/// Real signals: pidfds for processes, `cgroup.kill` for app units, systemd's D-Bus API for units.
+pub struct KernelSignals;
+
+impl SignalSink for KernelSignals {
+    #[cfg(target_os = "linux")]
+    fn stop_process(
+        &mut self,
+        process: &ProcessInfo,
+        config: &EscalationConfig,
+        critical: bool,
+        memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String> {
+        crate::escalate_kill(process, config, critical, memory_recovered)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn stop_process(
+        &mut self,
+        process: &ProcessInfo,
+        _config: &EscalationConfig,
+        _critical: bool,
+        _memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String> {
+        crate::kill_process(process).map(|()| KillOutcome::Killed {
+            after: std::time::Duration::ZERO,
+            term_sent: false,
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    fn stop_app(
+        &mut self,
+        group: &AppGroup,
+        config: &EscalationConfig,
+        critical: bool,
+        memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String> {
+        crate::escalate_kill_group(group, config, critical, memory_recovered)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn stop_app(
+        &mut self,
+        _group: &AppGroup,
+        _config: &EscalationConfig,
+        _critical: bool,
+        _memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String> {
+        Err("cgroup kill is only supported on Linux".to_string())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn stop_tree(
+        &mut self,
+        process: &ProcessInfo,
+        descendants: &[ProcessInfo],
+        config: &EscalationConfig,
+        critical: bool,
+        memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String> {
+        crate::escalate_kill_tree(process, descendants, config, critical, memory_recovered)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn stop_tree(
+        &mut self,
+        process: &ProcessInfo,
+        descendants: &[ProcessInfo],
+        config: &EscalationConfig,
+        critical: bool,
+        memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String> {
+        for descendant in descendants {
+            let _ = crate::kill_process(descendant);
+        }
+        self.stop_process(process, config, critical, memory_recovered)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn freeze(&mut self, target: &FrozenTarget, frozen: bool) -> Result<(), String> {
+        match target {
+            FrozenTarget::Process { pid, start_time } => {
+                let signal = if frozen { libc::SIGSTOP } else { libc::SIGCONT };
+                crate::PidFd::open_verified(*pid, *start_time)?
+                    .send_signal(signal)
+                    .map_err(|e| format!("Failed to signal pid {pid}: {e}"))
+            }
+            FrozenTarget::Cgroup { path } => freeze_cgroup(
+                &AppGroup {
+                    path: path.clone(),
+                    ..AppGroup::default()
+                }
+                .dir(),
+                frozen,
+            ),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn freeze(&mut self, _target: &FrozenTarget, _frozen: bool) -> Result<(), String> {
+        Err("freezing is only supported on Linux".to_string())
+    }
+
+    /// `StopUnit(name, "replace")` on the systemd manager; the job is queued, not waited for.
+    ///
+    /// The daemon runs as a system service, so for user units the user manager's bus is
+    /// found at `/run/user/UID/bus` when there is no session bus address.
+    fn stop_unit(&mut self, unit: &SystemdUnit) -> Result<(), String> {
+        let connection = if unit.user {
+            user_bus()
+        } else {
+            Connection::system()
+        }
+        .map_err(|e| format!("Failed to connect to the systemd manager: {e}"))?;
+        connection
+            .call_method(
+                Some("org.freedesktop.systemd1"),
+                "/org/freedesktop/systemd1",
+                Some("org.freedesktop.systemd1.Manager"),
+                "StopUnit",
+                &(unit.name.as_str(), "replace"),
+            )
+            .map(drop)
+            .map_err(|e| format!("Failed to stop {}: {e}", unit.name))
+    }
+}
+
+/// The session bus of the user the daemon runs as.
+fn user_bus() -> zbus::Result<Connection> {
+    if std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_some() {
+        return Connection::session();
+    }
+    let uid = crate::get_current_uid().map_err(zbus::Error::Failure)?;
+    zbus::blocking::connection::Builder::address(format!("unix:path=/run/user/{uid}/bus").as_str())?
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sysinfo_memory_reads_ram() {
+        let snapshot = SysinfoMemory::default()
+            .snapshot(&MemoryConfig {
+                accounting: Accounting::Ram,
+                ..MemoryConfig::default()
+            })
+            .unwrap();
+        assert!(snapshot.total > 0);
+        assert!(snapshot.used <= snapshot.total);
+    }
+
+    #[test]
+    fn test_sysinfo_processes_lists_own_child() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let mut table = SysinfoProcesses::default();
+        table.refresh(Target::All);
+        let found = table
+            .candidates(Target::All)
+            .unwrap()
+            .into_iter()
+            .find(|p| p.pid == child.id());
+        child.kill().unwrap();
+        child.wait().unwrap();
+        let found = found.expect("child listed");
+        assert_eq!(found.name, "sleep");
+        assert_eq!(found.cmdline, "sleep 30");
+        assert!(found.start_time > 0);
+    }
+}
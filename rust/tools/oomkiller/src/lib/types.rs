@@ -1,30 +1,88 @@
+use crate::smaps::{MemoryMetric, SmapsRollup};
+use std::fmt;
+use std::time::Duration;
+
 /// Represents a process with its PID, memory usage, and identity for logging.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ProcessInfo {
     pub pid: u32,
     /// RSS memory in bytes (sysinfo reports bytes).
     pub memory: u64,
+    /// Swapped-out memory in bytes (`VmSwap`; 0 if unreadable).
+    pub swap: u64,
     /// Short process name (comm / basename).
     pub name: String,
     /// Full command line, space-joined; may be empty if unavailable.
     pub cmdline: String,
+    /// Start time in clock ticks since boot; guards against PID reuse when signalling.
+    pub start_time: u64,
+    /// Kernel OOM badness from `/proc/<pid>/oom_score` (0 if unreadable).
+    pub oom_score: i32,
+    /// OOM bias from `/proc/<pid>/oom_score_adj` (0 if unreadable).
+    pub oom_score_adj: i32,
+    /// `/proc/<pid>/smaps_rollup`, read for candidates unless `scoring.memory_metric = "rss"`.
+    pub smaps: Option<SmapsRollup>,
+}
+
+impl ProcessInfo {
+    /// Memory plus swap under `metric`: what killing the process is expected to give back.
+    ///
+    /// Falls back to RSS + `VmSwap` when `smaps_rollup` could not be read.
+    pub fn footprint(&self, metric: MemoryMetric) -> u64 {
+        match (metric, &self.smaps) {
+            (MemoryMetric::Pss, Some(smaps)) => smaps.pss_footprint(),
+            (MemoryMetric::Uss, Some(smaps)) => smaps.uss_footprint(),
+            _ => self.memory + self.swap,
+        }
+    }
+}
+
+/// How an escalation ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillOutcome {
+    /// The process exited on its own after SIGTERM.
+    Terminated { after: Duration },
+    /// Memory was no longer critical before the grace period ran out; SIGKILL not sent.
+    Recovered { after: Duration },
+    /// SIGKILL was sent, either after the grace period or straight away.
+    Killed { after: Duration, term_sent: bool },
+}
+
+impl KillOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Terminated { .. } => "terminated",
+            Self::Recovered { .. } => "recovered",
+            Self::Killed { .. } => "killed",
+        }
+    }
+}
+
+/// A byte count that displays human-readably (e.g. `1.28 GiB`) without allocating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bytes(pub u64);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const KIB: f64 = 1024.0;
+        const MIB: f64 = KIB * 1024.0;
+        const GIB: f64 = MIB * 1024.0;
+        let b = self.0 as f64;
+        if b >= GIB {
+            write!(f, "{:.2} GiB", b / GIB)
+        } else if b >= MIB {
+            write!(f, "{:.1} MiB", b / MIB)
+        } else if b >= KIB {
+            write!(f, "{:.1} KiB", b / KIB)
+        } else {
+            write!(f, "{} B", self.0)
+        }
+    }
 }
 
 /// Formats a byte count for human-readable logs (e.g. `1.28 GiB`).
 pub fn format_bytes(bytes: u64) -> String {
-    const KIB: f64 = 1024.0;
-    const MIB: f64 = KIB * 1024.0;
-    const GIB: f64 = MIB * 1024.0;
-    let b = bytes as f64;
-    if b >= GIB {
-        format!("{:.2} GiB", b / GIB)
-    } else if b >= MIB {
-        format!("{:.1} MiB", b / MIB)
-    } else if b >= KIB {
-        format!("{:.1} KiB", b / KIB)
-    } else {
-        format!("{bytes} B")
-    }
+    Bytes(bytes).to_string()
 }
 
 #[cfg(test)]
@@ -37,6 +95,8 @@ mod tests {
             memory,
             name: "test".to_string(),
             cmdline: "test --flag".to_string(),
+            start_time: 42,
+            ..ProcessInfo::default()
         }
     }
 
@@ -66,6 +126,28 @@ mod tests {
         assert_eq!(process1.memory, process2.memory);
         assert_eq!(process1.name, process2.name);
         assert_eq!(process1.cmdline, process2.cmdline);
+        assert_eq!(process1.start_time, process2.start_time);
+    }
+
+    #[test]
+    fn test_footprint_includes_swap() {
+        let mut process = ProcessInfo {
+            swap: 512,
+            ..sample(1, 1024)
+        };
+        assert_eq!(process.footprint(MemoryMetric::Rss), 1536);
+        // Without smaps_rollup every metric falls back to RSS.
+        assert_eq!(process.footprint(MemoryMetric::Uss), 1536);
+        process.smaps = Some(SmapsRollup {
+            rss: 1024,
+            pss: 600,
+            private: 200,
+            swap: 512,
+            swap_pss: 256,
+        });
+        assert_eq!(process.footprint(MemoryMetric::Rss), 1536);
+        assert_eq!(process.footprint(MemoryMetric::Pss), 856);
+        assert_eq!(process.footprint(MemoryMetric::Uss), 712);
     }
 
     #[test]
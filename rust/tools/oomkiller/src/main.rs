@@ -1,15 +1,351 @@
-use oomkiller::daemon_iteration;
-use sysinfo::System;
+use clap::builder::BoolishValueParser;
+use clap::{Parser, Subcommand};
+use oomkiller::{
+    Config, ConfigOverrides, DaemonState, FreezeState, GroupBy, Host, KillHistory, ProtectTarget,
+    PsiTrigger, Request, SystemdNotifier, Target, TriggerMode, badness::EXPLAIN_HEADER,
+    daemon_iteration, default_config_path, default_socket_path, explain_candidate, format_bytes,
+    harden, parse_duration, ranked_candidates, select_app, send_request, serve_control,
+    serve_metrics, status_line,
+};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "oomkiller",
+    version,
+    about = "Kill the largest process before the system runs out of memory"
+)]
+struct Cli {
+    /// TOML config file (default: $XDG_CONFIG_HOME/oomkiller/config.toml if present)
+    #[arg(long, env = "OOMKILLER_CONFIG", value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Used memory percentage that triggers a kill
+    #[arg(long, env = "OOMKILLER_THRESHOLD", value_name = "PERCENT")]
+    threshold: Option<f64>,
+
+    /// Seconds between memory checks when poll.adaptive is off
+    #[arg(long, env = "OOMKILLER_INTERVAL", value_name = "SECS")]
+    interval: Option<f64>,
+
+    /// Which processes may be killed: user | all
+    #[arg(long, env = "OOMKILLER_TARGET", value_parser = Target::parse)]
+    target: Option<Target>,
+
+    /// Log what would be killed without sending any signal (--dry-run=false turns a
+    /// config file's dry_run off)
+    #[arg(
+        long,
+        env = "OOMKILLER_DRY_RUN",
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_parser = BoolishValueParser::new()
+    )]
+    dry_run: Option<bool>,
+
+    /// What counts as critical: percent | psi | either | both
+    #[arg(long, env = "OOMKILLER_TRIGGER", value_parser = TriggerMode::parse)]
+    trigger: Option<TriggerMode>,
+
+    /// Append every kill decision as a JSON line to this file
+    #[arg(long, env = "OOMKILLER_JOURNAL", value_name = "PATH")]
+    journal: Option<PathBuf>,
+
+    /// What to kill: process | cgroup (whole app scope via cgroup.kill)
+    #[arg(long, env = "OOMKILLER_GROUP_BY", value_parser = GroupBy::parse)]
+    group_by: Option<GroupBy>,
+
+    /// Print every candidate's badness breakdown (highest first) and exit
+    #[arg(long)]
+    explain: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Control the running daemon over its socket
+    Ctl {
+        /// Control socket (default: control.socket from the config, else $XDG_RUNTIME_DIR/oomkiller.sock)
+        #[arg(long, env = "OOMKILLER_SOCKET", value_name = "PATH")]
+        socket: Option<PathBuf>,
+
+        #[command(subcommand)]
+        request: CtlRequest,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum CtlRequest {
+    /// Show memory, PSI, pause/protect state and the top candidates
+    Status,
+    /// Stop killing for DURATION (e.g. 90s, 30m, 2h)
+    Pause {
+        #[arg(value_parser = parse_duration)]
+        duration: Duration,
+    },
+    /// End a pause
+    Resume,
+    /// Never kill this PID or process name (until unprotected or the daemon restarts)
+    Protect { target: String },
+    /// Drop the protection of TARGET, or all protections
+    Unprotect { target: Option<String> },
+    /// Run the normal victim selection now, whatever the memory usage
+    KillNow,
+}
+
+impl CtlRequest {
+    fn request(&self) -> Request {
+        match self {
+            Self::Status => Request::Status,
+            Self::Pause { duration } => Request::Pause(*duration),
+            Self::Resume => Request::Resume,
+            Self::Protect { target } => Request::Protect(ProtectTarget::parse(target)),
+            Self::Unprotect { target } => {
+                Request::Unprotect(target.as_deref().map(ProtectTarget::parse))
+            }
+            Self::KillNow => Request::KillNow,
+        }
+    }
+}
+
+impl Cli {
+    fn overrides(&self) -> ConfigOverrides {
+        ConfigOverrides {
+            threshold_percent: self.threshold,
+            interval_secs: self.interval,
+            target: self.target,
+            dry_run: self.dry_run,
+            trigger: self.trigger,
+            group_by: self.group_by,
+            journal_path: self.journal.clone(),
+        }
+    }
+}
+
+/// Arms the kernel PSI trigger when the config uses PSI, so the loop wakes on
+/// pressure instead of waiting out the full interval.
+fn arm_psi_trigger(config: &Config) -> Option<PsiTrigger> {
+    if !config.trigger.uses_psi() {
+        return None;
+    }
+    match PsiTrigger::new(&config.psi) {
+        Ok(trigger) => {
+            println!(
+                "PSI trigger armed: {} {}ms per {}ms",
+                config.psi.trigger_kind.as_str(),
+                config.psi.stall_ms,
+                config.psi.window_ms
+            );
+            Some(trigger)
+        }
+        Err(e) => {
+            eprintln!("PSI trigger unavailable, polling only: {e}");
+            None
+        }
+    }
+}
+
+/// `oomkiller ctl`: sends one request to the daemon and prints its reply.
+///
+/// The socket is `--socket`, else `control.socket` from the config (if it loads), else the default.
+fn ctl(
+    socket: Option<PathBuf>,
+    config_path: Option<&std::path::Path>,
+    overrides: &ConfigOverrides,
+    request: &CtlRequest,
+) -> Result<String, String> {
+    let socket = socket
+        .or_else(|| {
+            Config::load(config_path, overrides)
+                .ok()
+                .and_then(|config| config.control.socket_path())
+        })
+        .or_else(default_socket_path)
+        .ok_or("No control socket: pass --socket or set XDG_RUNTIME_DIR")?;
+    send_request(&socket, &request.request())
+}
+
+/// `--explain`: score the current candidates once and print the ranking.
+fn explain(config: &Config) -> Result<(), String> {
+    let mut host = Host::default();
+    host.processes.refresh(config.target);
+    let processes = host.processes.as_ref();
+    let ranked = ranked_candidates(processes, config)?;
+    println!("{EXPLAIN_HEADER}");
+    for candidate in &ranked {
+        println!("{}", explain_candidate(candidate));
+    }
+    if config.group_by == GroupBy::Cgroup {
+        match select_app(processes, config)? {
+            Some(group) => println!(
+                "\ngroup_by=cgroup would kill unit={} memory={} processes={}",
+                group.unit(),
+                format_bytes(group.memory),
+                group.processes.len()
+            ),
+            None => {
+                println!("\ngroup_by=cgroup: no delegated app unit, per-process selection applies")
+            }
+        }
+    }
+    Ok(())
+}
 
 fn main() {
-    // Create System object once and reuse it to avoid expensive initialization
-    let mut system = System::new_all();
+    let cli = Cli::parse();
+    let overrides = cli.overrides();
+    let config_path = cli.config.clone().or_else(default_config_path);
+
+    if let Some(Command::Ctl { socket, request }) = &cli.command {
+        match ctl(socket.clone(), config_path.as_deref(), &overrides, request) {
+            Ok(reply) => println!("{}", reply.trim_end()),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let mut config = match Config::load(config_path.as_deref(), &overrides) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid configuration: {e}");
+            std::process::exit(2);
+        }
+    };
+    if cli.explain {
+        if let Err(e) = explain(&config) {
+            eprintln!("Failed to list candidates: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    match &config_path {
+        Some(path) => println!(
+            "oomkiller started ({}): {}",
+            path.display(),
+            config.summary()
+        ),
+        None => println!(
+            "oomkiller started (built-in defaults): {}",
+            config.summary()
+        ),
+    }
+
+    // SIGHUP re-reads the config file; CLI/env overrides keep winning.
+    let reload = Arc::new(AtomicBool::new(false));
+    if let Err(e) = signal_hook::flag::register(signal_hook::consts::SIGHUP, Arc::clone(&reload)) {
+        eprintln!("Failed to install SIGHUP handler, config reload disabled: {e}");
+    }
+
+    let mut psi_trigger = arm_psi_trigger(&config);
+
+    // Create the host once and reuse it to avoid expensive initialization. The state
+    // comes first, so its snapshot buffers are covered by the memory lock.
+    let mut host = Host::default();
+    let mut state = DaemonState::default();
+    for step in harden(&config.hardening, &mut host, config.target) {
+        match step.result {
+            Ok(done) => println!("Hardening {}: {done}", step.name),
+            Err(e) => eprintln!("Hardening {} failed: {e}", step.name),
+        }
+    }
+
+    // Read once; changing kill_loop.history needs a restart.
+    if let Some(path) = config.kill_loop.history_path() {
+        match KillHistory::load(&path) {
+            Ok(history) => state.history = history,
+            Err(e) => eprintln!("{e}; starting with an empty kill history"),
+        }
+        state.history_path = Some(path);
+    }
+
+    // A victim left frozen by a previous run is adopted and asked about again.
+    if let Some(path) = config.freeze.state_path() {
+        match FreezeState::load(&path) {
+            Ok(freeze) => {
+                if let Some(frozen) = &freeze.frozen {
+                    println!(
+                        "Adopted frozen {} ({}) from the previous run",
+                        frozen.name(),
+                        frozen.target
+                    );
+                }
+                state.freeze = freeze;
+            }
+            Err(e) => eprintln!("{e}; not tracking any frozen victim"),
+        }
+        state.freeze_path = Some(path);
+    }
+
+    // Bound once; changing control.socket needs a restart.
+    state.control.set_config(&config);
+    state.control.set_waker(std::thread::current());
+    if config.control.enabled {
+        match config.control.socket_path() {
+            Some(path) => match serve_control(&path, Arc::clone(&state.control)) {
+                Ok(()) => println!("Control socket at {}", path.display()),
+                Err(e) => eprintln!("Control socket disabled: {e}"),
+            },
+            None => eprintln!("Control socket disabled: no XDG_RUNTIME_DIR and unknown UID"),
+        }
+    }
+
+    // The listener is bound once; changing metrics.listen needs a restart.
+    if let Some(addr) = config.metrics.listen {
+        match serve_metrics(addr, Arc::clone(&state.metrics_text)) {
+            Ok(bound) => println!("Serving metrics on http://{bound}/metrics"),
+            Err(e) => eprintln!("Metrics listener disabled: {e}"),
+        }
+    }
+
+    // Under Type=notify: READY=1 after the first iteration, then WATCHDOG=1 and STATUS=.
+    state.systemd = match SystemdNotifier::from_env() {
+        Some(Ok(notifier)) => {
+            if let Some(ping) = notifier.ping_interval() {
+                println!(
+                    "systemd watchdog armed, pinging every {:.1}s",
+                    ping.as_secs_f64()
+                );
+            }
+            Some(notifier)
+        }
+        Some(Err(e)) => {
+            eprintln!("systemd notification disabled: {e}");
+            None
+        }
+        None => None,
+    };
+    let mut ready = false;
 
-    // Main daemon loop: check memory every 5 seconds (reduced from 1 second)
-    // This significantly reduces CPU usage while still being responsive enough
     loop {
-        // Perform one daemon iteration with the reused System object
-        match daemon_iteration(&mut system) {
+        if reload.swap(false, Ordering::Relaxed) {
+            match Config::load(config_path.as_deref(), &overrides) {
+                Ok(new_config) => {
+                    println!("Reloaded configuration: {}", new_config.summary());
+                    let rearm =
+                        new_config.trigger != config.trigger || new_config.psi != config.psi;
+                    config = new_config;
+                    state.control.set_config(&config);
+                    if rearm {
+                        psi_trigger = arm_psi_trigger(&config);
+                    }
+                }
+                Err(e) => eprintln!("Config reload failed, keeping previous settings: {e}"),
+            }
+        }
+
+        // Perform one daemon iteration with the reused host
+        match daemon_iteration(&mut host, &config, &mut state) {
             Ok(()) => {
                 // Iteration completed successfully, continue monitoring
             }
@@ -20,7 +356,40 @@ fn main() {
             }
         }
 
-        // Sleep for 5 seconds before next check (reduced frequency = lower CPU)
-        std::thread::sleep(std::time::Duration::from_secs(5));
+        if let Some(systemd) = &state.systemd {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0.0, |d| d.as_secs_f64());
+            let status = status_line(&state.metrics, now);
+            let sent = if ready {
+                systemd.alive(&status)
+            } else {
+                systemd.ready(&status)
+            };
+            if let Err(e) = sent {
+                eprintln!("{e}");
+            }
+        }
+        ready = true;
+
+        // Sleep until the next check (as set from headroom and trend by the iteration),
+        // or until the kernel reports memory stalls. The watchdog has to be pinged in
+        // time, so it can shorten the interval. A `ctl kill-now` unparks the polling
+        // sleep; under PSI it waits for the next wake-up.
+        let interval = state.poll.interval();
+        let interval = state
+            .systemd
+            .as_ref()
+            .and_then(SystemdNotifier::ping_interval)
+            .map_or(interval, |ping| ping.min(interval));
+        match &psi_trigger {
+            Some(trigger) => {
+                if let Err(e) = trigger.wait(interval) {
+                    eprintln!("PSI trigger failed, falling back to polling: {e}");
+                    psi_trigger = None;
+                }
+            }
+            None => std::thread::park_timeout(interval),
+        }
     }
 }
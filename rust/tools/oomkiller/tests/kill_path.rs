@@ -0,0 +1,220 @@
+//! The per-process kill path must not allocate: when memory is exhausted, a failed
+//! allocation would abort the daemon before the victim is signalled. Choosing the
+//! victim does allocate, but nothing that is only reported (snapshot, journal,
+//! notifications) may add to that before the signal.
+//!
+//! Runs without the test harness so stdout is not captured (capturing allocates)
+//! and a counting global allocator can be installed.
+
+use oomkiller::fake_host::{FakeMemory, FakeProcesses, FakeSignals, GIB, RecordingSink};
+use oomkiller::notify::{KillNotifier, Notice};
+use oomkiller::{
+    AppGroup, Config, DaemonState, EscalationConfig, FrozenTarget, Host, KillOutcome, ProcessInfo,
+    SignalSink, SystemdUnit, daemon_iteration, escalate_kill, procfs,
+};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+struct Counting;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        // SAFETY: forwarded unchanged to the system allocator.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: `ptr` came from `alloc` above.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        // SAFETY: forwarded unchanged to the system allocator.
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn spawn() -> (Child, ProcessInfo) {
+    let child = Command::new("sleep").arg("30").spawn().unwrap();
+    let pid = child.id();
+    let process = ProcessInfo {
+        pid,
+        memory: 3 << 30,
+        name: "sleep".to_string(),
+        start_time: procfs::read_start_time(pid).unwrap(),
+        ..ProcessInfo::default()
+    };
+    (child, process)
+}
+
+/// Runs `escalate_kill` on a fresh child and returns its outcome and allocation count.
+fn count_allocations(critical: bool) -> (KillOutcome, u64) {
+    let (mut child, process) = spawn();
+    let config = EscalationConfig {
+        grace_ms: 2000,
+        poll_ms: 10,
+        ..EscalationConfig::default()
+    };
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let outcome = escalate_kill(&process, &config, critical, || false).unwrap();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    child.wait().unwrap();
+    (outcome, allocations)
+}
+
+/// Allocation count when [`Probe`] was asked to stop the victim.
+static SIGNALLED_AT: AtomicU64 = AtomicU64::new(0);
+
+/// Notices and files (journal, snapshots) that existed by then.
+static REPORTED_AT_SIGNAL: AtomicUsize = AtomicUsize::new(0);
+
+/// Stops like [`FakeSignals`], first noting what had happened by the signal.
+struct Probe {
+    signals: FakeSignals,
+    notices: Arc<Mutex<Vec<Notice>>>,
+    dir: PathBuf,
+}
+
+impl Probe {
+    fn note(&self) {
+        SIGNALLED_AT.store(ALLOCATIONS.load(Ordering::Relaxed), Ordering::Relaxed);
+        let notices = self.notices.lock().unwrap().len();
+        let files = fs::read_dir(&self.dir).map_or(0, Iterator::count);
+        REPORTED_AT_SIGNAL.store(notices + files, Ordering::Relaxed);
+    }
+}
+
+impl SignalSink for Probe {
+    fn stop_process(
+        &mut self,
+        process: &ProcessInfo,
+        config: &EscalationConfig,
+        critical: bool,
+        memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String> {
+        self.note();
+        self.signals
+            .stop_process(process, config, critical, memory_recovered)
+    }
+
+    fn stop_app(
+        &mut self,
+        group: &AppGroup,
+        config: &EscalationConfig,
+        critical: bool,
+        memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String> {
+        self.note();
+        self.signals
+            .stop_app(group, config, critical, memory_recovered)
+    }
+
+    fn stop_tree(
+        &mut self,
+        process: &ProcessInfo,
+        descendants: &[ProcessInfo],
+        config: &EscalationConfig,
+        critical: bool,
+        memory_recovered: &mut dyn FnMut() -> bool,
+    ) -> Result<KillOutcome, String> {
+        self.note();
+        self.signals
+            .stop_tree(process, descendants, config, critical, memory_recovered)
+    }
+
+    fn stop_unit(&mut self, unit: &SystemdUnit) -> Result<(), String> {
+        self.signals.stop_unit(unit)
+    }
+
+    fn freeze(&mut self, target: &FrozenTarget, frozen: bool) -> Result<(), String> {
+        self.signals.freeze(target, frozen)
+    }
+}
+
+/// Runs one daemon iteration that kills firefox on a fake desktop, with or without
+/// snapshot, journal and notifications, and returns the allocations up to the signal.
+fn count_iteration_allocations(reports: bool) -> u64 {
+    let dir = std::env::temp_dir().join(format!("oomkiller-kill-path-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let mut config = Config::default();
+    config.notify.enabled = reports;
+    config.notify.on_sigterm = reports;
+    if reports {
+        config.snapshot.enabled = true;
+        config.snapshot.dir = Some(dir.join("snapshots"));
+        config.journal.path = Some(dir.join("journal.jsonl"));
+    }
+    let mut processes = FakeProcesses::default();
+    processes
+        .add(1000, "systemd", 8 * GIB)
+        .add(2000, "firefox", 6 * GIB)
+        .add(2001, "bash", GIB);
+    let notices = Arc::new(Mutex::new(Vec::new()));
+    let mut host = Host {
+        memory: Box::new(FakeMemory::new(&[95.0])),
+        processes: Box::new(processes),
+        signals: Box::new(Probe {
+            signals: FakeSignals::default(),
+            notices: Arc::clone(&notices),
+            dir: dir.clone(),
+        }),
+    };
+    let mut state = DaemonState {
+        notifier: KillNotifier::new(Box::new(RecordingSink(Arc::clone(&notices)))),
+        ..DaemonState::default()
+    };
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    daemon_iteration(&mut host, &config, &mut state).unwrap();
+    let allocations = SIGNALLED_AT.load(Ordering::Relaxed) - before;
+
+    assert_eq!(
+        REPORTED_AT_SIGNAL.load(Ordering::Relaxed),
+        0,
+        "reported before the signal"
+    );
+    if reports {
+        assert!(!notices.lock().unwrap().is_empty(), "no notification");
+        assert!(dir.join("journal.jsonl").exists(), "no journal line");
+        let snapshots = fs::read_dir(dir.join("snapshots")).unwrap().count();
+        assert_eq!(snapshots, 1, "no snapshot");
+    }
+    fs::remove_dir_all(&dir).unwrap();
+    allocations
+}
+
+fn main() {
+    // Stdout's buffer is allocated on first use; the daemon does this at startup.
+    println!("kill path allocations:");
+    std::thread::sleep(Duration::from_millis(10));
+
+    let (outcome, allocations) = count_allocations(true);
+    println!("  SIGKILL only: {allocations} ({})", outcome.as_str());
+    assert!(matches!(outcome, KillOutcome::Killed { .. }));
+    assert_eq!(allocations, 0, "SIGKILL path allocated");
+
+    let (outcome, allocations) = count_allocations(false);
+    println!("  SIGTERM first: {allocations} ({})", outcome.as_str());
+    assert!(matches!(outcome, KillOutcome::Terminated { .. }));
+    assert_eq!(allocations, 0, "SIGTERM path allocated");
+
+    // The first run sets up what the process does once (thread locals and the like).
+    count_iteration_allocations(false);
+    let selection = count_iteration_allocations(false);
+    let reported = count_iteration_allocations(true);
+    println!("  full iteration up to the signal: {selection} (with reports: {reported})");
+    assert_eq!(reported, selection, "reports allocated before the signal");
+}